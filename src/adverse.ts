@@ -0,0 +1,72 @@
+import type { ClosedPositionRecord } from "./reports.js";
+import type { Storage } from "./storage.js";
+
+/** Fewer resolved positions than this per mode and the calibration is noise */
+const MIN_POSITIONS = 20;
+
+/** Resolved-position win rates of live and simulated runs, and the haircut that reconciles them */
+export interface AdverseSelectionFit {
+  live: { positions: number; win_rate: number };
+  sim: { positions: number; win_rate: number };
+  /** Share of each winning simulated fill to treat as unfilled; null without enough of both */
+  haircut: number | null;
+}
+
+/**
+ * Win rates of period-start positions held to resolution, split into live and simulated runs by the
+ * session they closed in (`sessions.jsonl`). Live limit buys fill disproportionately when the price is
+ * falling through them, i.e. on the losing side, so live wins less often than simulation at the same
+ * price. The haircut `h` is what makes simulation match: dropping `h` of every winning fill turns a
+ * sim win rate `s` into the live rate `l`, so `h = 1 - l(1 - s) / (s(1 - l))`.
+ */
+export function calibrateAdverseSelection(storage: Storage): AdverseSelectionFit {
+  const starts = storage
+    .readJsonl<{ event: string; ts: number; mode: string }>("sessions.jsonl")
+    .filter((s) => s.event === "start")
+    .sort((a, b) => a.ts - b.ts);
+  const modeAt = (ts: number): string | null => {
+    let mode: string | null = null;
+    for (const s of starts) {
+      if (s.ts > ts) break;
+      mode = s.mode;
+    }
+    return mode;
+  };
+  const tally = { live: { positions: 0, wins: 0 }, sim: { positions: 0, wins: 0 } };
+  for (const p of storage.readJsonl<ClosedPositionRecord>("positions.jsonl")) {
+    if (p.reason !== "period_start_limit") continue;
+    const resolution = p.legs.find((l) => l.reason === "resolution");
+    if (!resolution || p.legs.some((l) => l.reason !== "resolution" && l.reason !== "adverse_selection")) continue;
+    const mode = modeAt(p.ts);
+    const side = mode === "production" ? tally.live : mode === "simulation" || mode === "replay" ? tally.sim : null;
+    if (!side) continue;
+    side.positions++;
+    if (resolution.price >= 1) side.wins++;
+  }
+  const rate = (t: { positions: number; wins: number }) => (t.positions > 0 ? t.wins / t.positions : 0);
+  const l = rate(tally.live);
+  const s = rate(tally.sim);
+  let haircut: number | null = null;
+  if (tally.live.positions >= MIN_POSITIONS && tally.sim.positions >= MIN_POSITIONS && s > 0 && l < 1) {
+    haircut = Math.min(0.95, Math.max(0, 1 - (l * (1 - s)) / (s * (1 - l))));
+  }
+  return {
+    live: { positions: tally.live.positions, win_rate: l },
+    sim: { positions: tally.sim.positions, win_rate: s },
+    haircut,
+  };
+}
+
+/** The calibration behind `sim_adverse_selection: "auto"` */
+export function adverseSelectionReport(storage: Storage): string {
+  const fit = calibrateAdverseSelection(storage);
+  const pct = (v: number) => `${(v * 100).toFixed(1)}%`;
+  const lines = [
+    "Adverse selection: win rate of period-start positions held to resolution",
+    `   live        ${String(fit.live.positions).padStart(5)} position(s)  win rate ${pct(fit.live.win_rate)}`,
+    `   simulation  ${String(fit.sim.positions).padStart(5)} position(s)  win rate ${pct(fit.sim.win_rate)}`,
+  ];
+  if (fit.haircut == null) lines.push(`   (need ${MIN_POSITIONS}+ resolved positions from both live and simulated runs to calibrate)`);
+  else lines.push(`   Haircut ${pct(fit.haircut)} of each winning simulated fill (sim_adverse_selection: "auto" applies it)`);
+  return lines.join("\n");
+}
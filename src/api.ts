@@ -1,28 +1,68 @@
-import axios, { AxiosInstance } from "axios";
-import { Market, Token } from "./types.js";
+import axios from "axios";
+import { Market, Token, upDownTokenIds } from "./types.js";
 import type { Config } from "./config.js";
+import { EndpointPool } from "./endpoints.js";
+import type { TradePrint } from "./trades.js";
 
 const POLYGON_CHAIN_ID = 137;
 
+/** Gamma list fields arrive as arrays, JSON-encoded arrays or comma-separated strings */
+export function parseStringList(value: unknown): string[] {
+  if (Array.isArray(value)) return value.map((v) => String(v).trim());
+  if (typeof value !== "string" || value.trim() === "") return [];
+  try {
+    const parsed: unknown = JSON.parse(value);
+    if (Array.isArray(parsed)) return parsed.map((v) => String(v).trim());
+  } catch {
+    /* not JSON */
+  }
+  return value.split(",").map((v) => v.trim().replace(/^["']|["']$/g, ""));
+}
+
+/**
+ * Outcome tokens of a Gamma market: its `tokens` array when present (ids under tokenId, token_id or id),
+ * else `outcomes` zipped with `clobTokenIds`. Empty when neither yields a labelled id per outcome.
+ */
+export function gammaTokens(raw: Record<string, unknown>): Token[] {
+  if (Array.isArray(raw.tokens) && raw.tokens.length > 0) {
+    return (raw.tokens as Array<Record<string, unknown>>)
+      .map((t) => ({ token_id: String(t.tokenId ?? t.token_id ?? t.id ?? ""), outcome: String(t.outcome ?? "").trim() }))
+      .filter((t) => t.token_id !== "" && t.outcome !== "");
+  }
+  const outcomes = parseStringList(raw.outcomes);
+  const ids = parseStringList(raw.clobTokenIds ?? raw.clob_token_ids);
+  if (outcomes.length === 0 || outcomes.length !== ids.length) return [];
+  return outcomes.map((outcome, i) => ({ token_id: ids[i], outcome }));
+}
+
 export class PolymarketApi {
-  private gammaUrl: string;
-  private clobUrl: string;
+  private gamma: EndpointPool;
+  private clob: EndpointPool;
+  private dataUrl: string;
   private config: Config["polymarket"];
-  private gammaClient: AxiosInstance;
 
   constructor(cfg: Config["polymarket"]) {
-    this.gammaUrl = cfg.gamma_api_url.replace(/\/$/, "");
-    this.clobUrl = cfg.clob_api_url.replace(/\/$/, "");
+    const failAfter = cfg.failover_after_errors ?? 3;
+    const probeMs = (cfg.failover_probe_seconds ?? 60) * 1000;
+    this.gamma = new EndpointPool("Gamma API", [cfg.gamma_api_url, ...(cfg.gamma_api_fallback_urls ?? [])], failAfter, "/", probeMs);
+    this.clob = new EndpointPool("CLOB API", [cfg.clob_api_url, ...(cfg.clob_api_fallback_urls ?? [])], failAfter, "/", probeMs);
+    this.dataUrl = (cfg.data_api_url ?? "https://data-api.polymarket.com").replace(/\/$/, "");
     this.config = cfg;
-    this.gammaClient = axios.create({
-      baseURL: this.gammaUrl,
-      timeout: 15_000,
-      headers: { "Content-Type": "application/json" },
-    });
   }
 
+  /** Called with (api name, old URL, new URL) whenever Gamma or CLOB fails over or back */
+  onFailover(cb: (name: string, from: string, to: string) => void): void {
+    this.gamma.onFailover(cb);
+    this.clob.onFailover(cb);
+  }
+
+  /** Current CLOB base URL (changes on failover) */
   getClobUrl(): string {
-    return this.clobUrl;
+    return this.clob.current();
+  }
+
+  getGammaUrl(): string {
+    return this.gamma.current();
   }
 
   getPrivateKey(): string | null {
@@ -33,17 +73,62 @@ export class PolymarketApi {
     return this.config.proxy_wallet_address;
   }
 
+  getSignatureType(): number | null {
+    return this.config.signature_type;
+  }
+
   /** Gamma: get event by slug; returns first market from event.markets (tokens may be empty; use getMarketByConditionId for tokens) */
   async getMarketBySlug(slug: string): Promise<Market> {
-    const { data } = await this.gammaClient.get<{ markets?: unknown[] }>(
-      `/events/slug/${encodeURIComponent(slug)}`
+    const { data } = await this.gamma.request((base) =>
+      axios.get<{ markets?: unknown[] }>(`${base}/events/slug/${encodeURIComponent(slug)}`, {
+        timeout: 15_000,
+        headers: { "Content-Type": "application/json" },
+      })
     );
     const markets = data?.markets;
     if (!Array.isArray(markets) || markets.length === 0) {
       throw new Error(`Invalid market response: no markets for slug ${slug}`);
     }
-    const raw = markets[0] as Record<string, unknown>;
+    return this.toMarket(markets[0] as Record<string, unknown>);
+  }
+
+  /**
+   * Gamma: open events ending within `windowSeconds` of `endTime` (unix seconds), each with its first market,
+   * so a period's markets can be found by time and series instead of by slug.
+   */
+  async getEventsEndingAt(
+    endTime: number,
+    windowSeconds: number = 60
+  ): Promise<Array<{ title: string; slug: string; series: string[]; market: Record<string, unknown> }>> {
+    const { data } = await this.gamma.request((base) =>
+      axios.get<Array<Record<string, unknown>>>(`${base}/events`, {
+        params: {
+          closed: false,
+          end_date_min: new Date((endTime - windowSeconds) * 1000).toISOString(),
+          end_date_max: new Date((endTime + windowSeconds) * 1000).toISOString(),
+          limit: 200,
+        },
+        timeout: 15_000,
+      })
+    );
+    return (Array.isArray(data) ? data : [])
+      .filter((e) => Array.isArray(e.markets) && e.markets.length > 0)
+      .map((e) => ({
+        title: String(e.title ?? ""),
+        slug: String(e.slug ?? ""),
+        series: [
+          ...(Array.isArray(e.series) ? (e.series as Array<{ slug?: string }>).map((s) => String(s.slug ?? "")) : []),
+          ...(e.seriesSlug != null ? [String(e.seriesSlug)] : []),
+        ].filter((s) => s !== ""),
+        market: (e.markets as Array<Record<string, unknown>>)[0],
+      }));
+  }
+
+  /** Market from a raw Gamma market object, with tokens from the CLOB when Gamma doesn't label both sides */
+  async toMarket(raw: Record<string, unknown>): Promise<Market> {
     const conditionId = String(raw.conditionId ?? raw.condition_id ?? "");
+    const tokens = gammaTokens(raw);
+    const clobTokenIds = parseStringList(raw.clobTokenIds ?? raw.clob_token_ids);
     const base: Market = {
       conditionId,
       question: String(raw.question ?? ""),
@@ -54,40 +139,65 @@ export class PolymarketApi {
       resolutionSource: raw.resolutionSource != null ? String(raw.resolutionSource) : undefined,
       endDateISO: raw.endDateISO != null ? String(raw.endDateISO) : undefined,
       endDateIso: raw.endDateIso != null ? String(raw.endDateIso) : undefined,
-      tokens: Array.isArray(raw.tokens) ? (raw.tokens as Market["tokens"]) : undefined,
-      clobTokenIds: raw.clobTokenIds != null ? String(raw.clobTokenIds) : undefined,
-      outcomes: raw.outcomes != null ? String(raw.outcomes) : undefined,
+      endDate: raw.endDate != null ? String(raw.endDate) : undefined,
+      tokens: tokens.length > 0 ? tokens : undefined,
+      // Normalized to a JSON array string whatever shape Gamma sent
+      clobTokenIds: clobTokenIds.length > 0 ? JSON.stringify(clobTokenIds) : undefined,
+      outcomes: raw.outcomes != null ? JSON.stringify(parseStringList(raw.outcomes)) : undefined,
     };
-    if (Array.isArray(base.tokens) && base.tokens.length > 0) {
-      return base;
-    }
+    const { upId, downId } = upDownTokenIds(base);
+    if (upId && downId) return base;
+    // Gamma's payload didn't label both sides; the CLOB market lists its tokens with outcomes.
     const clobMarket = await this.getMarketByConditionId(conditionId);
     return { ...base, tokens: clobMarket.tokens as Market["tokens"] };
   }
 
-  /** CLOB: get market by condition ID (includes tokens with token_id and outcome) */
-  async getMarketByConditionId(conditionId: string): Promise<{ tokens: Token[] }> {
-    const { data } = await axios.get<{ tokens?: Array<{ token_id?: string; outcome?: string }> }>(
-      `${this.clobUrl}/markets/${conditionId}`,
-      { timeout: 10_000 }
+  /** CLOB: get market by condition ID (tokens with token_id, outcome and, once resolved, winner; whether it takes orders) */
+  async getMarketByConditionId(conditionId: string): Promise<{ tokens: Token[]; accepting_orders?: boolean }> {
+    const { data } = await this.clob.request((base) =>
+      axios.get<{
+        tokens?: Array<{ token_id?: string; outcome?: string; winner?: boolean }>;
+        accepting_orders?: boolean;
+      }>(`${base}/markets/${conditionId}`, {
+        timeout: 10_000,
+      })
     );
     const tokens: Token[] = (data?.tokens ?? []).map((t) => ({
       token_id: String(t.token_id ?? ""),
       outcome: String(t.outcome ?? ""),
+      winner: t.winner,
     }));
-    return { tokens };
+    return { tokens, accepting_orders: data?.accepting_orders };
+  }
+
+  /**
+   * Gamma: the market's UMA oracle resolution status (`proposed`, `disputed`, `resolved`, ...) and its
+   * history, oldest first; null status while nothing was proposed.
+   */
+  async getUmaResolution(conditionId: string): Promise<{ status: string | null; history: string[] }> {
+    const { data } = await this.gamma.request((base) =>
+      axios.get<Array<Record<string, unknown>>>(`${base}/markets`, {
+        params: { condition_ids: conditionId },
+        timeout: 10_000,
+      })
+    );
+    const raw = Array.isArray(data) ? data[0] : undefined;
+    const status = raw?.umaResolutionStatus != null && raw.umaResolutionStatus !== "" ? String(raw.umaResolutionStatus).toLowerCase() : null;
+    return { status, history: parseStringList(raw?.umaResolutionStatuses).map((s) => s.toLowerCase()) };
   }
 
   /** CLOB: get order book for a token. Returns bids/asks (price as string). Best bid = highest, best ask = lowest. */
   async getOrderBook(tokenId: string): Promise<{ bids: Array<{ price: string; size: string }>; asks: Array<{ price: string; size: string }> }> {
-    const { data } = await axios.get<{
-      bids?: Array<{ price: string; size: string }>;
-      asks?: Array<{ price: string; size: string }>;
-      error?: string;
-    }>(`${this.clobUrl}/book`, {
-      params: { token_id: tokenId },
-      timeout: 10_000,
-    });
+    const { data } = await this.clob.request((base) =>
+      axios.get<{
+        bids?: Array<{ price: string; size: string }>;
+        asks?: Array<{ price: string; size: string }>;
+        error?: string;
+      }>(`${base}/book`, {
+        params: { token_id: tokenId },
+        timeout: 10_000,
+      })
+    );
     if (data?.error) {
       return { bids: [], asks: [] };
     }
@@ -95,6 +205,44 @@ export class PolymarketApi {
     const asks = Array.isArray(data?.asks) ? data.asks : [];
     return { bids, asks };
   }
+
+  /** CLOB: price history of a token between two unix times, one point per `fidelityMinutes` */
+  async getPriceHistory(tokenId: string, startTs: number, endTs: number, fidelityMinutes: number = 1): Promise<Array<{ t: number; p: number }>> {
+    const { data } = await this.clob.request((base) =>
+      axios.get<{ history?: Array<{ t?: number; p?: number | string }> }>(`${base}/prices-history`, {
+        params: { market: tokenId, startTs, endTs, fidelity: fidelityMinutes },
+        timeout: 15_000,
+      })
+    );
+    return (Array.isArray(data?.history) ? data.history : [])
+      .map((h) => ({ t: Number(h.t ?? 0), p: Number(h.p ?? NaN) }))
+      .filter((h) => h.t > 0 && Number.isFinite(h.p));
+  }
+
+  /** Data API: most recent trade prints for a market (condition ID); `offset` pages further back */
+  async getTrades(conditionId: string, limit: number = 100, offset: number = 0): Promise<TradePrint[]> {
+    const { data } = await axios.get<
+      Array<{
+        asset?: string;
+        side?: string;
+        price?: number | string;
+        size?: number | string;
+        timestamp?: number;
+        transactionHash?: string;
+      }>
+    >(`${this.dataUrl}/trades`, {
+      params: { market: conditionId, limit, offset },
+      timeout: 10_000,
+    });
+    return (Array.isArray(data) ? data : []).map((t): TradePrint => ({
+      id: t.transactionHash,
+      token_id: String(t.asset ?? ""),
+      side: t.side === "SELL" ? "SELL" : "BUY",
+      price: Number(t.price ?? 0),
+      size: Number(t.size ?? 0),
+      timestamp: Number(t.timestamp ?? 0),
+    }));
+  }
 }
 
 // Re-export for clob client usage
@@ -0,0 +1,230 @@
+import { PolymarketApi } from "./api.js";
+import { DEFAULT_CONFIG } from "./config.js";
+import { TradeEventBus } from "./events.js";
+import type { TradeEvent } from "./events.js";
+import { configureLogging } from "./logging.js";
+import { buildSnapshot, currentPeriodTimestamp, formatPrices, QuoteFilter } from "./monitor.js";
+import type { Storage } from "./storage.js";
+import { Trader } from "./trader.js";
+import type { PendingTrade } from "./trader.js";
+import type { Market, MarketSnapshot, TokenPrice, TokenType } from "./types.js";
+import { tokenPriceForType } from "./types.js";
+
+/** Benchmark history, relative to `data_dir` */
+const BENCH_FILE = "bench.jsonl";
+/** Slowdown against the previous run that counts as a regression */
+const REGRESSION_PCT = 25;
+const FILL_CHECK_ORDERS = 5000;
+/** One resting order in this many crosses on every check, so fills and their events are part of the path */
+const FILL_CHECK_CROSSING_EVERY = 100;
+const TOKEN_TYPES: TokenType[] = ["BtcUp", "BtcDown", "EthUp", "EthDown", "SolanaUp", "SolanaDown", "XrpUp", "XrpDown"];
+
+interface BenchResult {
+  name: string;
+  ns_per_op: number;
+}
+
+interface BenchRun {
+  ts_ms: number;
+  node: string;
+  results: BenchResult[];
+}
+
+/** Results land here so the engine can't drop a benchmarked call as dead code */
+let blackhole: unknown = null;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Time `fn` over `iterations` after a warm-up pass; best of three rounds, so GC pauses don't count as regressions */
+function measure(name: string, iterations: number, fn: (i: number) => unknown): BenchResult {
+  for (let i = 0; i < Math.min(iterations, 1000); i++) blackhole = fn(i);
+  let best = Infinity;
+  for (let round = 0; round < 3; round++) {
+    const start = process.hrtime.bigint();
+    for (let i = 0; i < iterations; i++) blackhole = fn(i);
+    best = Math.min(best, Number(process.hrtime.bigint() - start) / iterations);
+  }
+  return { name, ns_per_op: best };
+}
+
+/** `measure` for an async path: each call is awaited before the next */
+async function measureAsync(name: string, iterations: number, fn: (i: number) => Promise<unknown>): Promise<BenchResult> {
+  for (let i = 0; i < Math.min(iterations, 100); i++) blackhole = await fn(i);
+  let best = Infinity;
+  for (let round = 0; round < 3; round++) {
+    const start = process.hrtime.bigint();
+    for (let i = 0; i < iterations; i++) blackhole = await fn(i);
+    best = Math.min(best, Number(process.hrtime.bigint() - start) / iterations);
+  }
+  return { name, ns_per_op: best };
+}
+
+/**
+ * A simulated trader holding `count` resting position buys on the snapshot's tokens, and a reset that
+ * re-arms the ones that fill. Default config, so runs compare however the bot itself is configured.
+ */
+function seededTrader(snapshot: MarketSnapshot, count: number): { trader: Trader; reset: () => void } {
+  const trader = new Trader(new PolymarketApi(DEFAULT_CONFIG.polymarket), { ...DEFAULT_CONFIG.trading }, true);
+  const events = new TradeEventBus();
+  events.subscribe((e) => (blackhole = e));
+  trader.setEventBus(events);
+  const positions: PendingTrade[] = [];
+  const byToken: Record<string, string[]> = {};
+  for (let i = 0; i < count; i++) {
+    const tokenType = TOKEN_TYPES[i % TOKEN_TYPES.length];
+    const quote = tokenPriceForType(snapshot, tokenType);
+    const tokenId = quote?.token_id ?? tokenType;
+    // Most limits rest below the ask; every FILL_CHECK_CROSSING_EVERY-th sits on it and fills.
+    const limit = i % FILL_CHECK_CROSSING_EVERY === 0 ? (quote?.ask ?? 0.5) : 0.3 + (i % 10) / 100;
+    const positionId = `pos-${i + 1}`;
+    (byToken[`${snapshot.period_timestamp}_${tokenId}`] ??= []).push(positionId);
+    positions.push({
+      position_id: positionId,
+      token_id: tokenId,
+      condition_id: "0xbench",
+      token_type: tokenType,
+      market_timestamp: snapshot.period_timestamp,
+      units: 10,
+      purchase_price: limit,
+      investment_amount: 10 * limit,
+      remaining_units: 10,
+      sell_legs: [],
+      take_profits_hit: 0,
+      entry_price: limit,
+      dca_adds: 0,
+      reason: "period_start_limit",
+      note: null,
+      oco: null,
+      oco_armed: false,
+      order_id: `sim-${i + 1}`,
+      order_ids: [],
+      pending_adds: [],
+      target_price: limit,
+      filled: false,
+      fill_elapsed_seconds: null,
+      last_mark: null,
+      sold: false,
+      hedged: false,
+      cancelled: false,
+    });
+  }
+  trader.restore({
+    simulation: true,
+    positions,
+    positions_by_token: byToken,
+    position_seq: count,
+    strategy_orders: [],
+    strategy_inventory_cost: 0,
+    sim_inventory: {},
+    sim_order_seq: count,
+    stop_outs: {},
+    rejected_tokens: [],
+    consecutive_rejections: 0,
+    liquidity: { maker: { fills: 0, notional: 0 }, taker: { fills: 0, notional: 0 } },
+  });
+  const crossing = positions.filter((_, i) => i % FILL_CHECK_CROSSING_EVERY === 0);
+  const reset = () => {
+    for (const t of crossing) {
+      t.filled = false;
+      t.order_ids = [];
+      t.fill_elapsed_seconds = null;
+    }
+  };
+  return { trader, reset };
+}
+
+function market(asset: string): Market {
+  return { conditionId: `0x${asset}`, question: asset, slug: asset, active: true, closed: false };
+}
+
+function price(tokenId: string, i: number): TokenPrice {
+  const bid = 0.4 + (i % 10) / 100;
+  return { token_id: tokenId, bid, ask: bid + 0.01, book_imbalance: 0.1 };
+}
+
+/**
+ * `--bench [iterations]`: time the monitoring loop's hot paths (building, filtering and formatting a
+ * snapshot of all eight tokens; `Trader.checkPositions` in simulation over thousands of resting orders;
+ * trade event serialization), append the run to `bench.jsonl` and compare with the previous run. Sets a
+ * non-zero exit code when a path got more than 25% slower, so it can gate CI.
+ */
+export async function runBenchmarks(storage: Storage, iterations: number = 20_000): Promise<boolean> {
+  const markets = ["btc", "eth", "sol", "xrp"].map(market);
+  const filter = new QuoteFilter(20);
+  const tokens = ["btc", "eth", "sol", "xrp"].flatMap((a) => [`${a}-up`, `${a}-down`]);
+  const snapshot = (i: number) => {
+    const p = tokens.map((t) => filter.apply(price(t, i)));
+    return buildSnapshot(
+      1_700_000_000,
+      900,
+      markets[1],
+      markets[0],
+      markets[2],
+      markets[3],
+      { up: p[2], down: p[3] },
+      { up: p[0], down: p[1] },
+      { up: p[4], down: p[5] },
+      { up: p[6], down: p[7] }
+    );
+  };
+  // The fill-check snapshot is on the current period, so the seeded positions are live rather than expired.
+  const period = currentPeriodTimestamp();
+  const quotes = tokens.map((t) => price(t, 0));
+  const fillSnapshot = buildSnapshot(
+    period,
+    900,
+    markets[1],
+    markets[0],
+    markets[2],
+    markets[3],
+    { up: quotes[2], down: quotes[3] },
+    { up: quotes[0], down: quotes[1] },
+    { up: quotes[4], down: quotes[5] },
+    { up: quotes[6], down: quotes[7] }
+  );
+  const { trader, reset } = seededTrader(fillSnapshot, FILL_CHECK_ORDERS);
+  // The gates are still checked per order; the fill lines themselves would only flood the terminal.
+  configureLogging({ classes: { fills: "off" } });
+  const event: TradeEvent = {
+    ts_ms: 1_700_000_000_000,
+    kind: "filled",
+    period_timestamp: 1_700_000_000,
+    token_type: "BtcUp",
+    order_id: "0xabc",
+    position_id: "pos-1",
+    units: 10,
+    price: 0.45,
+    reason: "period_start_limit",
+    liquidity: "maker",
+  };
+
+  const results = [
+    measure("snapshot_build", iterations, snapshot),
+    measure("snapshot_format", iterations, (i) => formatPrices(snapshot(i))),
+    await measureAsync(`check_positions_${FILL_CHECK_ORDERS}_orders`, Math.max(1, Math.floor(iterations / 100)), async () => {
+      await trader.checkPositions(fillSnapshot);
+      reset();
+    }),
+    measure("event_serialize", iterations, (i) => JSON.stringify({ ...event, ts_ms: event.ts_ms + i })),
+  ];
+
+  const previous = storage.readJsonl<BenchRun>(BENCH_FILE).at(-1);
+  storage.appendJsonl(BENCH_FILE, { ts_ms: Date.now(), node: process.version, results } satisfies BenchRun);
+  let regressed = false;
+  log(`⏱️ Benchmarks (${iterations} iterations, node ${process.version})`);
+  for (const r of results) {
+    const before = previous?.results.find((p) => p.name === r.name);
+    let change = "";
+    if (before) {
+      const pct = ((r.ns_per_op - before.ns_per_op) / before.ns_per_op) * 100;
+      const slower = pct > REGRESSION_PCT;
+      regressed ||= slower;
+      change = `  ${pct >= 0 ? "+" : ""}${pct.toFixed(1)}% vs previous${slower ? " ⚠️ regression" : ""}`;
+    }
+    log(`   ${r.name.padEnd(24)} ${r.ns_per_op.toFixed(0).padStart(10)} ns/op${change}`);
+  }
+  if (!previous) log(`   (first run - later runs compare against ${storage.path(BENCH_FILE)})`);
+  return !regressed;
+}
@@ -0,0 +1,84 @@
+import type { IncomingMessage, ServerResponse } from "http";
+import type { TradeEvent, TradeEventBus } from "./events.js";
+import { displayTimeZone } from "./money.js";
+
+const PAGE = `<!doctype html>
+<html><head><meta charset="utf-8"><title>Blotter</title>
+<style>
+body { font: 13px monospace; margin: 1em; }
+table { border-collapse: collapse; width: 100%; }
+td, th { padding: 2px 8px; text-align: left; border-bottom: 1px solid #ddd; }
+.pos { color: #080; } .neg { color: #c00; } .rejected { color: #c00; } .cancelled, .skipped { color: #888; }
+</style></head>
+<body><pre id="summary"></pre><table><thead><tr><th>time</th><th>period</th><th>token</th><th>event</th><th>units</th><th>price</th><th>notional</th><th>pnl</th><th>reason</th><th>detail</th></tr></thead>
+<tbody id="rows"></tbody></table>
+<script>
+const rows = document.getElementById("rows");
+const fmt = (n, d) => (n == null ? "" : n.toFixed(d));
+const timeZone = __TIME_ZONE__ ?? undefined;
+const clock = (ms, seconds) =>
+  new Date(ms).toLocaleTimeString("en-GB", { timeZone, hour: "2-digit", minute: "2-digit", second: seconds ? "2-digit" : undefined });
+const source = new EventSource("/blotter/events");
+source.addEventListener("summary", (m) => { document.getElementById("summary").textContent = JSON.parse(m.data); });
+source.onmessage = (m) => {
+  const e = JSON.parse(m.data);
+  const tr = document.createElement("tr");
+  tr.className = e.kind;
+  const pnlClass = e.pnl == null ? "" : e.pnl >= 0 ? "pos" : "neg";
+  tr.innerHTML = [clock(e.ts_ms, true), clock(e.period_timestamp * 1000, false),
+    e.token_type, e.kind, fmt(e.units, 2), fmt(e.price, 4), fmt(e.units != null && e.price != null ? e.units * e.price : null, 2),
+    fmt(e.pnl, 2), e.reason ?? "",
+    e.detail ?? e.note ?? e.order_id ?? ""]
+    .map((v, i) => "<td" + (i === 7 ? ' class="' + pnlClass + '"' : "") + ">" + String(v).replace(/</g, "&lt;") + "</td>").join("");
+  rows.insertBefore(tr, rows.firstChild);
+  while (rows.children.length > 500) rows.removeChild(rows.lastChild);
+};
+</script></body></html>`;
+
+/**
+ * Live trade blotter: keeps the most recent trade events from the bus and streams them to
+ * `GET /blotter` (page) via server-sent events on `GET /blotter/events`.
+ */
+export class Blotter {
+  private rows: TradeEvent[] = [];
+  private maxRows: number;
+  private clients: Set<ServerResponse> = new Set();
+  private lastSummary: string | null = null;
+
+  constructor(bus: TradeEventBus, maxRows: number = 200) {
+    this.maxRows = maxRows;
+    bus.subscribe((event) => {
+      this.rows.push(event);
+      if (this.rows.length > this.maxRows) this.rows.shift();
+      for (const res of this.clients) res.write(`data: ${JSON.stringify(event)}\n\n`);
+    });
+  }
+
+  /** HTTP handler for `/blotter*`; returns false for other paths */
+  handle(req: IncomingMessage, res: ServerResponse): boolean {
+    if (req.url === "/blotter") {
+      // Times render in the configured display zone; undefined (host local) becomes the browser's zone.
+      res.writeHead(200, { "Content-Type": "text/html; charset=utf-8" }).end(PAGE.replace("__TIME_ZONE__", JSON.stringify(displayTimeZone() ?? null)));
+      return true;
+    }
+    if (req.url !== "/blotter/events") return false;
+    res.writeHead(200, { "Content-Type": "text/event-stream", "Cache-Control": "no-cache", Connection: "keep-alive" });
+    for (const event of this.rows) res.write(`data: ${JSON.stringify(event)}\n\n`);
+    if (this.lastSummary) res.write(`event: summary\ndata: ${JSON.stringify(this.lastSummary)}\n\n`);
+    this.clients.add(res);
+    req.on("close", () => this.clients.delete(res));
+    return true;
+  }
+
+  /** Show `text` above the blotter table, replacing the previous summary */
+  summary(text: string): void {
+    this.lastSummary = text;
+    for (const res of this.clients) res.write(`event: summary\ndata: ${JSON.stringify(text)}\n\n`);
+  }
+
+  /** End open streams so the server can close */
+  close(): void {
+    for (const res of this.clients) res.end();
+    this.clients.clear();
+  }
+}
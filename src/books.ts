@@ -0,0 +1,143 @@
+import { existsSync, readdirSync } from "fs";
+import type { Storage } from "./storage.js";
+
+const PERIOD_DURATION = 900;
+const BUCKET_SECONDS = 5;
+const SHADES = " .:-=+*#%@";
+
+/** Full-book recording around period boundaries (`book_heatmap`) */
+export interface BookHeatmapConfig {
+  /** Seconds after the open and before the close to record (default 30) */
+  seconds?: number | null;
+  /** Price levels kept per side (default 20) */
+  levels?: number | null;
+}
+
+export type BookPhase = "open" | "close";
+
+/** One token's book at one tick, as written to `books/<period>.jsonl` */
+interface BookRow {
+  ts_ms: number;
+  phase: BookPhase;
+  elapsed_seconds: number;
+  market: string;
+  side: "up" | "down";
+  token_id: string;
+  /** [price, size], best first */
+  bids: Array<[number, number]>;
+  asks: Array<[number, number]>;
+}
+
+function levelsOf(entries: Array<{ price: string; size: string }>, levels: number, bestFirst: (a: number, b: number) => number): Array<[number, number]> {
+  return entries
+    .map((e) => [parseFloat(e.price), parseFloat(e.size)] as [number, number])
+    .filter(([p, s]) => Number.isFinite(p) && Number.isFinite(s))
+    .sort((a, b) => bestFirst(a[0], b[0]))
+    .slice(0, levels);
+}
+
+/**
+ * Writes the books the monitor already fetches to `books/<period>.jsonl` during the first and last
+ * `seconds` of each period. Pair with `phase_intervals` for dense sampling around the boundaries.
+ */
+export class BookRecorder {
+  private storage: Storage;
+  private seconds: number;
+  private levels: number;
+
+  constructor(storage: Storage, cfg: BookHeatmapConfig) {
+    this.storage = storage;
+    this.seconds = cfg.seconds ?? 30;
+    this.levels = cfg.levels ?? 20;
+  }
+
+  /** Boundary window `nowSec` falls in, or null in the middle of the period */
+  phase(period: number, nowSec: number): BookPhase | null {
+    const elapsed = nowSec - period;
+    if (elapsed >= 0 && elapsed < this.seconds) return "open";
+    if (elapsed >= PERIOD_DURATION - this.seconds && elapsed < PERIOD_DURATION) return "close";
+    return null;
+  }
+
+  record(
+    period: number,
+    phase: BookPhase,
+    nowMs: number,
+    market: string,
+    side: "up" | "down",
+    tokenId: string,
+    book: { bids: Array<{ price: string; size: string }>; asks: Array<{ price: string; size: string }> }
+  ): void {
+    const row: BookRow = {
+      ts_ms: nowMs,
+      phase,
+      elapsed_seconds: Math.floor(nowMs / 1000) - period,
+      market,
+      side,
+      token_id: tokenId,
+      bids: levelsOf(book.bids ?? [], this.levels, (a, b) => b - a),
+      asks: levelsOf(book.asks ?? [], this.levels, (a, b) => a - b),
+    };
+    this.storage.appendJsonl(`books/${period}.jsonl`, row);
+  }
+}
+
+/**
+ * Where bid liquidity sits in the first seconds of a period (average size per cent and 5-second bucket,
+ * over all recorded tokens), plus a placement guide: per limit price, the bid size queued at or above
+ * it and how often the ask traded down to it within the window.
+ */
+export function bookHeatmapReport(storage: Storage): string {
+  const dir = storage.path("books");
+  const files = existsSync(dir) ? readdirSync(dir).filter((f) => /^\d+\.jsonl$/.test(f)) : [];
+  const heat = new Map<number, number[]>();
+  const bestAsk = new Map<string, number>();
+  const snapshotsPerBucket: number[] = [];
+  let buckets = 0;
+  for (const f of files) {
+    for (const row of storage.readJsonl<BookRow>(`books/${f}`)) {
+      if (row.phase !== "open") continue;
+      const b = Math.floor(row.elapsed_seconds / BUCKET_SECONDS);
+      buckets = Math.max(buckets, b + 1);
+      snapshotsPerBucket[b] = (snapshotsPerBucket[b] ?? 0) + 1;
+      for (const [price, size] of row.bids) {
+        const cents = Math.round(price * 100);
+        const cells = heat.get(cents) ?? [];
+        cells[b] = (cells[b] ?? 0) + size;
+        heat.set(cents, cells);
+      }
+      const ask = row.asks[0]?.[0];
+      const key = `${f}_${row.token_id}`;
+      if (ask != null) bestAsk.set(key, Math.min(bestAsk.get(key) ?? Infinity, ask));
+    }
+  }
+  if (heat.size === 0) return "Book heatmap at the open\n   (no books recorded - enable book_heatmap)";
+
+  const avg = (cells: number[], b: number) => (cells[b] ?? 0) / (snapshotsPerBucket[b] || 1);
+  const rows = [...heat].map(([cents, cells]) => ({ cents, cells, total: cells.reduce((s, v) => s + (v ?? 0), 0) }));
+  const peak = Math.max(...rows.flatMap((r) => Array.from({ length: buckets }, (_, b) => avg(r.cells, b))));
+  const top = rows.sort((a, b) => b.total - a.total).slice(0, 25).sort((a, b) => b.cents - a.cents);
+  const header = Array.from({ length: buckets }, (_, b) => String(b * BUCKET_SECONDS).padStart(3)).join("");
+  const lines = [
+    `Book heatmap at the open: average bid size per price and ${BUCKET_SECONDS}s bucket (${files.length} period(s))`,
+    `   price  seconds into period ->${header}   avg size`,
+  ];
+  for (const r of top) {
+    const cells = Array.from({ length: buckets }, (_, b) => {
+      const shade = SHADES[Math.min(SHADES.length - 1, Math.round((avg(r.cells, b) / peak) * (SHADES.length - 1)))];
+      return shade.repeat(3);
+    }).join("");
+    const mean = Array.from({ length: buckets }, (_, b) => avg(r.cells, b)).reduce((s, v) => s + v, 0) / buckets;
+    lines.push(`   $${(r.cents / 100).toFixed(2)}                         ${cells}   ${mean.toFixed(0).padStart(8)}`);
+  }
+
+  lines.push("", "   Placement guide (open window)", "   limit   bid size at/above   ask reached limit");
+  const asks = [...bestAsk.values()];
+  const first = snapshotsPerBucket[0] ? 0 : snapshotsPerBucket.findIndex((n) => n > 0);
+  for (let cents = 55; cents >= 35; cents -= 1) {
+    const ahead = rows.filter((r) => r.cents >= cents).reduce((s, r) => s + avg(r.cells, first), 0);
+    const reached = asks.length > 0 ? (asks.filter((a) => a <= cents / 100 + 1e-9).length / asks.length) * 100 : 0;
+    lines.push(`   $${(cents / 100).toFixed(2)}   ${ahead.toFixed(0).padStart(17)}   ${reached.toFixed(0).padStart(16)}%`);
+  }
+  return lines.join("\n");
+}
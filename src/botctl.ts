@@ -0,0 +1,54 @@
+#!/usr/bin/env node
+/**
+ * Client for a running bot's `control_socket`:
+ *   botctl [--socket <path> | -c <config>] <command> [args...]
+ * e.g. `botctl status`, `botctl pause XRP`, `botctl report equity`. Without `--socket`, the path is
+ * read from the config file's `trading.control_socket`. Exits non-zero when the bot reports an error.
+ */
+import { existsSync } from "fs";
+import { connect } from "net";
+import { join } from "path";
+import { loadConfig } from "./config.js";
+import type { ControlReply } from "./control-socket.js";
+
+function main(): void {
+  const args = process.argv.slice(2);
+  let socket: string | null = null;
+  let configPath = "config.json";
+  while (args[0] === "--socket" || args[0] === "-c" || args[0] === "--config") {
+    const flag = args.shift();
+    const value = args.shift();
+    if (!value) throw new Error(`${flag} needs a value`);
+    if (flag === "--socket") socket = value;
+    else configPath = value;
+  }
+  // loadConfig writes a default config when there is none; a client must not.
+  if (!socket && existsSync(join(process.cwd(), configPath))) socket = loadConfig(configPath).trading.control_socket ?? null;
+  if (!socket) throw new Error("no control socket: pass --socket <path> or set trading.control_socket in the config");
+  const command = args.length > 0 ? args.join(" ") : "status";
+
+  const conn = connect(socket);
+  let buffer = "";
+  conn.setEncoding("utf-8");
+  conn.on("connect", () => conn.write(command + "\n"));
+  conn.on("data", (chunk: string) => {
+    buffer += chunk;
+    const nl = buffer.indexOf("\n");
+    if (nl < 0) return;
+    const reply = JSON.parse(buffer.slice(0, nl)) as ControlReply;
+    (reply.ok ? process.stdout : process.stderr).write(reply.output + "\n");
+    process.exitCode = reply.ok ? 0 : 1;
+    conn.end();
+  });
+  conn.on("error", (e) => {
+    process.stderr.write(`botctl: cannot reach ${socket}: ${e.message}\n`);
+    process.exitCode = 2;
+  });
+}
+
+try {
+  main();
+} catch (e) {
+  process.stderr.write(`botctl: ${e instanceof Error ? e.message : String(e)}\n`);
+  process.exitCode = 2;
+}
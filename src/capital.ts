@@ -0,0 +1,129 @@
+import { formatTime, formatUsd } from "./money.js";
+import type { TradeEvent, TradeEventBus } from "./events.js";
+import type { Storage } from "./storage.js";
+
+export const CAPITAL_FLOWS_FILE = "capital_flows.jsonl";
+/** Readings an unexplained balance change must persist for; fills can settle between two readings */
+const CONFIRM_READINGS = 2;
+
+/** A deposit (positive) or withdrawal (negative) as written to `capital_flows.jsonl` */
+export interface CapitalFlow {
+  ts: number;
+  kind: "deposit" | "withdrawal";
+  amount: number;
+  balance: number;
+}
+
+/** Balance, flows and equity at one position snapshot (live only) */
+export interface CapitalMark {
+  balance: number;
+  /** Deposits minus withdrawals seen this session */
+  net_flows: number;
+  /** Winnings of resolved positions not yet seen in the balance (unredeemed) */
+  owed_winnings: number;
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Tells deposits and withdrawals apart from trading. Cash the bot moved is summed from trade events
+ * (fills, exits, hedges); winnings count once they show up in the balance (redeemed by the bot or by
+ * hand). A balance change none of that explains, of at least `minAmount` and still there a reading later,
+ * is recorded as a capital flow so equity and returns can leave it out.
+ */
+export class CapitalFlowTracker {
+  private storage: Storage;
+  private minAmount: number;
+  private traded = 0;
+  private winnings = 0;
+  private redeemed = 0;
+  private last: { balance: number; traded: number } | null = null;
+  private unexplained = 0;
+  private streak = 0;
+  private netFlows = 0;
+
+  constructor(storage: Storage, events: TradeEventBus, minAmount: number) {
+    this.storage = storage;
+    this.minAmount = minAmount;
+    events.subscribe((event) => this.onEvent(event));
+  }
+
+  private onEvent(event: TradeEvent): void {
+    const notional = (event.units ?? 0) * (event.price ?? 0);
+    if (event.kind === "filled") this.traded += event.detail === "SELL" ? notional : -notional;
+    else if (event.kind === "exit") {
+      if (event.detail === "resolution") this.winnings += notional;
+      else this.traded += event.detail === "hedge" ? -notional : notional;
+    }
+  }
+
+  /** Compare a balance reading with the previous one; returns the flow it confirmed, if any */
+  sample(balance: number): CapitalFlow | null {
+    const last = this.last;
+    this.last = { balance, traded: this.traded };
+    if (!last) return null;
+    let residual = balance - last.balance - (this.traded - last.traded);
+    const owed = this.winnings - this.redeemed;
+    if (residual > 0 && owed > 0) {
+      const paid = Math.min(residual, owed);
+      this.redeemed += paid;
+      residual -= paid;
+    }
+    this.unexplained += residual;
+    if (Math.abs(this.unexplained) < this.minAmount) {
+      this.unexplained = 0;
+      this.streak = 0;
+      return null;
+    }
+    if (++this.streak < CONFIRM_READINGS) return null;
+    const flow: CapitalFlow = {
+      ts: Math.floor(Date.now() / 1000),
+      kind: this.unexplained > 0 ? "deposit" : "withdrawal",
+      amount: this.unexplained,
+      balance,
+    };
+    this.netFlows += flow.amount;
+    this.unexplained = 0;
+    this.streak = 0;
+    this.storage.appendJsonl(CAPITAL_FLOWS_FILE, flow);
+    log(`🏦 ${flow.kind === "deposit" ? "Deposit" : "Withdrawal"} of ${formatUsd(Math.abs(flow.amount))} detected (balance ${formatUsd(balance)})`);
+    return flow;
+  }
+
+  mark(balance: number): CapitalMark {
+    return { balance, net_flows: this.netFlows, owed_winnings: Math.max(0, this.winnings - this.redeemed) };
+  }
+}
+
+/**
+ * Equity curve from the live position snapshots with deposits and withdrawals taken out: start and end
+ * equity, net flows, trading PnL and a flow-adjusted (Modified Dietz) return.
+ */
+export function equityReport(storage: Storage): string {
+  const points = storage
+    .readJsonl<{ ts: number; equity?: number | null }>("position_snapshots.jsonl")
+    .filter((p): p is { ts: number; equity: number } => typeof p.equity === "number");
+  const lines = ["Equity (live, balance + positions at the bid + unredeemed winnings)"];
+  if (points.length < 2) {
+    lines.push("   (not enough live equity points yet - they are written with each position snapshot)");
+    return lines.join("\n");
+  }
+  const start = points[0];
+  const end = points[points.length - 1];
+  const flows = storage.readJsonl<CapitalFlow>(CAPITAL_FLOWS_FILE).filter((f) => f.ts > start.ts && f.ts <= end.ts);
+  const net = flows.reduce((s, f) => s + f.amount, 0);
+  const pnl = end.equity - start.equity - net;
+  const span = end.ts - start.ts;
+  // Each flow weighted by the share of the span it was invested for.
+  const base = start.equity + flows.reduce((s, f) => s + f.amount * ((end.ts - f.ts) / span), 0);
+  lines.push(
+    `   ${formatTime(start.ts * 1000)} -> ${formatTime(end.ts * 1000)}`,
+    `   Start equity ${formatUsd(start.equity)} | end equity ${formatUsd(end.equity)}`,
+    `   Deposits/withdrawals ${formatUsd(net)} over ${flows.length} flow(s)`,
+    `   Trading PnL ${formatUsd(pnl)}` + (base > 0 ? ` | return ${((pnl / base) * 100).toFixed(2)}% (flow-adjusted)` : "")
+  );
+  for (const f of flows) lines.push(`      ${formatTime(f.ts * 1000)} ${f.kind.padEnd(10)} ${formatUsd(f.amount).padStart(11)}`);
+  return lines.join("\n");
+}
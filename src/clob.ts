@@ -1,5 +1,5 @@
 import { ethers } from "ethers";
-import { ClobClient, Side, OrderType, Chain } from "clob-client-sdk";
+import { ClobClient, Side, OrderType, Chain, AssetType } from "clob-client-sdk";
 import type { Config } from "./config.js";
 
 /** Create ethers Wallet from private key hex (with or without 0x) */
@@ -24,7 +24,10 @@ export async function createClobClient(cfg: Config["polymarket"]): Promise<ClobC
     };
   }
 
-  const client = new ClobClient(host, Chain.POLYGON, wallet, apiCreds);
+  // Proxy-wallet accounts sign with the key but trade from the proxy (funder) address.
+  const signatureType = cfg.signature_type ?? undefined;
+  const funder = cfg.proxy_wallet_address ?? undefined;
+  const client = new ClobClient(host, Chain.POLYGON, wallet, apiCreds, signatureType, funder);
   if (!apiCreds) {
     // Try derive first (restores existing key). createApiKey() often returns 400 "Could not create api key" if the account already has a key.
     let creds: { key: string; secret: string; passphrase: string } | null = null;
@@ -52,7 +55,7 @@ export async function createClobClient(cfg: Config["polymarket"]): Promise<ClobC
     if (!creds) {
       throw new Error("CLOB API key derivation/creation returned no credentials. Add api_key, api_secret, api_passphrase to config.json.");
     }
-    return new ClobClient(host, Chain.POLYGON, wallet, creds);
+    return new ClobClient(host, Chain.POLYGON, wallet, creds, signatureType, funder);
   }
   return client;
 }
@@ -89,3 +92,83 @@ export async function placeLimitOrder(
     status: (result as { status?: string }).status ?? "unknown",
   };
 }
+
+/**
+ * Place a fill-and-kill limit order: whatever the book takes at `price` or better executes at once and
+ * the rest is cancelled, so nothing is left resting. Read the executed size back with `getOrder`.
+ */
+export async function placeFakOrder(
+  client: ClobClient,
+  params: PlaceLimitOrderParams
+): Promise<{ orderID: string; status: string }> {
+  const order = await buildLimitOrder(client, params);
+  const result = await client.postOrder(order as Parameters<ClobClient["postOrder"]>[0], OrderType.FAK);
+  return {
+    orderID: (result as { orderID?: string }).orderID ?? (result as { id?: string }).id ?? "",
+    status: (result as { status?: string }).status ?? "unknown",
+  };
+}
+
+/** How a live order filled, from the account's trades on its token */
+export interface FillDetails {
+  /** Maker (it rested and was matched) or taker (it crossed the book); null while no trade is listed */
+  liquidity: "maker" | "taker" | null;
+  /** Size-weighted executed price over the order's trades; null while no trade is listed */
+  price: number | null;
+}
+
+export async function getFillDetails(client: ClobClient, orderId: string, tokenId: string): Promise<FillDetails> {
+  const trades = (await client.getTrades({ asset_id: tokenId })) as Array<{
+    taker_order_id?: string;
+    price?: string;
+    size?: string;
+    maker_orders?: Array<{ order_id?: string; price?: string; matched_amount?: string }>;
+  }>;
+  let liquidity: FillDetails["liquidity"] = null;
+  let size = 0;
+  let notional = 0;
+  const add = (price: string | undefined, amount: string | undefined) => {
+    const p = parseFloat(price ?? "");
+    const s = parseFloat(amount ?? "");
+    if (!Number.isFinite(p) || !(s > 0)) return;
+    size += s;
+    notional += p * s;
+  };
+  for (const t of trades) {
+    if (t.taker_order_id === orderId) {
+      liquidity ??= "taker";
+      add(t.price, t.size);
+    }
+    for (const m of t.maker_orders ?? []) {
+      if (m.order_id !== orderId) continue;
+      liquidity ??= "maker";
+      add(m.price, m.matched_amount);
+    }
+  }
+  return { liquidity, price: size > 0 ? notional / size : null };
+}
+
+/** USDC collateral balance and exchange allowance, in dollars */
+export async function getCollateralBalance(client: ClobClient): Promise<{ balance: number; allowance: number }> {
+  const res = (await client.getBalanceAllowance({ asset_type: AssetType.COLLATERAL })) as {
+    balance?: string;
+    allowance?: string;
+    allowances?: Record<string, string>;
+  };
+  const toUsd = (raw: string | undefined) => parseFloat(ethers.utils.formatUnits(raw ?? "0", 6));
+  const allowances = res.allowances ? Object.values(res.allowances).map(toUsd) : [toUsd(res.allowance)];
+  return { balance: toUsd(res.balance), allowance: allowances.length ? Math.min(...allowances) : 0 };
+}
+
+/** Build and sign a GTC limit order without posting it (readiness checks) */
+export async function buildLimitOrder(client: ClobClient, params: PlaceLimitOrderParams): Promise<unknown> {
+  return client.createOrder(
+    {
+      tokenID: params.tokenId,
+      price: params.price,
+      size: params.size,
+      side: params.side === "BUY" ? Side.BUY : Side.SELL,
+    },
+    { tickSize: params.tickSize ?? "0.01", negRisk: params.negRisk ?? false }
+  );
+}
@@ -0,0 +1,52 @@
+/** Time source for the bot loop; the replay driver swaps in a virtual clock so nothing sleeps for real */
+export interface Clock {
+  /** Unix time in milliseconds */
+  now(): number;
+  sleep(ms: number): Promise<void>;
+}
+
+export class SystemClock implements Clock {
+  now(): number {
+    return Date.now();
+  }
+
+  sleep(ms: number): Promise<void> {
+    return new Promise((r) => setTimeout(r, ms));
+  }
+}
+
+/** Clock driven by recorded data: `sleep` returns immediately, time moves only via `set` */
+export class VirtualClock implements Clock {
+  private current: number;
+
+  constructor(startMs: number) {
+    this.current = startMs;
+  }
+
+  now(): number {
+    return this.current;
+  }
+
+  set(ms: number): void {
+    this.current = Math.max(this.current, ms);
+  }
+
+  async sleep(_ms: number): Promise<void> {
+    // Time is advanced by the replay driver to the next recorded snapshot instead.
+  }
+}
+
+let active: Clock = new SystemClock();
+
+export function clock(): Clock {
+  return active;
+}
+
+export function setClock(c: Clock): void {
+  active = c;
+}
+
+/** Current unix time in seconds on the active clock */
+export function nowSeconds(): number {
+  return Math.floor(active.now() / 1000);
+}
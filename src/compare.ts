@@ -0,0 +1,73 @@
+import { formatTime, formatUsd } from "./money.js";
+import type { ClosedPositionRecord } from "./reports.js";
+import { Storage } from "./storage.js";
+
+interface RunData {
+  placements: Set<string>;
+  fills: Set<string>;
+  pnlByPeriod: Map<number, number>;
+  positions: number;
+}
+
+function key(r: { period_timestamp: number; token_type: string }): string {
+  return `${r.period_timestamp} ${r.token_type}`;
+}
+
+function load(dir: string): RunData {
+  const storage = new Storage(dir);
+  const pnlByPeriod = new Map<number, number>();
+  const positions = storage.readJsonl<ClosedPositionRecord>("positions.jsonl");
+  for (const p of positions) pnlByPeriod.set(p.period_timestamp, (pnlByPeriod.get(p.period_timestamp) ?? 0) + p.realized_pnl);
+  return {
+    placements: new Set(storage.readJsonl<{ period_timestamp: number; token_type: string }>("placements.jsonl").map(key)),
+    fills: new Set(storage.readJsonl<{ period_timestamp: number; token_type: string }>("fills.jsonl").map(key)),
+    pnlByPeriod,
+    positions: positions.length,
+  };
+}
+
+function setDiff(a: Set<string>, b: Set<string>): string[] {
+  return [...a].filter((k) => !b.has(k)).sort();
+}
+
+/**
+ * `--compare <dirA> <dirB>`: diff two runs over the same data (e.g. `--replay` of one dataset with two
+ * configs, or two checkouts of the bot, each with its own `data_dir`): orders and fills only one run made,
+ * and realized PnL per period, so a refactor that should not change trading can be checked for that.
+ */
+export function compareRuns(dirA: string, dirB: string, maxRows: number = 10): string {
+  const a = load(dirA);
+  const b = load(dirB);
+  const lines = [`Run comparison: A = ${dirA}, B = ${dirB}`];
+  const section = (title: string, only: string[]) => {
+    lines.push(`   ${title}: ${only.length}`);
+    for (const k of only.slice(0, maxRows)) lines.push(`      ${k}`);
+    if (only.length > maxRows) lines.push(`      ... ${only.length - maxRows} more`);
+  };
+  section("Orders only in A", setDiff(a.placements, b.placements));
+  section("Orders only in B", setDiff(b.placements, a.placements));
+  section("Fills only in A", setDiff(a.fills, b.fills));
+  section("Fills only in B", setDiff(b.fills, a.fills));
+
+  const periods = [...new Set([...a.pnlByPeriod.keys(), ...b.pnlByPeriod.keys()])];
+  const deltas = periods
+    .map((p) => ({ period: p, a: a.pnlByPeriod.get(p) ?? 0, b: b.pnlByPeriod.get(p) ?? 0 }))
+    .filter((d) => Math.abs(d.b - d.a) > 1e-9)
+    .sort((x, y) => Math.abs(y.b - y.a) - Math.abs(x.b - x.a));
+  lines.push(`   Periods with a PnL difference: ${deltas.length}`);
+  if (deltas.length > 0) lines.push("      period                           A           B       B - A");
+  for (const d of deltas.slice(0, maxRows)) {
+    lines.push(
+      `      ${formatTime(d.period * 1000).padEnd(24)}  ${formatUsd(d.a).padStart(10)}  ${formatUsd(d.b).padStart(10)}  ` +
+        `${formatUsd(d.b - d.a).padStart(10)}`
+    );
+  }
+  const total = (r: RunData) => [...r.pnlByPeriod.values()].reduce((s, v) => s + v, 0);
+  lines.push(
+    `   Realized PnL: A ${formatUsd(total(a))} (${a.positions} closed), B ${formatUsd(total(b))} (${b.positions} closed), ` +
+      `delta ${formatUsd(total(b) - total(a))}`
+  );
+  const identical = a.placements.size === b.placements.size && setDiff(a.placements, b.placements).length === 0 && deltas.length === 0;
+  lines.push(identical ? "   ✅ Same orders and PnL" : "   ⚠️ Runs differ");
+  return lines.join("\n");
+}
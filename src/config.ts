@@ -1,9 +1,22 @@
 import { readFileSync, existsSync, writeFileSync } from "fs";
 import { join } from "path";
+import type { Asset } from "./types.js";
+import type { NetworkConfig } from "./network.js";
+import type { DisplayConfig } from "./money.js";
+import type { LoggingConfig } from "./logging.js";
+import type { SummaryConfig } from "./summary.js";
+import type { AlertRoutingConfig } from "./notifier.js";
+import type { BookHeatmapConfig } from "./books.js";
+import type { SimTieBreak } from "./sim-fill.js";
 
 export interface PolymarketConfig {
   gamma_api_url: string;
   clob_api_url: string;
+  data_api_url?: string | null;
+  gamma_api_fallback_urls?: string[] | null;
+  clob_api_fallback_urls?: string[] | null;
+  failover_after_errors?: number | null;
+  failover_probe_seconds?: number | null;
   api_key: string | null;
   api_secret: string | null;
   api_passphrase: string | null;
@@ -12,6 +25,194 @@ export interface PolymarketConfig {
   signature_type: number | null;
 }
 
+export interface TakeProfitLevel {
+  gain_pct: number;
+  fraction: number;
+}
+
+/** never: no re-entry after a stop-out; always: re-enter right away; recross: re-enter once the underlying is back across the period open */
+export interface ReentryRule {
+  mode: "never" | "always" | "recross";
+  max_reentries?: number | null;
+}
+
+export interface LatencyArbConfig {
+  enabled: boolean;
+  lookback_ms: number;
+  min_move_bps: number;
+  min_edge: number;
+  max_slippage: number;
+  max_shares: number;
+  volatility_per_sqrt_sec: number;
+  max_trades_per_period?: number | null;
+}
+
+/** Synthetic stop execution: ticks at or below the level before firing, and the furthest below it an exit may sell */
+export interface StopTriggerConfig {
+  confirm_ticks?: number | null;
+  max_slippage?: number | null;
+}
+
+/**
+ * No-entry windows enforced by the trader for every strategy: the first `first_seconds` and last
+ * `last_seconds` of each period, and `after_discovery_seconds` after markets are (re)subscribed.
+ */
+export interface FlatPeriodsConfig {
+  first_seconds?: number | null;
+  last_seconds?: number | null;
+  after_discovery_seconds?: number | null;
+}
+
+/** Mirror trade events to Redis pub/sub and/or MQTT for external consumers */
+export interface PubSubConfig {
+  redis_url?: string | null;
+  mqtt_url?: string | null;
+  /** Redis channel `<prefix>:<kind>`, MQTT topic `<prefix>/<kind>`; default `polymarket-bot` */
+  topic_prefix?: string | null;
+}
+
+/**
+ * Hot standby: run two instances with the same config on different hosts, one `primary` and one
+ * `standby`, sharing `lease_file`. The standby places no orders until the primary's lease is
+ * `takeover_after_seconds` old, then cancels the account's resting orders and takes over.
+ */
+export interface FailoverConfig {
+  role: "primary" | "standby";
+  lease_file: string;
+  takeover_after_seconds?: number | null;
+}
+
+/**
+ * One-cancels-other exits per filled position: a take-profit sell resting `take_profit_pct` above the
+ * entry and a stop `stop_pct` below it; whichever executes first cancels the other.
+ */
+export interface OcoConfig {
+  take_profit_pct: number;
+  stop_pct: number;
+}
+
+/**
+ * Dollar-cost averaging into a filled position: one add each time the bid falls another `step` below the
+ * first fill, up to `max_adds` adds and `max_notional` total cost. The combined position is stopped out
+ * on `max_loss` at the bid instead of `stop_loss_price`.
+ */
+export interface DcaConfig {
+  enabled: boolean;
+  step: number;
+  max_adds: number;
+  max_notional: number;
+  /** Shares per add; defaults to the initial order's size */
+  shares?: number | null;
+  max_loss?: number | null;
+}
+
+/**
+ * Grid inside a period: buy levels `spacing` apart below the recent mid, each fill answered by a sell one
+ * step above it (and each sell by a buy one step below), between `start_seconds` and `stop_seconds`.
+ */
+export interface GridConfig {
+  enabled: boolean;
+  levels: number;
+  spacing: number;
+  shares_per_level: number;
+  /** Most shares held per token */
+  max_inventory: number;
+  /** Per token and period, realized plus marked-to-bid; the grid flattens and stops there */
+  max_loss: number;
+  start_seconds?: number | null;
+  stop_seconds?: number | null;
+  mid_lookback_seconds?: number | null;
+  side?: "up" | "down" | "both" | null;
+  /** Inventory skew: with f(inventory / max_inventory), sells sit `spacing * (1 - skew_strength * f)` above and buys `spacing * (1 + skew_strength * f)` below */
+  skew?: "none" | "linear" | "quadratic" | null;
+  skew_strength?: number | null;
+}
+
+/** BTC/ETH Up divergence pair trade (see `PairTradeStrategy`) */
+export interface PairTradeConfig {
+  enabled: boolean;
+  /** |z-score| of BTC Up mid - ETH Up mid against its rolling history that opens a pair */
+  entry_z: number;
+  /** Shares per leg (equal on both legs) */
+  shares: number;
+  /** Spread samples (one per snapshot) in the rolling history (default 1800) */
+  lookback_samples?: number | null;
+  /** Samples needed before trading (default 300) */
+  min_samples?: number | null;
+  /** Most paid for the two asks together (default 1.00) */
+  max_pair_cost?: number | null;
+  max_pairs_per_period?: number | null;
+  start_seconds?: number | null;
+  stop_seconds?: number | null;
+}
+
+/** Polling interval by period phase: the first `open_seconds`, the last `close_seconds`, and the rest */
+export interface PhaseIntervalsConfig {
+  open_seconds: number;
+  open_ms: number;
+  close_seconds: number;
+  close_ms: number;
+  middle_ms?: number | null;
+}
+
+export interface RedemptionConfig {
+  enabled: boolean;
+  max_gas_gwei?: number | null;
+  max_delay_minutes?: number | null;
+  check_interval_seconds?: number | null;
+}
+
+export interface AlertsConfig {
+  webhook_url?: string | null;
+  min_interval_seconds?: number | null;
+  max_consecutive_rejections?: number | null;
+  max_fill_deviation_pct?: number | null;
+  /** Alert when an asset's average slippage (per share, vs target) over the last `slippage_window` live fills exceeds this */
+  max_avg_slippage?: number | null;
+  slippage_window?: number | null;
+  max_period_pnl?: number | null;
+  snapshot_gap_seconds?: number | null;
+  /** Alert wording per kind, overriding `DEFAULT_TEMPLATES` (e.g. translations) */
+  templates?: Record<string, string> | null;
+  telegram?: { bot_token: string; chat_id: string } | null;
+  routing?: AlertRoutingConfig | null;
+}
+
+/** Sell both sides of a market once both filled below $1 combined (`spread_capture`) */
+export interface SpreadCaptureConfig {
+  enabled: boolean;
+  /** Profit per share pair above combined cost plus fees (default 0.01) */
+  min_profit?: number | null;
+}
+
+/** Buy only the side a direction signal picks at period start (`one_sided`) */
+export interface OneSidedConfig {
+  signal: "spot_momentum" | "webhook";
+  /** spot_momentum: smallest move (%) over the previous period that counts as a direction (default 0.05) */
+  min_move_pct?: number | null;
+  webhook_url?: string | null;
+  /** Without a direction from the signal: buy both sides, or skip the asset this period (default skip) */
+  when_unknown?: "both" | "skip" | null;
+}
+
+/** Enter the period in progress when launched mid-period, if it still clears the EV bar (`catch_up`) */
+export interface CatchUpConfig {
+  /** Latest point into the period (minutes) a launch still enters it (default 5) */
+  max_elapsed_minutes?: number | null;
+  /** EV per share a catch-up buy must clear (default `min_expected_value`, else 0) */
+  min_expected_value?: number | null;
+}
+
+/** Randomize limit-buy sizes and submission times so the bot's orders are harder to spot on the book (`order_jitter`) */
+export interface OrderJitterConfig {
+  /** Size is drawn uniformly within +/- this percent of the computed size */
+  size_pct?: number | null;
+  /** Submission is delayed by a uniform 0..this many milliseconds */
+  max_delay_ms?: number | null;
+  /** Seed of the draws; unset picks one per run. Either way it is recorded in `sessions.jsonl` */
+  seed?: number | null;
+}
+
 export interface TradingConfig {
   eth_condition_id: string | null;
   btc_condition_id: string | null;
@@ -25,6 +226,84 @@ export interface TradingConfig {
   max_buy_price: number | null;
   stop_loss_price: number | null;
   hedge_price: number | null;
+  taker_fee_rate?: number | null;
+  spread_capture?: SpreadCaptureConfig | null;
+  one_sided?: OneSidedConfig | null;
+  catch_up?: CatchUpConfig | null;
+  order_jitter?: OrderJitterConfig | null;
+  /** Minimum expected value per share (P(fill) x (fair - limit - fee)) for a period-start buy; null places every one */
+  min_expected_value?: number | null;
+  /** Check each held market's UMA resolution before settling and hold settlement while it is disputed */
+  uma_dispute_check?: boolean | null;
+  /** Record each entry's thesis and its outcome to `journal.jsonl` */
+  journal?: boolean | null;
+  take_profit_levels?: TakeProfitLevel[] | null;
+  reentry_after_stop?: Partial<Record<Asset, ReentryRule>> | null;
+  early_exit_seconds?: number | null;
+  early_exit_band?: [number, number] | null;
+  spot_feed?: "binance" | "coinbase" | "chainlink" | null;
+  oracle_feed?: "chainlink" | null;
+  spot_poll_interval_ms?: number | null;
+  polygon_rpc_url?: string | null;
+  chainlink_feeds?: Partial<Record<Asset, string>> | null;
+  latency_arb?: LatencyArbConfig | null;
+  grid?: GridConfig | null;
+  pair_trade?: PairTradeConfig | null;
+  dca?: DcaConfig | null;
+  oco?: OcoConfig | null;
+  stop_trigger?: StopTriggerConfig | null;
+  flat_periods?: FlatPeriodsConfig | null;
+  latency_warn_p95_ms?: number | null;
+  target_maker_ratio?: number | null;
+  order_priority?: Asset[] | "edge" | null;
+  order_concurrency?: number | null;
+  trade_stats_window_seconds?: number | null;
+  sim_fill_requires_print?: boolean | null;
+  /** Price difference below which a simulated touch counts as exactly at the limit */
+  sim_fill_epsilon?: number | null;
+  sim_fill_at_limit?: SimTieBreak | null;
+  /** Simulated strategy sells beyond held shares: refuse them, or place them with a warning */
+  sim_naked_sells?: "reject" | "flag" | null;
+  /** Share of each winning simulated fill treated as unfilled, or "auto" to calibrate from live vs simulated history */
+  sim_adverse_selection?: number | "auto" | null;
+  /** Live: shadow every position buy with a simulated order and compare fills and PnL per period */
+  divergence_monitor?: boolean | null;
+  book_depth_levels?: number | null;
+  max_opposing_book_imbalance?: number | null;
+  fair_value_volatility_per_sqrt_sec?: number | null;
+  track_edge_decay?: boolean | null;
+  data_dir?: string | null;
+  record_snapshots?: boolean | null;
+  book_heatmap?: BookHeatmapConfig | null;
+  /** Log the prices line (and spot/oracle lines) every tick; off skips formatting them at all */
+  log_ticks?: boolean | null;
+  heartbeat_file?: string | null;
+  failover?: FailoverConfig | null;
+  pubsub?: PubSubConfig | null;
+  health_port?: number | null;
+  health_stale_after_ms?: number | null;
+  /** Unix socket path for `botctl` (status, state, pause/resume, reports) */
+  control_socket?: string | null;
+  alerts?: AlertsConfig | null;
+  redemption?: RedemptionConfig | null;
+  phase_intervals?: PhaseIntervalsConfig | null;
+  price_history_minutes?: number | null;
+  max_tick_jump_pct?: number | null;
+  position_snapshot_seconds?: number | null;
+  /** Live: smallest unexplained balance change recorded as a deposit/withdrawal; null disables (default 5) */
+  capital_flow_min_usd?: number | null;
+  display?: DisplayConfig | null;
+  logging?: LoggingConfig | null;
+  summary?: SummaryConfig | null;
+  max_account_exposure?: number | null;
+  /** Cap (USDC) on the loss if every open market resolved against this process's positions */
+  max_worst_case_loss?: number | null;
+  /** Cap on markets (condition_ids) with open exposure at once */
+  max_open_markets?: number | null;
+  /** At the cap: skip new markets, or free the slot of the oldest market whose buys are all unfilled */
+  open_markets_policy?: "skip_new" | "replace_unfilled" | null;
+  halt_recheck_seconds?: number | null;
+  discovery_series?: Partial<Record<Asset, string>> | null;
   market_closure_check_interval_seconds: number;
   min_time_remaining_seconds: number | null;
   enable_eth_trading: boolean;
@@ -37,12 +316,18 @@ export interface TradingConfig {
 export interface Config {
   polymarket: PolymarketConfig;
   trading: TradingConfig;
+  network?: NetworkConfig | null;
 }
 
-const DEFAULT_CONFIG: Config = {
+export const DEFAULT_CONFIG: Config = {
   polymarket: {
     gamma_api_url: "https://gamma-api.polymarket.com",
     clob_api_url: "https://clob.polymarket.com",
+    data_api_url: "https://data-api.polymarket.com",
+    gamma_api_fallback_urls: null,
+    clob_api_fallback_urls: null,
+    failover_after_errors: 3,
+    failover_probe_seconds: 60,
     api_key: null,
     api_secret: null,
     api_passphrase: null,
@@ -63,6 +348,71 @@ const DEFAULT_CONFIG: Config = {
     max_buy_price: 0.95,
     stop_loss_price: 0.85,
     hedge_price: 0.5,
+    taker_fee_rate: 0,
+    spread_capture: null,
+    one_sided: null,
+    catch_up: null,
+    order_jitter: null,
+    min_expected_value: null,
+    journal: null,
+    uma_dispute_check: null,
+    take_profit_levels: null,
+    reentry_after_stop: null,
+    early_exit_seconds: null,
+    early_exit_band: null,
+    spot_feed: null,
+    oracle_feed: null,
+    spot_poll_interval_ms: 500,
+    polygon_rpc_url: "https://polygon-rpc.com",
+    chainlink_feeds: null,
+    latency_arb: null,
+    grid: null,
+    pair_trade: null,
+    dca: null,
+    oco: null,
+    stop_trigger: null,
+    flat_periods: null,
+    latency_warn_p95_ms: 1000,
+    target_maker_ratio: null,
+    order_priority: null,
+    order_concurrency: 1,
+    trade_stats_window_seconds: null,
+    sim_fill_requires_print: false,
+    sim_fill_epsilon: 0.0001,
+    sim_fill_at_limit: "fill",
+    sim_naked_sells: "reject",
+    sim_adverse_selection: null,
+    divergence_monitor: null,
+    book_depth_levels: 5,
+    max_opposing_book_imbalance: null,
+    fair_value_volatility_per_sqrt_sec: 0.0001,
+    track_edge_decay: false,
+    data_dir: "data",
+    record_snapshots: false,
+    book_heatmap: null,
+    log_ticks: true,
+    heartbeat_file: null,
+    failover: null,
+    pubsub: null,
+    health_port: null,
+    health_stale_after_ms: 60000,
+    control_socket: null,
+    alerts: null,
+    redemption: null,
+    phase_intervals: null,
+    price_history_minutes: 5,
+    max_tick_jump_pct: 50,
+    position_snapshot_seconds: 60,
+    capital_flow_min_usd: 5,
+    display: null,
+    logging: null,
+    summary: null,
+    max_account_exposure: null,
+    max_worst_case_loss: null,
+    max_open_markets: null,
+    open_markets_policy: null,
+    halt_recheck_seconds: 30,
+    discovery_series: null,
     market_closure_check_interval_seconds: 10,
     min_time_remaining_seconds: 30,
     enable_eth_trading: false,
@@ -73,6 +423,11 @@ const DEFAULT_CONFIG: Config = {
   },
 };
 
+/** Whether `key` names a trading setting (`config.trading`) */
+export function isTradingSetting(key: string): boolean {
+  return Object.prototype.hasOwnProperty.call(DEFAULT_CONFIG.trading, key);
+}
+
 export function loadConfig(configPath: string = "config.json"): Config {
   const path = join(process.cwd(), configPath);
   if (existsSync(path)) {
@@ -83,14 +438,93 @@ export function loadConfig(configPath: string = "config.json"): Config {
   return DEFAULT_CONFIG;
 }
 
-export function parseArgs(): { simulation: boolean; config: string } {
+export interface ControlCommand {
+  action: "pause" | "resume";
+  asset: Asset;
+}
+
+/** `--fetch-history <from> [to]`: unix seconds */
+export interface FetchHistoryCommand {
+  from: number;
+  to: number;
+}
+
+/** `--state export <file>` / `--state import <file>` */
+export interface StateCommand {
+  action: "export" | "import";
+  file: string;
+}
+
+/** Unix seconds from `1760000000` or an ISO date/time */
+function parseTime(value: string): number {
+  const ts = /^\d+$/.test(value) ? Number(value) : Math.floor(Date.parse(value) / 1000);
+  if (!Number.isFinite(ts)) throw new Error(`invalid time '${value}' (unix seconds or ISO date)`);
+  return ts;
+}
+
+export function parseArgs(): {
+  simulation: boolean;
+  config: string;
+  report: string | null;
+  replay: string | null;
+  control: ControlCommand | null;
+  state: StateCommand | null;
+  fetchHistory: FetchHistoryCommand | null;
+  compare: [string, string] | null;
+  bench: number | null;
+  trainFillModel: boolean;
+  verify: boolean;
+  approve: boolean;
+  observe: boolean;
+  repl: boolean;
+} {
   const args = process.argv.slice(2);
   let simulation = true;
   let config = "config.json";
+  let report: string | null = null;
+  let replay: string | null = null;
+  let control: ControlCommand | null = null;
+  let state: StateCommand | null = null;
+  let fetchHistory: FetchHistoryCommand | null = null;
+  let compare: [string, string] | null = null;
+  let bench: number | null = null;
+  let trainFillModel = false;
+  let verify = false;
+  let approve = false;
+  let observe = false;
+  let repl = false;
   for (let i = 0; i < args.length; i++) {
     if (args[i] === "--no-simulation") simulation = false;
     else if (args[i] === "--simulation") simulation = true;
     else if (args[i] === "-c" || args[i] === "--config") config = args[++i] ?? config;
+    else if (args[i] === "--report") report = args[++i] ?? null;
+    else if (args[i] === "--replay") replay = args[++i] ?? null;
+    else if (args[i] === "--verify") verify = true;
+    else if (args[i] === "--train-fill-model") trainFillModel = true;
+    else if (args[i] === "--bench") bench = args[i + 1] != null && /^\d+$/.test(args[i + 1]) ? Number(args[++i]) : 20_000;
+    else if (args[i] === "--approve") approve = true;
+    else if (args[i] === "--observe") observe = true;
+    else if (args[i] === "--repl") repl = true;
+    else if (args[i] === "--pause" || args[i] === "--resume") {
+      const asset = (args[++i] ?? "").toUpperCase() as Asset;
+      control = { action: args[i - 1] === "--pause" ? "pause" : "resume", asset };
+    } else if (args[i] === "--state") {
+      const action = args[++i];
+      const file = args[++i];
+      if ((action !== "export" && action !== "import") || !file) throw new Error("usage: --state export|import <file>");
+      state = { action, file };
+    } else if (args[i] === "--compare") {
+      const dirA = args[++i];
+      const dirB = args[++i];
+      if (!dirA || !dirB) throw new Error("usage: --compare <data_dir A> <data_dir B>");
+      compare = [dirA, dirB];
+    } else if (args[i] === "--fetch-history") {
+      const from = args[++i];
+      if (!from) throw new Error("usage: --fetch-history <from> [to]");
+      const to = args[i + 1] != null && !args[i + 1].startsWith("-") ? args[++i] : null;
+      fetchHistory = { from: parseTime(from), to: to ? parseTime(to) : Math.floor(Date.now() / 1000) };
+    }
   }
-  return { simulation, config };
+  // Observing never trades, so it can never be live.
+  return { simulation: simulation || observe, config, report, replay, control, state, fetchHistory, compare, bench, trainFillModel, verify, approve, observe, repl };
 }
@@ -0,0 +1,141 @@
+import { createInterface } from "readline";
+import { isTradingSetting } from "./config.js";
+import { BotControl } from "./control.js";
+import { REPORTS } from "./reports.js";
+import type { BotRuntime } from "./runtime.js";
+import { formatSummary } from "./summary.js";
+import type { Asset, TokenType } from "./types.js";
+import { tokenPriceForType, tokenTypeDisplayName } from "./types.js";
+
+const TOKEN_TYPES: TokenType[] = ["BtcUp", "BtcDown", "EthUp", "EthDown", "SolanaUp", "SolanaDown", "XrpUp", "XrpDown"];
+
+const HELP = [
+  "Commands:",
+  "   state                          trader state as JSON (orders, positions, totals, portfolio)",
+  "   summary                        the periodic summary, now",
+  "   orders                         working orders",
+  "   pause <asset> | resume <asset> stop/allow new entries for BTC, ETH, SOL or XRP",
+  "   get <setting>                  current value of a trading setting",
+  "   set <setting> <json>           change a trading setting for this run (not written to the config file)",
+  "   buy|sell <token> <price> <units>  place a test limit order on this period's market (e.g. buy BtcUp 0.05 5)",
+  "   cancel <order_id>              cancel an order placed with buy/sell (or by a strategy)",
+  "   report <name>                  run a --report",
+  "   help",
+].join("\n");
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * One console command against a running bot; returns the text to show. Shared by `--repl` and anything
+ * else that takes commands (a throw is the error message to show).
+ */
+export async function runConsoleCommand(runtime: BotRuntime, line: string): Promise<string> {
+  const [cmd, ...args] = line.trim().split(/\s+/);
+  const trading = runtime.config.trading as unknown as Record<string, unknown>;
+  switch (cmd) {
+    case "":
+      return "";
+    case "help":
+      return HELP;
+    case "state":
+      return JSON.stringify(await runtime.trader.state(), null, 2);
+    case "summary":
+      return formatSummary(await runtime.trader.state(), {});
+    case "orders": {
+      const { orders } = await runtime.trader.state();
+      if (orders.length === 0) return "No working orders";
+      return orders
+        .map(
+          (o) =>
+            `${o.order_id ?? "pending"} ${o.side} ${tokenTypeDisplayName(o.token_type)} ${o.units.toFixed(2)} @ $${o.price.toFixed(2)}` +
+            ` (${o.position_id ?? "strategy"}, ${o.reason ?? "unspecified"})`
+        )
+        .join("\n");
+    }
+    case "pause":
+    case "resume": {
+      const asset = (args[0] ?? "").toUpperCase() as Asset;
+      const paused = BotControl.setPaused(runtime.storage, asset, cmd === "pause");
+      return `${asset} ${cmd}d (paused: ${paused.join(", ") || "none"}) - applied on the next tick`;
+    }
+    case "get": {
+      if (!args[0]) throw new Error("usage: get <setting>");
+      return `${args[0]} = ${JSON.stringify(trading[args[0]] ?? null)}`;
+    }
+    case "set": {
+      const [key, ...rest] = args;
+      if (!key || rest.length === 0) throw new Error("usage: set <setting> <json>");
+      if (!isTradingSetting(key)) throw new Error(`Unknown trading setting '${key}'`);
+      const raw = rest.join(" ");
+      let value: unknown;
+      try {
+        value = JSON.parse(raw);
+      } catch {
+        value = raw;
+      }
+      const before = trading[key];
+      trading[key] = value;
+      log(`🛠️ Console: ${key} ${JSON.stringify(before ?? null)} -> ${JSON.stringify(value)}`);
+      // Settings copied at startup (limit price, feeds, strategies) keep their startup value.
+      return `${key} = ${JSON.stringify(value)} (takes effect where the setting is read per decision)`;
+    }
+    case "buy":
+    case "sell": {
+      const [token, priceArg, unitsArg] = args;
+      const tokenType = TOKEN_TYPES.find((t) => t.toLowerCase() === (token ?? "").toLowerCase());
+      const price = Number(priceArg);
+      const units = Number(unitsArg);
+      if (!tokenType || !(price > 0 && price < 1) || !(units > 0)) throw new Error(`usage: ${cmd} <${TOKEN_TYPES.join("|")}> <price> <units>`);
+      const snapshot = runtime.lastSnapshot();
+      const quote = snapshot ? tokenPriceForType(snapshot, tokenType) : null;
+      if (!snapshot || !quote) throw new Error(`No ${tokenTypeDisplayName(tokenType)} market in the latest snapshot`);
+      const order = await runtime.trader.placeOrder({
+        token_id: quote.token_id,
+        token_type: tokenType,
+        period_timestamp: snapshot.period_timestamp,
+        side: cmd === "buy" ? "BUY" : "SELL",
+        price,
+        units,
+        reason: "console_test",
+      });
+      if (!order) return "Order not placed (paused, halted, or blocked by a risk limit - see the log)";
+      return `Placed ${order.order_id}: ${order.side} ${tokenTypeDisplayName(tokenType)} ${units.toFixed(2)} @ $${price.toFixed(2)} - not managed further, cancel it when done`;
+    }
+    case "cancel": {
+      const order = (await runtime.trader.state()).orders.find((o) => o.order_id === args[0]);
+      if (!order || !order.order_id) throw new Error(`No working order '${args[0] ?? ""}'`);
+      if (order.position_id) throw new Error(`${order.order_id} is a position buy (${order.position_id}); the trader manages its cancellation`);
+      await runtime.trader.cancelOrder({ ...order, order_id: order.order_id, reason: order.reason ?? undefined });
+      return `Cancelled ${order.order_id}`;
+    }
+    case "report": {
+      const run = REPORTS[args[0] ?? ""];
+      if (!run) throw new Error(`Unknown report '${args[0] ?? ""}' (available: ${Object.keys(REPORTS).join(", ")})`);
+      return run(runtime.storage);
+    }
+    default:
+      throw new Error(`Unknown command '${cmd}' (try help)`);
+  }
+}
+
+/**
+ * Interactive console on stdin/stderr next to the running bot (`--repl`): inspect state, change
+ * settings, place and cancel test orders and run reports while it trades. Ctrl-C stops the bot as usual.
+ */
+export function startConsole(runtime: BotRuntime): void {
+  const rl = createInterface({ input: process.stdin, output: process.stderr, prompt: "bot> " });
+  log("⌨️ Console ready - type help for commands");
+  rl.on("line", (line) => {
+    runConsoleCommand(runtime, line)
+      .then((out) => {
+        if (out) log(out);
+      })
+      .catch((e) => log(`❌ ${e instanceof Error ? e.message : String(e)}`))
+      .finally(() => rl.prompt());
+  });
+  // readline swallows Ctrl-C; hand it to the session handler so the bot shuts down cleanly.
+  rl.on("SIGINT", () => process.kill(process.pid, "SIGINT"));
+  rl.prompt();
+}
@@ -0,0 +1,84 @@
+import { createServer } from "net";
+import type { Server } from "net";
+import { chmodSync, existsSync, unlinkSync } from "fs";
+import { runConsoleCommand } from "./console.js";
+import type { BotRuntime } from "./runtime.js";
+
+/** Console commands allowed over the socket: inspection and pause/resume, nothing that places orders or edits settings */
+const SOCKET_COMMANDS = new Set(["help", "status", "state", "summary", "orders", "pause", "resume", "report"]);
+
+/** One reply per request line, as a line of JSON */
+export interface ControlReply {
+  ok: boolean;
+  output: string;
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Control channel on a unix domain socket (`control_socket`) for headless servers: status, state,
+ * pause/resume and reports without opening a TCP port. Each request is a console command on one line,
+ * answered with one line of `ControlReply` JSON; `botctl` is the client. The socket is created
+ * owner-only (0600).
+ */
+export class ControlSocket {
+  private path: string;
+  private runtime: BotRuntime;
+  private status: () => Record<string, unknown>;
+  private server: Server | null = null;
+
+  constructor(path: string, runtime: BotRuntime, status: () => Record<string, unknown>) {
+    this.path = path;
+    this.runtime = runtime;
+    this.status = status;
+  }
+
+  start(): void {
+    // A socket file left by a process that was killed would make listen fail with EADDRINUSE.
+    if (existsSync(this.path)) unlinkSync(this.path);
+    this.server = createServer((conn) => {
+      let buffer = "";
+      conn.setEncoding("utf-8");
+      conn.on("data", (chunk: string) => {
+        buffer += chunk;
+        let nl: number;
+        while ((nl = buffer.indexOf("\n")) >= 0) {
+          const line = buffer.slice(0, nl).trim();
+          buffer = buffer.slice(nl + 1);
+          void this.handle(line).then((reply) => conn.write(JSON.stringify(reply) + "\n"));
+        }
+      });
+      conn.on("error", () => conn.destroy());
+    });
+    this.server.on("error", (e) => log(`⚠️ Control socket error: ${String(e)}`));
+    this.server.listen(this.path, () => {
+      chmodSync(this.path, 0o600);
+      log(`🔌 Control socket on ${this.path} (botctl --socket ${this.path} status)`);
+    });
+    process.once("exit", () => this.stop());
+  }
+
+  stop(): void {
+    if (!this.server) return;
+    this.server.close();
+    this.server = null;
+    try {
+      unlinkSync(this.path);
+    } catch {
+      // Already gone.
+    }
+  }
+
+  private async handle(line: string): Promise<ControlReply> {
+    const cmd = line.split(/\s+/)[0];
+    if (!SOCKET_COMMANDS.has(cmd)) return { ok: false, output: `'${cmd}' is not available over the control socket (${[...SOCKET_COMMANDS].join(", ")})` };
+    if (cmd === "status") return { ok: true, output: JSON.stringify(this.status(), null, 2) };
+    try {
+      return { ok: true, output: await runConsoleCommand(this.runtime, line) };
+    } catch (e) {
+      return { ok: false, output: e instanceof Error ? e.message : String(e) };
+    }
+  }
+}
@@ -0,0 +1,63 @@
+import { existsSync, statSync } from "fs";
+import type { Storage } from "./storage.js";
+import type { Asset } from "./types.js";
+
+const CONTROL_FILE = "control.json";
+const ASSETS: Asset[] = ["BTC", "ETH", "SOL", "XRP"];
+
+interface ControlState {
+  paused_assets: Asset[];
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Runtime switches read from `<data_dir>/control.json`, picked up by the running bot on its next tick.
+ * Paused assets get no new entries; exits and monitoring of open positions carry on.
+ */
+export class BotControl {
+  private storage: Storage;
+  private paused: Set<Asset> = new Set();
+  private lastMtimeMs = -1;
+
+  constructor(storage: Storage) {
+    this.storage = storage;
+  }
+
+  /** Reload the control file if it changed since the last call, logging pause/resume transitions */
+  refresh(): void {
+    const file = this.storage.path(CONTROL_FILE);
+    const mtimeMs = existsSync(file) ? statSync(file).mtimeMs : 0;
+    if (mtimeMs === this.lastMtimeMs) return;
+    this.lastMtimeMs = mtimeMs;
+    let state: ControlState | null = null;
+    try {
+      state = this.storage.readJson<ControlState>(CONTROL_FILE);
+    } catch (e) {
+      log(`⚠️ Ignoring unreadable ${file}: ${String(e)}`);
+      return;
+    }
+    const next = new Set((state?.paused_assets ?? []).filter((a) => ASSETS.includes(a)));
+    for (const asset of next) if (!this.paused.has(asset)) log(`⏸️ ${asset} entries paused`);
+    for (const asset of this.paused) if (!next.has(asset)) log(`▶️ ${asset} entries resumed`);
+    this.paused = next;
+  }
+
+  isPaused(asset: Asset): boolean {
+    return this.paused.has(asset);
+  }
+
+  /** Update the control file (used by `--pause` / `--resume`); a running bot applies it on its next tick */
+  static setPaused(storage: Storage, asset: Asset, paused: boolean): Asset[] {
+    if (!ASSETS.includes(asset)) throw new Error(`Unknown asset '${asset}' (expected ${ASSETS.join(", ")})`);
+    const state = storage.readJson<ControlState>(CONTROL_FILE) ?? { paused_assets: [] };
+    const assets = new Set(state.paused_assets ?? []);
+    if (paused) assets.add(asset);
+    else assets.delete(asset);
+    const next = ASSETS.filter((a) => assets.has(a));
+    storage.writeJson(CONTROL_FILE, { ...state, paused_assets: next });
+    return next;
+  }
+}
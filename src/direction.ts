@@ -0,0 +1,68 @@
+import axios from "axios";
+import type { OneSidedConfig } from "./config.js";
+import type { SpotFeed } from "./spot.js";
+import type { Asset, MarketSnapshot } from "./types.js";
+
+const PERIOD_DURATION = 900;
+
+/** Side a signal favours for the period; null when it has no view */
+export type Direction = "up" | "down" | null;
+
+/**
+ * Picks the side that gets the period-start order in `one_sided` mode. Built in: `spot_momentum` and
+ * `webhook`; register a model of your own with `BotRuntimeBuilder.directionSignal`.
+ */
+export interface DirectionSignal {
+  readonly name: string;
+  direction(asset: Asset, snapshot: MarketSnapshot): Direction | Promise<Direction>;
+}
+
+/** Follows the underlying's move over the previous period: spot now vs that period's open */
+export class SpotMomentumSignal implements DirectionSignal {
+  readonly name = "spot_momentum";
+  private spotFeed: SpotFeed;
+  private minMovePct: number;
+
+  constructor(spotFeed: SpotFeed, minMovePct: number) {
+    this.spotFeed = spotFeed;
+    this.minMovePct = minMovePct;
+  }
+
+  direction(asset: Asset, snapshot: MarketSnapshot): Direction {
+    const spot = this.spotFeed.price(asset);
+    const open = this.spotFeed.periodOpen(asset, snapshot.period_timestamp - PERIOD_DURATION);
+    if (spot == null || open == null) return null;
+    const movePct = ((spot - open) / open) * 100;
+    if (Math.abs(movePct) < this.minMovePct) return null;
+    return movePct > 0 ? "up" : "down";
+  }
+}
+
+/** Asks an external service: `GET <url>?asset=BTC&period=<ts>` answering `{ "direction": "up" | "down" | null }` */
+export class WebhookSignal implements DirectionSignal {
+  readonly name = "webhook";
+  private url: string;
+
+  constructor(url: string) {
+    this.url = url;
+  }
+
+  async direction(asset: Asset, snapshot: MarketSnapshot): Promise<Direction> {
+    const { data } = await axios.get<{ direction?: string | null }>(this.url, {
+      params: { asset, period: snapshot.period_timestamp },
+      timeout: 2_000,
+    });
+    return data?.direction === "up" || data?.direction === "down" ? data.direction : null;
+  }
+}
+
+/** The signal configured under `one_sided`, or null when the mode is off */
+export function createDirectionSignal(cfg: OneSidedConfig | null | undefined, spotFeed: SpotFeed | null): DirectionSignal | null {
+  if (!cfg) return null;
+  if (cfg.signal === "webhook") {
+    if (!cfg.webhook_url) throw new Error("one_sided.signal webhook requires one_sided.webhook_url");
+    return new WebhookSignal(cfg.webhook_url);
+  }
+  if (!spotFeed) throw new Error("one_sided.signal spot_momentum requires spot_feed");
+  return new SpotMomentumSignal(spotFeed, cfg.min_move_pct ?? 0.05);
+}
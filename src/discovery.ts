@@ -0,0 +1,266 @@
+import type { PolymarketApi } from "./api.js";
+import { clock, nowSeconds } from "./clock.js";
+import type { Config } from "./config.js";
+import type { MarketMonitor, MonitoredMarkets } from "./monitor.js";
+import type { Asset, Market } from "./types.js";
+import { outcomeSide } from "./types.js";
+
+const DISCOVERY_VALIDATION_ATTEMPTS = 3;
+/** Markets sometimes appear after their period starts: keep looking this long */
+const LATE_MARKET_WINDOW_SECONDS = 30;
+const DISCOVERY: Record<keyof MonitoredMarkets, { name: string; asset: Asset; prefixes: string[]; keywords: RegExp }> = {
+  eth: { name: "ETH", asset: "ETH", prefixes: ["eth"], keywords: /\b(ethereum|eth)\b/i },
+  btc: { name: "BTC", asset: "BTC", prefixes: ["btc"], keywords: /\b(bitcoin|btc)\b/i },
+  solana: { name: "Solana", asset: "SOL", prefixes: ["solana", "sol"], keywords: /\b(solana|sol)\b/i },
+  xrp: { name: "XRP", asset: "XRP", prefixes: ["xrp"], keywords: /\b(xrp|ripple)\b/i },
+};
+/** A 15-minute window in an event title, e.g. "12:00PM-12:15PM ET" (hourly events name a single hour) */
+const QUARTER_HOUR_TITLE = /\d{1,2}:\d{2}\s*[AP]M\s*-\s*\d{1,2}:\d{2}\s*[AP]M/i;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Candidate event slugs of one asset's market for a period, in the order discovery tries them */
+export function periodSlugs(key: keyof MonitoredMarkets, periodTimestamp: number): string[] {
+  return DISCOVERY[key].prefixes.map((prefix) => `${prefix}-updown-15m-${periodTimestamp}`);
+}
+
+function disabledMarket(conditionId: string, slug: string, question: string): Market {
+  return {
+    conditionId,
+    slug,
+    question,
+    active: false,
+    closed: true,
+  };
+}
+
+/** Why a discovered market can't be traded as the `periodTimestamp` Up/Down market, or null if it can */
+function marketProblem(market: Market, periodTimestamp: number): string | null {
+  const tokens = market.tokens ?? [];
+  const ids = (side: "up" | "down") => tokens.filter((t) => outcomeSide(t.outcome) === side).map((t) => t.tokenId ?? t.token_id ?? "");
+  const up = ids("up");
+  const down = ids("down");
+  if (tokens.length !== 2 || up.length !== 1 || down.length !== 1) {
+    return `outcomes are [${tokens.map((t) => t.outcome).join(", ")}], expected Up and Down`;
+  }
+  if (!/^\d+$/.test(up[0]) || !/^\d+$/.test(down[0])) return "outcome token ids are missing or malformed";
+  if (market.clobTokenIds) {
+    let listed: unknown;
+    try {
+      listed = JSON.parse(market.clobTokenIds);
+    } catch {
+      return `clobTokenIds does not parse: ${market.clobTokenIds}`;
+    }
+    if (!Array.isArray(listed) || listed.length !== 2 || !listed.includes(up[0]) || !listed.includes(down[0])) {
+      return `clobTokenIds ${market.clobTokenIds} do not match the outcome tokens`;
+    }
+  }
+  const end = market.endDate ?? market.endDateIso ?? market.endDateISO;
+  const endSeconds = end ? Math.floor(Date.parse(end) / 1000) : NaN;
+  if (!end || !Number.isFinite(endSeconds)) return `end time missing or unparseable (${end ?? "none"})`;
+  const expected = new Date((periodTimestamp + 900) * 1000).toISOString();
+  // A date-only end can only be checked to the day.
+  if (/^\d{4}-\d{2}-\d{2}$/.test(end) ? end !== expected.slice(0, 10) : endSeconds !== periodTimestamp + 900) {
+    return `ends ${end}, expected ${expected}`;
+  }
+  return null;
+}
+
+/**
+ * Fetch `slug` and validate it; an invalid market is re-fetched (Gamma can lag) before giving up on it.
+ * With `retryErrors`, a failed fetch (not listed yet, network error) is retried after a second too.
+ */
+async function fetchValidMarket(
+  api: PolymarketApi,
+  name: string,
+  slug: string,
+  periodTimestamp: number,
+  retryErrors = false
+): Promise<Market | null> {
+  for (let attempt = 1; attempt <= DISCOVERY_VALIDATION_ATTEMPTS; attempt++) {
+    let market: Market;
+    try {
+      market = await api.getMarketBySlug(slug);
+    } catch {
+      if (!retryErrors || attempt === DISCOVERY_VALIDATION_ATTEMPTS) return null;
+      await clock().sleep(1_000);
+      continue;
+    }
+    const problem = marketProblem(market, periodTimestamp);
+    if (!problem) return market;
+    log(`⚠️ Rejecting ${name} market ${slug} (attempt ${attempt}/${DISCOVERY_VALIDATION_ATTEMPTS}): ${problem}`);
+    if (attempt < DISCOVERY_VALIDATION_ATTEMPTS) await clock().sleep(2_000);
+  }
+  return null;
+}
+
+/**
+ * Find `key`'s market for `periodTimestamp` among the Gamma events ending with that period, by series
+ * (`discovery_series`) when configured, else by asset name, "Up or Down" and a 15-minute window in the title.
+ */
+async function discoverViaEvents(
+  api: PolymarketApi,
+  key: keyof MonitoredMarkets,
+  periodTimestamp: number,
+  seenIds: Set<string>,
+  series: string | null
+): Promise<Market | null> {
+  const { name, keywords } = DISCOVERY[key];
+  let events: Awaited<ReturnType<PolymarketApi["getEventsEndingAt"]>>;
+  try {
+    events = await api.getEventsEndingAt(periodTimestamp + 900);
+  } catch (e) {
+    log(`⚠️ Gamma events lookup failed: ${String(e)}`);
+    return null;
+  }
+  const candidates = events.filter((e) =>
+    series
+      ? e.series.includes(series)
+      : keywords.test(e.title) && /up or down/i.test(e.title) && (QUARTER_HOUR_TITLE.test(e.title) || [e.slug, ...e.series].some((s) => /15m/.test(s)))
+  );
+  for (const event of candidates) {
+    const market = await api.toMarket(event.market);
+    if (seenIds.has(market.conditionId) || !market.active || market.closed) continue;
+    const problem = marketProblem(market, periodTimestamp);
+    if (problem) {
+      log(`⚠️ Rejecting ${name} event ${event.slug}: ${problem}`);
+      continue;
+    }
+    log(`Found ${name} market via Gamma events: ${event.title} (${event.slug}) | Condition ID: ${market.conditionId}`);
+    return market;
+  }
+  return null;
+}
+
+async function discoverMarket(
+  api: PolymarketApi,
+  key: keyof MonitoredMarkets,
+  currentTime: number,
+  seenIds: Set<string>,
+  includePrevious: boolean,
+  series: string | null = null,
+  retryErrors = false
+): Promise<Market> {
+  const { name, prefixes: slugPrefixes } = DISCOVERY[key];
+  const roundedTime = Math.floor(currentTime / 900) * 900;
+  for (let i = 0; i < slugPrefixes.length; i++) {
+    const prefix = slugPrefixes[i];
+    if (i > 0) log(`🔍 Trying ${name} market with slug prefix '${prefix}'...`);
+    let slug = `${prefix}-updown-15m-${roundedTime}`;
+    const market = await fetchValidMarket(api, name, slug, roundedTime, retryErrors);
+    if (market && !seenIds.has(market.conditionId) && market.active && !market.closed) {
+      log(`Found ${name} market by slug: ${market.slug} | Condition ID: ${market.conditionId}`);
+      return market;
+    }
+    if (includePrevious) {
+      for (let offset = 1; offset <= 3; offset++) {
+        const tryTime = roundedTime - offset * 900;
+        slug = `${prefix}-updown-15m-${tryTime}`;
+        const previous = await fetchValidMarket(api, name, slug, tryTime);
+        if (previous && !seenIds.has(previous.conditionId) && previous.active && !previous.closed) {
+          log(`Found ${name} market by slug: ${previous.slug} | Condition ID: ${previous.conditionId}`);
+          return previous;
+        }
+      }
+    }
+  }
+  // Slug guesses break whenever Polymarket changes the slug format; the events listing doesn't depend on it.
+  const viaEvents = await discoverViaEvents(api, key, roundedTime, seenIds, series);
+  if (viaEvents) return viaEvents;
+  throw new Error(`Could not find active ${name} 15-minute up/down market (tried slugs: ${slugPrefixes.join(", ")}, Gamma events)`);
+}
+
+/** Enabled markets that discovery replaced with a placeholder */
+export function missingMarkets(markets: MonitoredMarkets, trading: Config["trading"]): Array<keyof MonitoredMarkets> {
+  const enabled: Record<keyof MonitoredMarkets, boolean> = {
+    btc: true,
+    eth: trading.enable_eth_trading,
+    solana: trading.enable_solana_trading,
+    xrp: trading.enable_xrp_trading,
+  };
+  return (Object.keys(DISCOVERY) as Array<keyof MonitoredMarkets>).filter(
+    (key) => enabled[key] && markets[key].conditionId.startsWith("dummy_")
+  );
+}
+
+/**
+ * One more discovery attempt for markets missing from `period`; found ones are swapped into the monitor
+ * unless it has moved on to another period. Returns the condition ids found and the keys still missing
+ * (none once the period is past the late-market window).
+ */
+export async function retryLateMarkets(
+  api: PolymarketApi,
+  monitor: MarketMonitor,
+  missing: Array<keyof MonitoredMarkets>,
+  period: number,
+  series: Partial<Record<Asset, string>>
+): Promise<{ found: string[]; missing: Array<keyof MonitoredMarkets> }> {
+  const elapsed = nowSeconds() - period;
+  if (elapsed > LATE_MARKET_WINDOW_SECONDS) {
+    log(`⚠️ ${missing.map((k) => DISCOVERY[k].name).join(", ")} still not listed ${elapsed}s into period ${period} - disabled until the next period`);
+    return { found: [], missing: [] };
+  }
+  const found: string[] = [];
+  const stillMissing: Array<keyof MonitoredMarkets> = [];
+  for (const key of missing) {
+    const { name, asset } = DISCOVERY[key];
+    const seenIds = new Set(Object.values(monitor.getMarkets()).map((m: Market) => m.conditionId));
+    const market = await discoverMarket(api, key, period, seenIds, false, series[asset] ?? null, true).catch(() => null);
+    if (monitor.getMarketsPeriod() !== period) return { found, missing: [] };
+    if (!market) {
+      stillMissing.push(key);
+      continue;
+    }
+    // Re-read: other markets may have changed while discovery was waiting on Gamma.
+    monitor.updateMarkets({ ...monitor.getMarkets(), [key]: market }, period);
+    found.push(market.conditionId);
+    log(`🆕 ${name} market appeared ${nowSeconds() - period}s into period ${period} (${market.slug}) - now monitored`);
+  }
+  return { found, missing: stillMissing };
+}
+
+export async function getOrDiscoverMarkets(
+  api: PolymarketApi,
+  trading: Config["trading"],
+  includePrevious: boolean = true,
+  now: number = nowSeconds()
+): Promise<{ eth: Market; btc: Market; solana: Market; xrp: Market }> {
+  const seenIds = new Set<string>();
+  const enableEth = trading.enable_eth_trading;
+  const enableSolana = trading.enable_solana_trading;
+  const enableXrp = trading.enable_xrp_trading;
+  const series = trading.discovery_series ?? {};
+
+  const eth = enableEth
+    ? await discoverMarket(api, "eth", now, seenIds, includePrevious, series.ETH ?? null).catch(() => {
+        log("⚠️ Could not discover ETH market - using fallback");
+        return disabledMarket("dummy_eth_fallback", "eth-updown-15m-fallback", "ETH Trading Disabled");
+      })
+    : disabledMarket("dummy_eth_fallback", "eth-updown-15m-fallback", "ETH Trading Disabled");
+  seenIds.add(eth.conditionId);
+
+  log("🔍 Discovering BTC market...");
+  const btc = await discoverMarket(api, "btc", now, seenIds, includePrevious, series.BTC ?? null).catch(() => {
+    log("⚠️ Could not discover BTC market - using fallback");
+    return disabledMarket("dummy_btc_fallback", "btc-updown-15m-fallback", "BTC Trading Disabled");
+  });
+  seenIds.add(btc.conditionId);
+
+  const solana = enableSolana
+    ? await discoverMarket(api, "solana", now, seenIds, false, series.SOL ?? null).catch(() => {
+        log("⚠️ Could not discover Solana market - using fallback");
+        return disabledMarket("dummy_solana_fallback", "solana-updown-15m-fallback", "Solana Trading Disabled");
+      })
+    : disabledMarket("dummy_solana_fallback", "solana-updown-15m-fallback", "Solana Trading Disabled");
+
+  const xrp = enableXrp
+    ? await discoverMarket(api, "xrp", now, seenIds, false, series.XRP ?? null).catch(() => {
+        log("⚠️ Could not discover XRP market - using fallback");
+        return disabledMarket("dummy_xrp_fallback", "xrp-updown-15m-fallback", "XRP Trading Disabled");
+      })
+    : disabledMarket("dummy_xrp_fallback", "xrp-updown-15m-fallback", "XRP Trading Disabled");
+
+  return { eth, btc, solana, xrp };
+}
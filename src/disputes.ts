@@ -0,0 +1,55 @@
+import { formatTime, formatUsd } from "./money.js";
+import type { ClosedPositionRecord } from "./reports.js";
+import type { Storage } from "./storage.js";
+
+export const DISPUTES_FILE = "disputes.jsonl";
+
+/** A dispute opening or ending on a held position, as written to `disputes.jsonl` */
+export interface DisputeRecord {
+  ts: number;
+  kind: "opened" | "resolved";
+  position_id: string;
+  period_timestamp: number;
+  token_type: string;
+  condition_id: string;
+  /** UMA status when seen */
+  status: string | null;
+}
+
+/**
+ * Whether a market's UMA resolution is contested: disputed now, or disputed earlier and not final yet
+ * (a re-proposal after a dispute can still be escalated to a vote).
+ */
+export function isUnderDispute(uma: { status: string | null; history: string[] }): boolean {
+  return uma.status === "disputed" || (uma.history.includes("disputed") && uma.status !== "resolved");
+}
+
+/** Positions whose market was disputed: still held back, or settled after the dispute with their PnL */
+export function disputesReport(storage: Storage): string {
+  const byPosition = new Map<string, { opened: DisputeRecord; resolved: DisputeRecord | null }>();
+  for (const r of storage.readJsonl<DisputeRecord>(DISPUTES_FILE)) {
+    if (r.kind === "opened" && !byPosition.has(r.position_id)) byPosition.set(r.position_id, { opened: r, resolved: null });
+    else if (r.kind === "resolved") {
+      const d = byPosition.get(r.position_id);
+      if (d) d.resolved = r;
+    }
+  }
+  const lines = ["UMA disputes on held positions"];
+  if (byPosition.size === 0) {
+    lines.push("   (none - enable uma_dispute_check to watch for them)");
+    return lines.join("\n");
+  }
+  const closed = new Map(
+    storage
+      .readJsonl<ClosedPositionRecord>("positions.jsonl")
+      .filter((p) => p.position_id != null)
+      .map((p) => [p.position_id as string, p] as [string, ClosedPositionRecord])
+  );
+  for (const [id, d] of byPosition) {
+    const settled = closed.get(id);
+    const state = settled ? `settled ${formatUsd(settled.realized_pnl)}` : d.resolved ? "dispute over, awaiting settlement" : "DISPUTED - settlement held";
+    const held = d.resolved ? ` (held ${Math.round((d.resolved.ts - d.opened.ts) / 60)} min)` : "";
+    lines.push(`   ${id.padEnd(10)} ${d.opened.token_type.padEnd(10)} period ${formatTime(d.opened.period_timestamp * 1000)}  ${state}${held}`);
+  }
+  return lines.join("\n");
+}
@@ -0,0 +1,205 @@
+import type { PolymarketApi } from "./api.js";
+import { clock } from "./clock.js";
+import type { TradeEvent, TradeEventBus } from "./events.js";
+import { formatTime, formatUsd } from "./money.js";
+import type { SimFillRule } from "./sim-fill.js";
+import type { Storage } from "./storage.js";
+import type { MarketSnapshot, TokenType } from "./types.js";
+import { marketForTokenType, tokenPriceForType } from "./types.js";
+
+const DIVERGENCE_FILE = "divergence.jsonl";
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** One live position buy, mirrored by a simulated order at the same limit and size */
+interface ShadowOrder {
+  period_timestamp: number;
+  token_type: TokenType;
+  price: number;
+  units: number;
+  condition_id: string | null;
+  token_id: string | null;
+  sim_filled: boolean;
+  /** Simulated fills are held to resolution: true/false once the market named a winner */
+  won: boolean | null;
+  live_filled: boolean;
+  live_fill_price: number | null;
+  live_pnl: number;
+  live_open: boolean;
+}
+
+/** Live vs simulated results of one period, as written to `divergence.jsonl` */
+export interface DivergenceRecord {
+  ts: number;
+  period_timestamp: number;
+  orders: number;
+  live_filled: number;
+  sim_filled: number;
+  live_avg_fill_price: number | null;
+  sim_avg_fill_price: number | null;
+  /** Realized PnL of the live positions, exits included */
+  live_pnl: number;
+  /** Simulated fills held to resolution */
+  sim_pnl: number;
+}
+
+/**
+ * Runs the simulator alongside live trading: every live position buy gets a simulated twin at the same
+ * limit and size, filled by the `SimFillRule` against the same snapshots and held to resolution. Once
+ * a period's live positions are closed and its markets resolved, fill rate, fill price and PnL of both
+ * are written to `divergence.jsonl`, showing how optimistic the simulation assumptions are.
+ */
+export class DivergenceMonitor {
+  private api: PolymarketApi;
+  private storage: Storage;
+  private simFill: SimFillRule;
+  private requiresPrint: boolean;
+  private checkIntervalMs: number;
+  private orders: Map<string, ShadowOrder> = new Map();
+  /** position_id -> order key */
+  private positions: Map<string, string> = new Map();
+  private lastCheckMs = 0;
+
+  constructor(api: PolymarketApi, storage: Storage, events: TradeEventBus, simFill: SimFillRule, requiresPrint: boolean, checkIntervalMs: number) {
+    this.api = api;
+    this.storage = storage;
+    this.simFill = simFill;
+    this.requiresPrint = requiresPrint;
+    this.checkIntervalMs = checkIntervalMs;
+    events.subscribe((event) => this.onEvent(event));
+  }
+
+  private onEvent(event: TradeEvent): void {
+    if (!event.position_id) return;
+    const key = `${event.period_timestamp}_${event.token_type}`;
+    const exitOrder = event.reason === "spread_capture" || event.reason?.startsWith("oco");
+    if (event.kind === "placed" && event.price != null && event.units != null) {
+      if (exitOrder) return;
+      const order = this.orders.get(key);
+      if (order) {
+        // A re-entry or add in the same token: the twin grows at the average limit.
+        order.price = (order.price * order.units + event.price * event.units) / (order.units + event.units);
+        order.units += event.units;
+      } else {
+        this.orders.set(key, {
+          period_timestamp: event.period_timestamp,
+          token_type: event.token_type,
+          price: event.price,
+          units: event.units,
+          condition_id: null,
+          token_id: null,
+          sim_filled: false,
+          won: null,
+          live_filled: false,
+          live_fill_price: null,
+          live_pnl: 0,
+          live_open: true,
+        });
+      }
+      this.positions.set(event.position_id, key);
+      return;
+    }
+    const order = this.orders.get(this.positions.get(event.position_id) ?? "");
+    if (!order) return;
+    if (event.kind === "filled" && !exitOrder && event.detail !== "SELL") {
+      order.live_filled = true;
+      order.live_fill_price = event.price ?? order.price;
+    } else if (event.kind === "closed") {
+      order.live_pnl += event.pnl ?? 0;
+      order.live_open = false;
+    } else if (event.kind === "cancelled" && !order.live_filled) {
+      order.live_open = false;
+    }
+  }
+
+  /** Fill twins against this snapshot; settle and report finished periods */
+  async onSnapshot(snapshot: MarketSnapshot): Promise<void> {
+    for (const order of this.orders.values()) {
+      if (order.period_timestamp !== snapshot.period_timestamp || order.sim_filled) continue;
+      const price = tokenPriceForType(snapshot, order.token_type);
+      order.condition_id ??= marketForTokenType(snapshot, order.token_type).condition_id;
+      order.token_id ??= price?.token_id ?? null;
+      if (!this.simFill.crosses("BUY", price?.ask ?? null, order.price)) continue;
+      if (this.requiresPrint && !this.simFill.crosses("BUY", price?.trade_stats?.low ?? null, order.price)) continue;
+      order.sim_filled = true;
+    }
+    const now = clock().now();
+    if (now - this.lastCheckMs < this.checkIntervalMs) return;
+    this.lastCheckMs = now;
+    const finished = [...new Set([...this.orders.values()].map((o) => o.period_timestamp))].filter((p) => p < snapshot.period_timestamp);
+    for (const period of finished) await this.settle(period);
+  }
+
+  private async settle(period: number): Promise<void> {
+    const orders = [...this.orders.entries()].filter(([, o]) => o.period_timestamp === period);
+    for (const [, o] of orders) {
+      if (!o.sim_filled || o.won != null || !o.condition_id) continue;
+      try {
+        const market = await this.api.getMarketByConditionId(o.condition_id);
+        const token = market.tokens.find((t) => (t.token_id ?? t.tokenId) === o.token_id);
+        if (market.tokens.some((t) => t.winner)) o.won = token?.winner === true;
+      } catch {
+        return;
+      }
+    }
+    if (orders.some(([, o]) => o.live_open || (o.sim_filled && o.won == null && o.condition_id))) return;
+    const live = orders.filter(([, o]) => o.live_filled).map(([, o]) => o);
+    const sim = orders.filter(([, o]) => o.sim_filled).map(([, o]) => o);
+    const avg = (os: ShadowOrder[], price: (o: ShadowOrder) => number) => {
+      const units = os.reduce((s, o) => s + o.units, 0);
+      return units > 0 ? os.reduce((s, o) => s + price(o) * o.units, 0) / units : null;
+    };
+    const record: DivergenceRecord = {
+      ts: Math.floor(clock().now() / 1000),
+      period_timestamp: period,
+      orders: orders.length,
+      live_filled: live.length,
+      sim_filled: sim.length,
+      live_avg_fill_price: avg(live, (o) => o.live_fill_price ?? o.price),
+      sim_avg_fill_price: avg(sim, (o) => o.price),
+      live_pnl: orders.reduce((s, [, o]) => s + o.live_pnl, 0),
+      sim_pnl: sim.reduce((s, o) => s + ((o.won ? 1 : 0) - o.price) * o.units, 0),
+    };
+    this.storage.appendJsonl(DIVERGENCE_FILE, record);
+    log(
+      `🪞 Live vs sim ${formatTime(period * 1000)}: fills ${record.live_filled}/${record.orders} vs ${record.sim_filled}/${record.orders}, ` +
+        `PnL ${formatUsd(record.live_pnl)} vs ${formatUsd(record.sim_pnl)}`
+    );
+    for (const [key] of orders) this.orders.delete(key);
+    for (const [id, key] of this.positions) if (!this.orders.has(key)) this.positions.delete(id);
+  }
+}
+
+/** Live vs simulated fill rate, fill price and PnL per period, and how far simulation overstates live */
+export function divergenceReport(storage: Storage): string {
+  const rows = storage.readJsonl<DivergenceRecord>(DIVERGENCE_FILE);
+  const lines = [
+    "Live vs simulation on the same decisions",
+    "   period              fills live/sim   avg price live/sim        PnL live        PnL sim        sim - live",
+  ];
+  if (rows.length === 0) {
+    lines.push("   (no periods compared yet - enable divergence_monitor on a live run)");
+    return lines.join("\n");
+  }
+  const price = (p: number | null) => (p == null ? "  -  " : p.toFixed(3));
+  for (const r of rows.slice(-30)) {
+    lines.push(
+      `   ${formatTime(r.period_timestamp * 1000).padEnd(19)} ${`${r.live_filled}/${r.sim_filled} of ${r.orders}`.padStart(13)}   ` +
+        `${`${price(r.live_avg_fill_price)}/${price(r.sim_avg_fill_price)}`.padStart(18)} ${formatUsd(r.live_pnl).padStart(14)} ` +
+        `${formatUsd(r.sim_pnl).padStart(14)} ${formatUsd(r.sim_pnl - r.live_pnl).padStart(17)}`
+    );
+  }
+  const orders = rows.reduce((s, r) => s + r.orders, 0);
+  const liveFills = rows.reduce((s, r) => s + r.live_filled, 0);
+  const simFills = rows.reduce((s, r) => s + r.sim_filled, 0);
+  const livePnl = rows.reduce((s, r) => s + r.live_pnl, 0);
+  const simPnl = rows.reduce((s, r) => s + r.sim_pnl, 0);
+  const rate = (n: number) => (orders > 0 ? `${((n / orders) * 100).toFixed(1)}%` : "-");
+  lines.push(
+    `   ${rows.length} period(s): fill rate live ${rate(liveFills)} vs sim ${rate(simFills)}, ` +
+      `PnL live ${formatUsd(livePnl)} vs sim ${formatUsd(simPnl)} (simulation overstates by ${formatUsd(simPnl - livePnl)})`
+  );
+  return lines.join("\n");
+}
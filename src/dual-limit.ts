@@ -0,0 +1,360 @@
+import type { Config } from "./config.js";
+import type { Direction } from "./direction.js";
+import { fairUpProbability } from "./fair-value.js";
+import type { BotRuntime } from "./runtime.js";
+import type { SpotFeed } from "./spot.js";
+import type { Strategy } from "./strategy.js";
+import type { Asset, BuyOpportunity, MarketSnapshot, TokenType } from "./types.js";
+import { assetForTokenType, tokenPriceForType } from "./types.js";
+
+export const LIMIT_PRICE = 0.45;
+export const DEFAULT_VOLATILITY_PER_SQRT_SEC = 0.0001;
+const PERIOD_DURATION = 900;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Fair value of the opportunity's token: spot model when a feed has the period open, else token mid, else 0.5 */
+function fairValue(opp: BuyOpportunity, snapshot: MarketSnapshot, spotFeed: SpotFeed | null, volatilityPerSqrtSec: number): number {
+  const asset = assetForTokenType(opp.token_type);
+  const spot = spotFeed?.price(asset) ?? null;
+  const open = spotFeed?.periodOpen(asset, snapshot.period_timestamp) ?? null;
+  let fair = 0.5;
+  if (spot != null && open != null) {
+    const up = fairUpProbability(spot, open, snapshot.time_remaining_seconds, volatilityPerSqrtSec);
+    fair = opp.token_type.endsWith("Up") ? up : 1 - up;
+  } else {
+    const price = tokenPriceForType(snapshot, opp.token_type);
+    if (price?.bid != null && price.ask != null) fair = (price.bid + price.ask) / 2;
+  }
+  return fair;
+}
+
+/** Expected edge of a limit buy: fair value minus the limit price */
+function opportunityEdge(
+  opp: BuyOpportunity,
+  snapshot: MarketSnapshot,
+  spotFeed: SpotFeed | null,
+  volatilityPerSqrtSec: number
+): number {
+  return fairValue(opp, snapshot, spotFeed, volatilityPerSqrtSec) - opp.bid_price;
+}
+
+/** Components of a limit buy's expected value per share */
+interface ExpectedValue {
+  fair: number;
+  /** From the fill model; 1 (assume it fills) when none is trained */
+  fill_probability: number;
+  fee: number;
+  ev: number;
+}
+
+/** EV per share of a limit buy: P(fill) x (fair value - limit price - fee), fee at `taker_fee_rate` of the price */
+function expectedValue(
+  opp: BuyOpportunity,
+  snapshot: MarketSnapshot,
+  runtime: BotRuntime,
+  feeRate: number,
+  volatilityPerSqrtSec: number
+): ExpectedValue {
+  const fair = fairValue(opp, snapshot, runtime.spotFeed, volatilityPerSqrtSec);
+  const fillProbability =
+    runtime.estimateFillProbability({
+      token_type: opp.token_type,
+      price: opp.bid_price,
+      spread: opp.spread ?? null,
+      period_timestamp: opp.period_timestamp,
+    }) ?? 1;
+  const fee = feeRate * opp.bid_price;
+  return { fair, fill_probability: fillProbability, fee, ev: fillProbability * (fair - opp.bid_price - fee) };
+}
+
+/** Order period-start submissions: by configured asset list, or largest edge first ("edge"); default keeps BTC, ETH, SOL, XRP */
+function prioritizeOpportunities(
+  opps: BuyOpportunity[],
+  snapshot: MarketSnapshot,
+  priority: Asset[] | "edge" | null,
+  spotFeed: SpotFeed | null,
+  volatilityPerSqrtSec: number
+): BuyOpportunity[] {
+  if (priority === "edge") {
+    const edges = new Map<BuyOpportunity, number>(
+      opps.map((o) => [o, opportunityEdge(o, snapshot, spotFeed, volatilityPerSqrtSec)] as [BuyOpportunity, number])
+    );
+    return [...opps].sort((a, b) => (edges.get(b) ?? 0) - (edges.get(a) ?? 0));
+  }
+  if (!priority || priority.length === 0) return opps;
+  const rank = (o: BuyOpportunity) => {
+    const idx = priority.indexOf(assetForTokenType(o.token_type));
+    return idx === -1 ? priority.length : idx;
+  };
+  return [...opps].sort((a, b) => rank(a) - rank(b));
+}
+
+function buildOpportunities(
+  snapshot: MarketSnapshot,
+  limitPrice: number,
+  enableEth: boolean,
+  enableSolana: boolean,
+  enableXrp: boolean,
+  reason: string
+): BuyOpportunity[] {
+  const opps: BuyOpportunity[] = [];
+  const period = snapshot.period_timestamp;
+  const timeRem = snapshot.time_remaining_seconds;
+  const timeElapsed = PERIOD_DURATION - timeRem;
+
+  const decisionMs = Date.now();
+  const add = (conditionId: string, tokenId: string, tokenType: TokenType) => {
+    const price = tokenPriceForType(snapshot, tokenType);
+    opps.push({
+      condition_id: conditionId,
+      token_id: tokenId,
+      token_type: tokenType,
+      bid_price: limitPrice,
+      period_timestamp: period,
+      time_remaining_seconds: timeRem,
+      time_elapsed_seconds: timeElapsed,
+      use_market_order: false,
+      decision_ms: decisionMs,
+      reason,
+      spread: price?.bid != null && price.ask != null ? price.ask - price.bid : null,
+    });
+  };
+
+  if (snapshot.btc_market.up_token) add(snapshot.btc_market.condition_id, snapshot.btc_market.up_token.token_id, "BtcUp");
+  if (snapshot.btc_market.down_token) add(snapshot.btc_market.condition_id, snapshot.btc_market.down_token.token_id, "BtcDown");
+  if (enableEth) {
+    if (snapshot.eth_market.up_token) add(snapshot.eth_market.condition_id, snapshot.eth_market.up_token.token_id, "EthUp");
+    if (snapshot.eth_market.down_token) add(snapshot.eth_market.condition_id, snapshot.eth_market.down_token.token_id, "EthDown");
+  }
+  if (enableSolana) {
+    if (snapshot.solana_market.up_token) add(snapshot.solana_market.condition_id, snapshot.solana_market.up_token.token_id, "SolanaUp");
+    if (snapshot.solana_market.down_token) add(snapshot.solana_market.condition_id, snapshot.solana_market.down_token.token_id, "SolanaDown");
+  }
+  if (enableXrp) {
+    if (snapshot.xrp_market.up_token) add(snapshot.xrp_market.condition_id, snapshot.xrp_market.up_token.token_id, "XrpUp");
+    if (snapshot.xrp_market.down_token) add(snapshot.xrp_market.condition_id, snapshot.xrp_market.down_token.token_id, "XrpDown");
+  }
+  return opps;
+}
+
+/**
+ * The dual limit-start strategy: at each period start, limit buys for the enabled assets' Up and Down at
+ * `dual_limit_price`, filtered by book imbalance, the direction signal and the EV gate. Also re-enters
+ * after stop-outs, places markets that appear late, and with `catch_up` evaluates the period in progress
+ * at launch. `companions` (grid, latency arb, trackers, ...) run first on every snapshot.
+ */
+export class DualLimitStrategy implements Strategy {
+  readonly name = "dual_limit_045";
+  private trading: Config["trading"];
+  private observe: boolean;
+  private companions: Strategy[];
+  private limitPrice: number;
+  private limitShares: number | null;
+  private volatility: number;
+  private catchUp: Config["trading"]["catch_up"];
+  private lastPlacedPeriod: number | null = null;
+  private lastSeenPeriod: number | null = null;
+  private startupPeriod: number | null = null;
+
+  constructor(trading: Config["trading"], observe: boolean, companions: Strategy[]) {
+    this.trading = trading;
+    this.observe = observe;
+    this.companions = companions;
+    this.limitPrice = trading.dual_limit_price ?? LIMIT_PRICE;
+    this.limitShares = trading.dual_limit_shares ?? null;
+    this.volatility = trading.fair_value_volatility_per_sqrt_sec ?? DEFAULT_VOLATILITY_PER_SQRT_SEC;
+    this.catchUp = trading.catch_up ?? null;
+  }
+
+  async onSnapshot(snapshot: MarketSnapshot, runtime: BotRuntime): Promise<void> {
+    for (const companion of this.companions) await runtime.isolate(companion.name, () => companion.onSnapshot(snapshot, runtime));
+    if (!this.observe && snapshot.time_remaining_seconds > (this.trading.min_time_remaining_seconds ?? 0)) {
+      await this.reenterAfterStops(snapshot, runtime);
+    }
+
+    if (snapshot.time_remaining_seconds === 0) {
+      return;
+    }
+
+    if (this.lastSeenPeriod === null) {
+      this.lastSeenPeriod = snapshot.period_timestamp;
+      this.startupPeriod = snapshot.period_timestamp;
+      if (!this.catchUp) return;
+    }
+    this.lastSeenPeriod = snapshot.period_timestamp;
+
+    const timeElapsed = PERIOD_DURATION - snapshot.time_remaining_seconds;
+    // A market that appeared late is still placed on its first snapshot; prior placements are filtered below.
+    const lateEntry = runtime.hasLateMarket() && snapshot.time_remaining_seconds > (this.trading.min_time_remaining_seconds ?? 0);
+    // Launched mid-period: evaluate the period in progress once instead of waiting for the next one.
+    const catchUpEntry =
+      this.catchUp != null &&
+      timeElapsed > 2 &&
+      this.lastPlacedPeriod === null &&
+      snapshot.period_timestamp === this.startupPeriod &&
+      timeElapsed <= (this.catchUp.max_elapsed_minutes ?? 5) * 60 &&
+      snapshot.time_remaining_seconds > (this.trading.min_time_remaining_seconds ?? 0);
+    if (this.observe || (timeElapsed > 2 && !lateEntry && !catchUpEntry)) {
+      return;
+    }
+
+    if (this.lastPlacedPeriod === snapshot.period_timestamp && !lateEntry) {
+      return;
+    }
+    this.lastPlacedPeriod = snapshot.period_timestamp;
+    runtime.clearLateMarket();
+
+    const opportunities = buildOpportunities(
+      snapshot,
+      this.limitPrice,
+      this.trading.enable_eth_trading,
+      this.trading.enable_solana_trading,
+      this.trading.enable_xrp_trading,
+      lateEntry ? "late_market_limit" : catchUpEntry ? "catch_up_limit" : "period_start_limit"
+    );
+    await this.filter(opportunities, snapshot, runtime, catchUpEntry);
+    if (opportunities.length === 0) {
+      return;
+    }
+
+    if (catchUpEntry) log(`⏩ Started ${Math.round(timeElapsed / 60)} min into the period - catching up with limit buys at $${this.limitPrice.toFixed(2)}`);
+    else log(`🎯 Market start detected - placing limit buys at $${this.limitPrice.toFixed(2)}`);
+    await this.place(opportunities, snapshot, runtime);
+  }
+
+  async onFinish(runtime: BotRuntime): Promise<void> {
+    for (const companion of this.companions) {
+      if (companion.onFinish) await runtime.isolate(companion.name, () => companion.onFinish?.(runtime));
+    }
+  }
+
+  /** Buy back tokens stopped out this period once the trader allows re-entry */
+  private async reenterAfterStops(snapshot: MarketSnapshot, runtime: BotRuntime): Promise<void> {
+    const { trader } = runtime;
+    const candidates = buildOpportunities(
+      snapshot,
+      this.limitPrice,
+      this.trading.enable_eth_trading,
+      this.trading.enable_solana_trading,
+      this.trading.enable_xrp_trading,
+      "reentry_after_stop"
+    );
+    for (const opp of candidates) {
+      if (!trader.canReenter(opp.period_timestamp, opp.token_type, snapshot)) continue;
+      log(`🔁 Re-entering ${opp.token_type} after stop-out`);
+      trader.noteReentry(opp.period_timestamp, opp.token_type);
+      try {
+        await trader.executeLimitBuy(opp, this.limitPrice, this.limitShares);
+      } catch (e) {
+        log("Error executing re-entry limit buy: " + String(e));
+      }
+    }
+  }
+
+  /** Drop opportunities against a heavy ask book, the direction signal, a missing period open (catch-up) or the EV gate */
+  private async filter(opportunities: BuyOpportunity[], snapshot: MarketSnapshot, runtime: BotRuntime, catchUpEntry: boolean): Promise<void> {
+    const { spotFeed } = runtime;
+    const maxImbalance = this.trading.max_opposing_book_imbalance;
+    if (maxImbalance != null) {
+      for (let i = opportunities.length - 1; i >= 0; i--) {
+        const imbalance = tokenPriceForType(snapshot, opportunities[i].token_type)?.book_imbalance ?? null;
+        if (imbalance != null && -imbalance >= maxImbalance) {
+          log(`⚖️ Skipping ${opportunities[i].token_type}: book imbalance ${imbalance.toFixed(2)} (heavy asks)`);
+          opportunities.splice(i, 1);
+        }
+      }
+    }
+    const signal = runtime.directionSignal;
+    if (signal) {
+      const directions = new Map<Asset, Direction>();
+      for (const asset of new Set(opportunities.map((o) => assetForTokenType(o.token_type)))) {
+        let direction: Direction = null;
+        try {
+          direction = await signal.direction(asset, snapshot);
+        } catch (e) {
+          log(`⚠️ Direction signal ${signal.name} failed for ${asset}: ${String(e)}`);
+        }
+        directions.set(asset, direction);
+      }
+      const whenUnknown = this.trading.one_sided?.when_unknown ?? "skip";
+      for (let i = opportunities.length - 1; i >= 0; i--) {
+        const opp = opportunities[i];
+        const direction = directions.get(assetForTokenType(opp.token_type)) ?? null;
+        const side = opp.token_type.endsWith("Up") ? "up" : "down";
+        if (direction == null ? whenUnknown === "both" : direction === side) {
+          opp.note = [opp.note, `direction=${direction ?? "none"} (${signal.name})`].filter(Boolean).join(" ");
+          continue;
+        }
+        log(`🧭 Skipping ${opp.token_type}: ${signal.name} says ${direction ?? "no direction"}`);
+        opportunities.splice(i, 1);
+      }
+    }
+    if (catchUpEntry && spotFeed) {
+      // The EV gate needs the period's real open: a print seen after launch would put fair value near 0.5.
+      for (const asset of new Set(opportunities.map((o) => assetForTokenType(o.token_type)))) {
+        if ((await spotFeed.loadPeriodOpen(asset, snapshot.period_timestamp)) != null) continue;
+        log(`⏩ No ${asset} open for the period in progress from ${spotFeed.name} - not catching up on ${asset}`);
+        for (let i = opportunities.length - 1; i >= 0; i--) if (assetForTokenType(opportunities[i].token_type) === asset) opportunities.splice(i, 1);
+      }
+    }
+    // Entering late, the limit is no longer the period-start price the strategy was sized for: always gate on EV.
+    const minEv =
+      catchUpEntry ? (this.catchUp?.min_expected_value ?? this.trading.min_expected_value ?? 0) : this.trading.min_expected_value;
+    if (minEv != null) {
+      const feeRate = this.trading.taker_fee_rate ?? 0;
+      for (let i = opportunities.length - 1; i >= 0; i--) {
+        const opp = opportunities[i];
+        const ev = expectedValue(opp, snapshot, runtime, feeRate, this.volatility);
+        const take = ev.ev >= minEv;
+        log(
+          `${take ? "📈" : "📉"} ${opp.token_type} EV $${ev.ev.toFixed(4)}/share = P(fill) ${ev.fill_probability.toFixed(2)} x ` +
+            `(fair ${ev.fair.toFixed(4)} - limit ${opp.bid_price.toFixed(2)} - fee ${ev.fee.toFixed(4)}) ${take ? ">=" : "<"} $${minEv.toFixed(4)}` +
+            (take ? "" : " - skipping")
+        );
+        const components = `ev=${ev.ev.toFixed(4)} fair=${ev.fair.toFixed(4)} p_fill=${ev.fill_probability.toFixed(2)} fee=${ev.fee.toFixed(4)}`;
+        if (take) opp.note = [opp.note, components].filter(Boolean).join(" ");
+        else opportunities.splice(i, 1);
+      }
+    }
+  }
+
+  /** Submit in priority order, skipping tokens already placed or held, `order_concurrency` at a time */
+  private async place(opportunities: BuyOpportunity[], snapshot: MarketSnapshot, runtime: BotRuntime): Promise<void> {
+    const { trader, spotFeed } = runtime;
+    const ordered = prioritizeOpportunities(
+      await trader.withoutPriorPlacements(opportunities),
+      snapshot,
+      this.trading.order_priority ?? null,
+      spotFeed,
+      this.volatility
+    ).filter((opp) => !trader.hasActivePosition(opp.period_timestamp, opp.token_type));
+    if (runtime.journal) {
+      for (const opp of ordered) {
+        const ev = expectedValue(opp, snapshot, runtime, this.trading.taker_fee_rate ?? 0, this.volatility);
+        const asset = assetForTokenType(opp.token_type);
+        runtime.journal.record(opp, {
+          fair_value: ev.fair,
+          fill_probability: ev.fill_probability,
+          ev: ev.ev,
+          spread: opp.spread ?? null,
+          spot: spotFeed?.price(asset) ?? null,
+          period_open: spotFeed?.periodOpen(asset, snapshot.period_timestamp) ?? null,
+          time_remaining_seconds: snapshot.time_remaining_seconds,
+        });
+      }
+    }
+    const concurrency = Math.max(1, this.trading.order_concurrency ?? 1);
+    for (let i = 0; i < ordered.length; i += concurrency) {
+      await Promise.all(
+        ordered.slice(i, i + concurrency).map((opp) =>
+          trader.executeLimitBuy(opp, this.limitPrice, this.limitShares).catch((e) => {
+            log("Error executing limit buy: " + String(e));
+          })
+        )
+      );
+    }
+  }
+}
@@ -0,0 +1,110 @@
+import { fairUpProbability } from "./fair-value.js";
+import type { SpotFeed } from "./spot.js";
+import type { Storage } from "./storage.js";
+import type { Strategy } from "./strategy.js";
+import type { Asset, MarketSnapshot } from "./types.js";
+import { marketForTokenType } from "./types.js";
+
+const PERIOD_DURATION = 900;
+const SAMPLE_EVERY_SECONDS = 5;
+const UP_TYPES = { BTC: "BtcUp", ETH: "EthUp", SOL: "SolanaUp", XRP: "XrpUp" } as const;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+interface EdgeSample {
+  asset: Asset;
+  elapsed_seconds: number;
+  spot: number;
+  open: number;
+  fair_up: number;
+  up_ask: number | null;
+  down_ask: number | null;
+  /** fair value minus ask: what buying each side right now is expected to earn per share */
+  up_edge: number | null;
+  down_edge: number | null;
+}
+
+/** Token prices vs model fair value through each period, persisted per period to `edge/<period>.json` */
+export class EdgeDecayTracker implements Strategy {
+  readonly name = "edge_decay";
+  private spotFeed: SpotFeed;
+  private storage: Storage;
+  private volatility: number;
+  private period: number | null = null;
+  private samples: EdgeSample[] = [];
+  private lastSampleElapsed = -Infinity;
+
+  constructor(spotFeed: SpotFeed, storage: Storage, volatilityPerSqrtSec: number) {
+    this.spotFeed = spotFeed;
+    this.storage = storage;
+    this.volatility = volatilityPerSqrtSec;
+  }
+
+  onSnapshot(snapshot: MarketSnapshot): void {
+    if (this.period !== snapshot.period_timestamp) {
+      const finished = this.flush();
+      if (finished) log(finished);
+      this.period = snapshot.period_timestamp;
+      this.lastSampleElapsed = -Infinity;
+    }
+    const elapsed = PERIOD_DURATION - snapshot.time_remaining_seconds;
+    if (elapsed - this.lastSampleElapsed < SAMPLE_EVERY_SECONDS) return;
+    this.lastSampleElapsed = elapsed;
+    for (const asset of Object.keys(UP_TYPES) as Asset[]) {
+      const spot = this.spotFeed.price(asset);
+      const open = this.spotFeed.periodOpen(asset, snapshot.period_timestamp);
+      const market = marketForTokenType(snapshot, UP_TYPES[asset]);
+      if (spot == null || open == null || (!market.up_token && !market.down_token)) continue;
+      const fairUp = fairUpProbability(spot, open, snapshot.time_remaining_seconds, this.volatility);
+      const upAsk = market.up_token?.ask ?? null;
+      const downAsk = market.down_token?.ask ?? null;
+      this.samples.push({
+        asset,
+        elapsed_seconds: elapsed,
+        spot,
+        open,
+        fair_up: fairUp,
+        up_ask: upAsk,
+        down_ask: downAsk,
+        up_edge: upAsk != null ? fairUp - upAsk : null,
+        down_edge: downAsk != null ? 1 - fairUp - downAsk : null,
+      });
+    }
+  }
+
+  /** Persist the finished period with per-minute average best edge, and return a one-line summary */
+  flush(): string | null {
+    if (this.period == null || this.samples.length === 0) return null;
+    const byMinute: Record<string, Record<number, number>> = {};
+    for (const asset of Object.keys(UP_TYPES) as Asset[]) {
+      const buckets = new Map<number, number[]>();
+      for (const s of this.samples.filter((x) => x.asset === asset)) {
+        const best = Math.max(s.up_edge ?? -Infinity, s.down_edge ?? -Infinity);
+        if (!Number.isFinite(best)) continue;
+        const minute = Math.floor(s.elapsed_seconds / 60);
+        buckets.set(minute, [...(buckets.get(minute) ?? []), best]);
+      }
+      if (buckets.size === 0) continue;
+      byMinute[asset] = {};
+      for (const [minute, edges] of buckets) byMinute[asset][minute] = edges.reduce((a, b) => a + b, 0) / edges.length;
+    }
+    this.storage.writeJson(`edge/${this.period}.json`, {
+      period_timestamp: this.period,
+      avg_best_edge_by_minute: byMinute,
+      samples: this.samples,
+    });
+    const summary = Object.entries(byMinute)
+      .map(([asset, mins]) => `${asset} ${Object.entries(mins).map(([m, e]) => `m${m}:${e >= 0 ? "+" : ""}${e.toFixed(3)}`).join(" ")}`)
+      .join(" | ");
+    this.samples = [];
+    return `📐 Edge decay (period ${this.period}): ${summary}`;
+  }
+
+  /** Persist and log the last period (end of replay) */
+  onFinish(): void {
+    const finished = this.flush();
+    if (finished) log(finished);
+  }
+}
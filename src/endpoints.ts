@@ -0,0 +1,83 @@
+import axios from "axios";
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Failed because the endpoint is unhealthy (network error, timeout, 5xx), not because of the request itself */
+export function isEndpointFailure(e: unknown): boolean {
+  if (!axios.isAxiosError(e)) return false;
+  return !e.response || e.response.status >= 500;
+}
+
+/**
+ * Ordered base URLs for one API (primary first). After `failAfter` consecutive endpoint failures it
+ * moves to the next URL; while off the primary it probes `primary + probePath` every `probeIntervalMs`
+ * and fails back once that answers.
+ */
+export class EndpointPool {
+  readonly name: string;
+  private urls: string[];
+  private index = 0;
+  private consecutiveFailures = 0;
+  private failAfter: number;
+  private probePath: string;
+  private probeIntervalMs: number;
+  private lastProbeMs = 0;
+  private onSwitch: ((name: string, from: string, to: string) => void) | null = null;
+
+  constructor(name: string, urls: string[], failAfter: number, probePath: string, probeIntervalMs: number) {
+    this.name = name;
+    this.urls = urls.map((u) => u.replace(/\/$/, ""));
+    this.failAfter = Math.max(1, failAfter);
+    this.probePath = probePath;
+    this.probeIntervalMs = probeIntervalMs;
+  }
+
+  current(): string {
+    return this.urls[this.index];
+  }
+
+  onFailover(cb: (name: string, from: string, to: string) => void): void {
+    this.onSwitch = cb;
+  }
+
+  /** Run a request against the current URL, counting endpoint failures toward failover */
+  async request<T>(fn: (baseUrl: string) => Promise<T>): Promise<T> {
+    void this.maybeProbePrimary();
+    try {
+      const result = await fn(this.current());
+      this.consecutiveFailures = 0;
+      return result;
+    } catch (e) {
+      if (isEndpointFailure(e)) this.recordFailure();
+      throw e;
+    }
+  }
+
+  private recordFailure(): void {
+    this.consecutiveFailures++;
+    if (this.consecutiveFailures < this.failAfter || this.urls.length < 2) return;
+    this.switchTo((this.index + 1) % this.urls.length, `${this.consecutiveFailures} consecutive errors`);
+  }
+
+  private switchTo(index: number, reason: string): void {
+    const from = this.current();
+    this.index = index;
+    this.consecutiveFailures = 0;
+    this.lastProbeMs = Date.now();
+    log(`🔀 ${this.name} failover: ${from} -> ${this.current()} (${reason})`);
+    this.onSwitch?.(this.name, from, this.current());
+  }
+
+  private async maybeProbePrimary(): Promise<void> {
+    if (this.index === 0 || Date.now() - this.lastProbeMs < this.probeIntervalMs) return;
+    this.lastProbeMs = Date.now();
+    try {
+      await axios.get(`${this.urls[0]}${this.probePath}`, { timeout: 5_000 });
+      if (this.index !== 0) this.switchTo(0, "primary healthy again");
+    } catch {
+      /* stay on the fallback */
+    }
+  }
+}
@@ -0,0 +1,50 @@
+import type { TokenType } from "./types.js";
+
+/** One order or position event, as shown in the blotter */
+export interface TradeEvent {
+  ts_ms: number;
+  kind: "placed" | "rejected" | "skipped" | "filled" | "cancelled" | "exit" | "closed";
+  period_timestamp: number;
+  token_type: TokenType;
+  order_id?: string | null;
+  /** Position the order opened, added to or exited (`pos-N`); null for strategy-managed orders */
+  position_id?: string | null;
+  units?: number;
+  price?: number;
+  /** Exit reason (take_profit, stop_loss, ...) or rejection message */
+  detail?: string;
+  /** Leg PnL for `exit`, realized position PnL for `closed` */
+  pnl?: number;
+  /** Decision that placed the order (period_start_limit, latency_arb, grid_buy, ...) */
+  reason?: string;
+  note?: string | null;
+  /** Live `filled` events: whether the order made or took liquidity */
+  liquidity?: "maker" | "taker" | null;
+}
+
+/** Downstream consumer of trade events (database, analytics, ...); register with `BotRuntimeBuilder.eventSink` */
+export interface EventSink {
+  readonly name: string;
+  write(event: TradeEvent): unknown;
+}
+
+/** In-process fan-out of trade events; a throwing listener never affects the trader */
+export class TradeEventBus {
+  private listeners: Set<(event: TradeEvent) => void> = new Set();
+
+  /** Returns the unsubscribe function */
+  subscribe(listener: (event: TradeEvent) => void): () => void {
+    this.listeners.add(listener);
+    return () => this.listeners.delete(listener);
+  }
+
+  emit(event: TradeEvent): void {
+    for (const listener of this.listeners) {
+      try {
+        listener(event);
+      } catch {
+        /* listener's problem */
+      }
+    }
+  }
+}
@@ -0,0 +1,104 @@
+import { existsSync, mkdirSync, readFileSync, renameSync, writeFileSync } from "fs";
+import { dirname } from "path";
+import type { Notifier } from "./notifier.js";
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Lease file contents: which instance is placing orders and when it last said so */
+interface Lease {
+  instance_id: string;
+  ts_ms: number;
+}
+
+/**
+ * Primary/standby pair sharing `leaseFile` (a shared disk or mount). The active instance renews the
+ * lease every tick; a standby places no orders and takes over once the lease is older than
+ * `takeoverAfterMs`, after `reconcile` has dealt with the dead primary's resting orders. A primary that
+ * finds the lease held by someone else (it was taken over while hung) steps down to standby.
+ * Wall-clock based, like the heartbeat, so both machines need synced clocks.
+ */
+export class FailoverGuard {
+  private leaseFile: string;
+  private takeoverAfterMs: number;
+  private instanceId: string;
+  private active: boolean;
+  private notifier: Notifier | null;
+  private reconcile: () => Promise<void>;
+  private takingOver = false;
+
+  constructor(
+    leaseFile: string,
+    role: "primary" | "standby",
+    takeoverAfterMs: number,
+    reconcile: () => Promise<void>,
+    notifier: Notifier | null = null
+  ) {
+    this.leaseFile = leaseFile;
+    this.takeoverAfterMs = takeoverAfterMs;
+    this.instanceId = `${role}-${process.pid}-${Date.now().toString(36)}`;
+    this.active = role === "primary";
+    this.reconcile = reconcile;
+    this.notifier = notifier;
+    log(`🤝 Failover ${role} (${this.instanceId}), lease ${leaseFile}, takeover after ${takeoverAfterMs}ms`);
+  }
+
+  /** True while this instance must not place orders */
+  isStandby(): boolean {
+    return !this.active;
+  }
+
+  /** Once per loop iteration: renew the lease when active, else watch it and take over when stale */
+  async tick(): Promise<void> {
+    const lease = this.read();
+    if (lease === undefined) return;
+    const now = Date.now();
+    if (this.active) {
+      if (lease && lease.instance_id !== this.instanceId && now - lease.ts_ms < this.takeoverAfterMs) {
+        this.active = false;
+        log(`🤝 Lease held by ${lease.instance_id} - stepping down to standby`);
+        this.notifier?.alert("failover", "failover_standby", { instance: this.instanceId, holder: lease.instance_id });
+        return;
+      }
+      this.write(now);
+      return;
+    }
+    if (this.takingOver || (lease && now - lease.ts_ms < this.takeoverAfterMs)) return;
+    this.takingOver = true;
+    const age = lease ? `${now - lease.ts_ms}ms old` : "missing";
+    log(`🚨 Primary lease ${age} - reconciling open orders and taking over`);
+    try {
+      await this.reconcile();
+      this.write(Date.now());
+      this.active = true;
+      this.notifier?.alert("failover", "failover_takeover", { instance: this.instanceId, lease_age: age });
+    } catch (e) {
+      log(`⚠️ Takeover failed, staying on standby: ${String(e)}`);
+    } finally {
+      this.takingOver = false;
+    }
+  }
+
+  /** Null when there is no lease yet; undefined when it could not be read this tick (retried next tick) */
+  private read(): Lease | null | undefined {
+    if (!existsSync(this.leaseFile)) return null;
+    try {
+      return JSON.parse(readFileSync(this.leaseFile, "utf8")) as Lease;
+    } catch (e) {
+      log(`⚠️ Could not read failover lease ${this.leaseFile}: ${String(e)}`);
+      return undefined;
+    }
+  }
+
+  private write(nowMs: number): void {
+    try {
+      mkdirSync(dirname(this.leaseFile), { recursive: true });
+      const tmp = `${this.leaseFile}.${this.instanceId}.tmp`;
+      writeFileSync(tmp, JSON.stringify({ instance_id: this.instanceId, ts_ms: nowMs }) + "\n");
+      renameSync(tmp, this.leaseFile);
+    } catch (e) {
+      log(`⚠️ Could not renew failover lease ${this.leaseFile}: ${String(e)}`);
+    }
+  }
+}
@@ -0,0 +1,22 @@
+/** Standard normal CDF (Abramowitz-Stegun 7.1.26 erf approximation, |error| < 1.5e-7) */
+export function normalCdf(x: number): number {
+  const t = 1 / (1 + 0.3275911 * (Math.abs(x) / Math.SQRT2));
+  const poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+  const erf = 1 - poly * Math.exp(-(x * x) / 2);
+  return x >= 0 ? (1 + erf) / 2 : (1 - erf) / 2;
+}
+
+/**
+ * Fair probability that the period resolves Up: spot follows a driftless log-normal walk from now
+ * to the period end, so P(up) = Φ(ln(spot/open) / (σ·√t)) with σ the volatility per √second.
+ */
+export function fairUpProbability(
+  spot: number,
+  open: number,
+  secondsRemaining: number,
+  volatilityPerSqrtSec: number
+): number {
+  const logMove = Math.log(spot / open);
+  if (secondsRemaining <= 0 || volatilityPerSqrtSec <= 0) return logMove > 0 ? 1 : logMove < 0 ? 0 : 0.5;
+  return normalCdf(logMove / (volatilityPerSqrtSec * Math.sqrt(secondsRemaining)));
+}
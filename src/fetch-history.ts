@@ -0,0 +1,128 @@
+import { existsSync } from "fs";
+import type { PolymarketApi } from "./api.js";
+import type { Config } from "./config.js";
+import { periodSlugs } from "./discovery.js";
+import type { MonitoredMarkets } from "./monitor.js";
+import type { RecordedTick } from "./replay.js";
+import type { Storage } from "./storage.js";
+import type { MarketData, MarketSnapshot, TokenPrice } from "./types.js";
+import { upDownTokenIds } from "./types.js";
+
+const PERIOD_DURATION = 900;
+const TRADE_PAGE = 500;
+const MAX_TRADE_PAGES = 4;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+function round2(price: number): number {
+  return Math.round(price * 100) / 100;
+}
+
+/** A period's market for one asset with its price points, keyed by token side */
+interface FetchedMarket {
+  key: keyof MonitoredMarkets;
+  condition_id: string;
+  up: { token_id: string; points: Array<{ t: number; p: number }> } | null;
+  down: { token_id: string; points: Array<{ t: number; p: number }> } | null;
+}
+
+/**
+ * `--fetch-history <from> [to]`: download past periods' Up/Down markets into `snapshots/<period>.jsonl`
+ * so `--replay` can run before the recorder has collected anything. The CLOB keeps a price history per
+ * token (one point a minute), not books, so each tick's bid/ask is synthesized `halfSpread` around that
+ * price; fills in such a replay are optimistic and edges are coarser than in recorded data. Trade prints
+ * go to `trades/<period>.jsonl`. Periods that already have a snapshot file are left alone.
+ */
+export async function fetchHistory(
+  api: PolymarketApi,
+  storage: Storage,
+  trading: Config["trading"],
+  fromTs: number,
+  toTs: number,
+  halfSpread: number = 0.01
+): Promise<void> {
+  const keys: Array<keyof MonitoredMarkets> = ["btc"];
+  if (trading.enable_eth_trading) keys.push("eth");
+  if (trading.enable_solana_trading) keys.push("solana");
+  if (trading.enable_xrp_trading) keys.push("xrp");
+  const first = Math.floor(fromTs / PERIOD_DURATION) * PERIOD_DURATION;
+  let written = 0;
+  for (let period = first; period + PERIOD_DURATION <= toTs; period += PERIOD_DURATION) {
+    if (existsSync(storage.path("snapshots", `${period}.jsonl`))) {
+      log(`⏭️ ${period}: snapshots already on disk`);
+      continue;
+    }
+    const markets: FetchedMarket[] = [];
+    for (const key of keys) {
+      const market = await fetchMarket(api, key, period);
+      if (market) markets.push(market);
+    }
+    if (markets.length === 0) {
+      log(`⚠️ ${period} (${new Date(period * 1000).toISOString()}): no markets found`);
+      continue;
+    }
+    const ticks = buildTicks(period, markets, halfSpread);
+    for (const tick of ticks) storage.appendJsonl(`snapshots/${period}.jsonl`, tick);
+    let trades = 0;
+    for (const m of markets) {
+      for (let page = 0; page < MAX_TRADE_PAGES; page++) {
+        const prints = await api.getTrades(m.condition_id, TRADE_PAGE, page * TRADE_PAGE).catch(() => []);
+        for (const p of prints) storage.appendJsonl(`trades/${period}.jsonl`, { ...p, condition_id: m.condition_id });
+        trades += prints.length;
+        if (prints.length < TRADE_PAGE) break;
+      }
+    }
+    written++;
+    log(`📥 ${period} (${new Date(period * 1000).toISOString()}): ${markets.length} market(s), ${ticks.length} tick(s), ${trades} trade(s)`);
+  }
+  log(`✅ Fetched ${written} period(s) into ${storage.path("snapshots")}`);
+}
+
+async function fetchMarket(api: PolymarketApi, key: keyof MonitoredMarkets, period: number): Promise<FetchedMarket | null> {
+  for (const slug of periodSlugs(key, period)) {
+    try {
+      const market = await api.getMarketBySlug(slug);
+      const { upId, downId } = upDownTokenIds(market);
+      const side = async (tokenId: string | null) =>
+        tokenId ? { token_id: tokenId, points: await api.getPriceHistory(tokenId, period, period + PERIOD_DURATION, 1) } : null;
+      return { key, condition_id: market.conditionId, up: await side(upId), down: await side(downId) };
+    } catch {
+      /* try the next prefix */
+    }
+  }
+  return null;
+}
+
+/** One tick per minute that has a price for any token; each token carries its latest price forward */
+function buildTicks(period: number, markets: FetchedMarket[], halfSpread: number): RecordedTick[] {
+  const times = new Set<number>();
+  for (const m of markets) for (const p of [...(m.up?.points ?? []), ...(m.down?.points ?? [])]) times.add(p.t);
+  const quote = (side: FetchedMarket["up"], t: number): TokenPrice | null => {
+    if (!side) return null;
+    let last: number | null = null;
+    for (const p of side.points) if (p.t <= t) last = p.p;
+    if (last == null) return { token_id: side.token_id, bid: null, ask: null };
+    return { token_id: side.token_id, bid: Math.max(0.01, round2(last - halfSpread)), ask: Math.min(0.99, round2(last + halfSpread)) };
+  };
+  const empty: MarketData = { condition_id: "", up_token: null, down_token: null };
+  return [...times]
+    .filter((t) => t >= period && t < period + PERIOD_DURATION)
+    .sort((a, b) => a - b)
+    .map((t) => {
+      const data = (key: keyof MonitoredMarkets): MarketData => {
+        const m = markets.find((x) => x.key === key);
+        return m ? { condition_id: m.condition_id, up_token: quote(m.up, t), down_token: quote(m.down, t) } : empty;
+      };
+      const snapshot: MarketSnapshot = {
+        btc_market: data("btc"),
+        eth_market: data("eth"),
+        solana_market: data("solana"),
+        xrp_market: data("xrp"),
+        time_remaining_seconds: period + PERIOD_DURATION - t,
+        period_timestamp: period,
+      };
+      return { ts_ms: t * 1000, snapshot };
+    });
+}
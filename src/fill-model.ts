@@ -0,0 +1,164 @@
+import type { Storage } from "./storage.js";
+import type { Asset, TokenType } from "./types.js";
+import { assetForTokenType } from "./types.js";
+
+/** Trained weights, relative to `data_dir` */
+export const FILL_MODEL_FILE = "fill_model.json";
+
+const FEATURES = ["bias", "price", "spread", "eth", "sol", "xrp", "hour_sin", "hour_cos"];
+const ITERATIONS = 2000;
+const LEARNING_RATE = 0.5;
+const L2 = 0.001;
+/** Fewer labelled placements than this and the fit is noise */
+const MIN_SAMPLES = 30;
+const CALIBRATION_BINS = 5;
+
+/** Inputs of one fill-probability estimate */
+export interface FillQuery {
+  token_type: TokenType;
+  /** Limit price */
+  price: number;
+  /** Ask - bid when the order is placed; null when a side is missing */
+  spread: number | null;
+  /** Period start (epoch seconds), for time of day */
+  period_timestamp: number;
+}
+
+interface SavedModel {
+  trained_at: number;
+  samples: number;
+  fill_rate: number;
+  features: string[];
+  weights: number[];
+  /** Mean spread in the training set, used when a query has none */
+  mean_spread: number;
+}
+
+/** Placement row fields the model uses (written by Trader) */
+interface PlacementRow {
+  period_timestamp: number;
+  token_type: TokenType;
+  price?: number;
+  spread?: number | null;
+  fill_probability?: number | null;
+}
+
+function features(q: FillQuery, meanSpread: number): number[] {
+  const asset: Asset = assetForTokenType(q.token_type);
+  const hour = ((q.period_timestamp % 86_400) / 3600) * ((2 * Math.PI) / 24);
+  return [1, q.price, q.spread ?? meanSpread, asset === "ETH" ? 1 : 0, asset === "SOL" ? 1 : 0, asset === "XRP" ? 1 : 0, Math.sin(hour), Math.cos(hour)];
+}
+
+function sigmoid(z: number): number {
+  return 1 / (1 + Math.exp(-z));
+}
+
+function predict(weights: number[], x: number[]): number {
+  return sigmoid(x.reduce((sum, v, i) => sum + v * weights[i], 0));
+}
+
+/** Period-start placements with a recorded limit price, labelled by whether they filled */
+function labelledPlacements(storage: Storage): Array<{ row: PlacementRow & { price: number }; filled: boolean }> {
+  const key = (r: { period_timestamp: number; token_type: string }) => `${r.period_timestamp}_${r.token_type}`;
+  const filled = new Set(storage.readJsonl<{ period_timestamp: number; token_type: string }>("fills.jsonl").map(key));
+  return storage
+    .readJsonl<PlacementRow>("placements.jsonl")
+    .filter((r): r is PlacementRow & { price: number } => typeof r.price === "number")
+    .map((row) => ({ row, filled: filled.has(key(row)) }));
+}
+
+/**
+ * Logistic model of whether a limit buy fills within its period, from limit price, spread at placement,
+ * asset and time of day. Trained offline from `placements.jsonl` + `fills.jsonl` (`--train-fill-model`),
+ * loaded at startup and queried by strategies through `BotRuntime.estimateFillProbability`.
+ */
+export class FillProbabilityModel {
+  private model: SavedModel;
+
+  private constructor(model: SavedModel) {
+    this.model = model;
+  }
+
+  /** The model trained into `data_dir`, or null when none was trained */
+  static load(storage: Storage): FillProbabilityModel | null {
+    const model = storage.readJson<SavedModel>(FILL_MODEL_FILE);
+    if (!model || model.features.join() !== FEATURES.join()) return null;
+    return new FillProbabilityModel(model);
+  }
+
+  /** Fit on everything recorded so far and save to `fill_model.json`; returns a one-line fit summary */
+  static train(storage: Storage): string {
+    const data = labelledPlacements(storage);
+    if (data.length < MIN_SAMPLES) {
+      throw new Error(`Only ${data.length} placement(s) with a recorded limit price; need at least ${MIN_SAMPLES} to train`);
+    }
+    const spreads = data.map((d) => d.row.spread).filter((s): s is number => s != null);
+    const meanSpread = spreads.length > 0 ? spreads.reduce((s, v) => s + v, 0) / spreads.length : 0.01;
+    const xs = data.map((d) => features({ ...d.row, spread: d.row.spread ?? null }, meanSpread));
+    const ys = data.map((d) => (d.filled ? 1 : 0));
+    const weights = new Array<number>(FEATURES.length).fill(0);
+    for (let iter = 0; iter < ITERATIONS; iter++) {
+      const grad = new Array<number>(FEATURES.length).fill(0);
+      for (let i = 0; i < xs.length; i++) {
+        const err = predict(weights, xs[i]) - ys[i];
+        for (let j = 0; j < weights.length; j++) grad[j] += err * xs[i][j];
+      }
+      for (let j = 0; j < weights.length; j++) {
+        weights[j] -= LEARNING_RATE * (grad[j] / xs.length + (j === 0 ? 0 : L2 * weights[j]));
+      }
+    }
+    const logLoss =
+      -xs.reduce((sum, x, i) => {
+        const p = Math.min(1 - 1e-9, Math.max(1e-9, predict(weights, x)));
+        return sum + (ys[i] ? Math.log(p) : Math.log(1 - p));
+      }, 0) / xs.length;
+    const fillRate = ys.reduce((s: number, y) => s + y, 0) / ys.length;
+    storage.writeJson(FILL_MODEL_FILE, {
+      trained_at: Date.now(),
+      samples: data.length,
+      fill_rate: fillRate,
+      features: FEATURES,
+      weights,
+      mean_spread: meanSpread,
+    } satisfies SavedModel);
+    return `Trained on ${data.length} placement(s), base fill rate ${(fillRate * 100).toFixed(1)}%, log loss ${logLoss.toFixed(4)} -> ${storage.path(FILL_MODEL_FILE)}`;
+  }
+
+  /** Probability in [0, 1] that a limit buy with these inputs fills within its period */
+  estimate(q: FillQuery): number {
+    return predict(this.model.weights, features(q, this.model.mean_spread));
+  }
+}
+
+/**
+ * Calibration of the fill model: placements bucketed by the probability estimated when they were placed
+ * (online), predicted vs realized fill rate per bucket and the Brier score. Placements made before a
+ * model existed are left out.
+ */
+export function fillCalibrationReport(storage: Storage): string {
+  const rows = labelledPlacements(storage).filter((d) => d.row.fill_probability != null);
+  const lines = ["Fill model calibration (estimates made at placement)", "   predicted      n   mean est.   realized"];
+  if (rows.length === 0) {
+    lines.push("   (no scored placements yet - train with --train-fill-model, then run)");
+    return lines.join("\n");
+  }
+  const bins = Array.from({ length: CALIBRATION_BINS }, () => ({ n: 0, est: 0, filled: 0 }));
+  let brier = 0;
+  for (const { row, filled } of rows) {
+    const p = row.fill_probability as number;
+    const bin = bins[Math.min(CALIBRATION_BINS - 1, Math.floor(p * CALIBRATION_BINS))];
+    bin.n++;
+    bin.est += p;
+    bin.filled += filled ? 1 : 0;
+    brier += (p - (filled ? 1 : 0)) ** 2;
+  }
+  bins.forEach((b, i) => {
+    if (b.n === 0) return;
+    const range = `${((i / CALIBRATION_BINS) * 100).toFixed(0)}-${(((i + 1) / CALIBRATION_BINS) * 100).toFixed(0)}%`;
+    lines.push(
+      `   ${range.padEnd(9)} ${String(b.n).padStart(6)} ${((b.est / b.n) * 100).toFixed(1).padStart(10)}% ${((b.filled / b.n) * 100).toFixed(1).padStart(9)}%`
+    );
+  });
+  lines.push(`   Brier score ${(brier / rows.length).toFixed(4)} over ${rows.length} placement(s) (0.25 = coin flip)`);
+  return lines.join("\n");
+}
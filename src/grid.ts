@@ -0,0 +1,238 @@
+import type { Config, GridConfig } from "./config.js";
+import type { Storage } from "./storage.js";
+import type { Strategy } from "./strategy.js";
+import type { StrategyOrder, Trader } from "./trader.js";
+import type { Asset, MarketSnapshot, TokenType } from "./types.js";
+import { tokenPriceForType, tokenTypeDisplayName } from "./types.js";
+
+const PERIOD_DURATION = 900;
+const TOKEN_TYPES: Record<Asset, [TokenType, TokenType]> = {
+  BTC: ["BtcUp", "BtcDown"],
+  ETH: ["EthUp", "EthDown"],
+  SOL: ["SolanaUp", "SolanaDown"],
+  XRP: ["XrpUp", "XrpDown"],
+};
+
+/** One token's grid in one period */
+interface GridBook {
+  period: number;
+  token_type: TokenType;
+  token_id: string;
+  center: number;
+  orders: StrategyOrder[];
+  inventory: number;
+  /** Cost basis of `inventory` */
+  cost: number;
+  realized: number;
+  fills: number;
+  last_bid: number | null;
+  closed: boolean;
+}
+
+function round2(price: number): number {
+  return Math.round(price * 100) / 100;
+}
+
+/**
+ * Grid trading in the middle of a period: a ladder of limit buys below the recent mid; every filled buy
+ * is answered by a sell one step above it and every filled sell by a buy one step below, so the grid
+ * earns `spacing` per round trip while price chops. Net inventory and loss per token are capped; at
+ * `stop_seconds` (or the loss cap) resting orders are cancelled and inventory is sold into the bid.
+ * With `skew` set, quotes lean towards reducing inventory as it builds up.
+ */
+export class GridStrategy implements Strategy {
+  readonly name = "grid";
+  private cfg: GridConfig;
+  private trader: Trader;
+  private storage: Storage | null;
+  private tokenTypes: TokenType[] = [];
+  private books: Map<string, GridBook> = new Map();
+
+  constructor(cfg: GridConfig, trading: Config["trading"], trader: Trader, storage: Storage | null) {
+    this.cfg = cfg;
+    this.trader = trader;
+    this.storage = storage;
+    const assets: Asset[] = ["BTC"];
+    if (trading.enable_eth_trading) assets.push("ETH");
+    if (trading.enable_solana_trading) assets.push("SOL");
+    if (trading.enable_xrp_trading) assets.push("XRP");
+    const side = cfg.side ?? "up";
+    for (const a of assets) {
+      const [up, down] = TOKEN_TYPES[a];
+      if (side !== "down") this.tokenTypes.push(up);
+      if (side !== "up") this.tokenTypes.push(down);
+    }
+  }
+
+  async onSnapshot(snapshot: MarketSnapshot): Promise<void> {
+    const elapsed = PERIOD_DURATION - snapshot.time_remaining_seconds;
+    const start = this.cfg.start_seconds ?? 120;
+    const stop = this.cfg.stop_seconds ?? 780;
+    for (const book of this.books.values()) {
+      // Snapshots skipped past the stop: close last period's grid at the last bid seen.
+      if (!book.closed && book.period !== snapshot.period_timestamp) await this.close(book, "period ended");
+    }
+    for (const tokenType of this.tokenTypes) {
+      const key = `${snapshot.period_timestamp}_${tokenType}`;
+      let book = this.books.get(key) ?? null;
+      if (book?.closed || elapsed < start) continue;
+      const quote = tokenPriceForType(snapshot, tokenType);
+      if (book) book.last_bid = quote?.bid ?? book.last_bid;
+      if (elapsed >= stop) {
+        if (book) await this.close(book, "window ended");
+        continue;
+      }
+      if (!book) {
+        if (!quote || quote.bid == null || quote.ask == null) continue;
+        const lookbackMs = (this.cfg.mid_lookback_seconds ?? 60) * 1000;
+        const center = snapshot.history?.meanMid(quote.token_id, lookbackMs) ?? (quote.bid + quote.ask) / 2;
+        book = {
+          period: snapshot.period_timestamp,
+          token_type: tokenType,
+          token_id: quote.token_id,
+          center,
+          orders: [],
+          inventory: 0,
+          cost: 0,
+          realized: 0,
+          fills: 0,
+          last_bid: quote.bid,
+          closed: false,
+        };
+        this.books.set(key, book);
+        log(`🕸️ GRID ${tokenTypeDisplayName(tokenType)}: ${this.cfg.levels} level(s) $${this.cfg.spacing.toFixed(2)} apart below mid $${center.toFixed(3)}`);
+        for (let k = 1; k <= this.cfg.levels; k++) await this.place(book, "BUY", round2(center - k * this.cfg.spacing));
+        continue;
+      }
+      await this.checkFills(book, snapshot);
+      if (book.last_bid != null && this.pnl(book, book.last_bid) <= -this.cfg.max_loss) {
+        await this.close(book, `loss cap $${this.cfg.max_loss.toFixed(2)} hit`);
+      }
+    }
+    for (const [key, book] of this.books) if (book.closed && book.period < snapshot.period_timestamp) this.books.delete(key);
+  }
+
+  /** Close every open grid (end of replay) */
+  async flush(): Promise<void> {
+    for (const book of this.books.values()) if (!book.closed) await this.close(book, "shutdown");
+  }
+
+  onFinish(): Promise<void> {
+    return this.flush();
+  }
+
+  private async checkFills(book: GridBook, snapshot: MarketSnapshot): Promise<void> {
+    for (const order of [...book.orders]) {
+      if (!(await this.trader.isFilled(order, snapshot))) continue;
+      book.orders = book.orders.filter((o) => o !== order);
+      book.fills++;
+      if (order.side === "BUY") {
+        book.inventory += order.units;
+        book.cost += order.units * order.price;
+        log(`🕸️ GRID ${tokenTypeDisplayName(book.token_type)}: bought ${order.units} @ $${order.price.toFixed(2)} (${order.order_id}), holding ${book.inventory}`);
+        await this.place(book, "SELL", Math.max(order.price + 0.01, round2(order.price + this.cfg.spacing * (1 - this.skew(book)))));
+      } else {
+        const avg = book.inventory > 0 ? book.cost / book.inventory : order.price;
+        book.realized += (order.price - avg) * order.units;
+        book.inventory = Math.max(0, book.inventory - order.units);
+        book.cost = book.inventory > 0 ? avg * book.inventory : 0;
+        log(
+          `🕸️ GRID ${tokenTypeDisplayName(book.token_type)}: sold ${order.units} @ $${order.price.toFixed(2)} (${order.order_id}), ` +
+            `holding ${book.inventory}, realized $${book.realized.toFixed(2)}`
+        );
+        await this.place(book, "BUY", round2(order.price - this.cfg.spacing * (1 + this.skew(book))));
+      }
+    }
+  }
+
+  /** Place one level; buys only while inventory plus resting buys stay within `max_inventory` */
+  private async place(book: GridBook, side: "BUY" | "SELL", price: number): Promise<void> {
+    if (price < 0.01 || price > 0.99) return;
+    const units = this.cfg.shares_per_level;
+    if (side === "BUY") {
+      const restingBuys = book.orders.filter((o) => o.side === "BUY").reduce((s, o) => s + o.units, 0);
+      if (book.inventory + restingBuys + units > this.cfg.max_inventory + 1e-9) return;
+    }
+    try {
+      const order = await this.trader.placeOrder({
+        token_id: book.token_id,
+        token_type: book.token_type,
+        period_timestamp: book.period,
+        side,
+        price,
+        units,
+        reason: side === "BUY" ? "grid_buy" : "grid_sell",
+      });
+      if (order) book.orders.push(order);
+    } catch (e) {
+      log(`Error placing grid ${side} ${tokenTypeDisplayName(book.token_type)} @ $${price.toFixed(2)}: ${String(e)}`);
+    }
+  }
+
+  /**
+   * How far to shift quotes towards reducing inventory, as a fraction of `spacing`: sells come closer
+   * and buys move away as the book fills up to `max_inventory`.
+   */
+  private skew(book: GridBook): number {
+    const fill = Math.min(1, book.inventory / this.cfg.max_inventory);
+    const shape = this.cfg.skew ?? "none";
+    const f = shape === "linear" ? fill : shape === "quadratic" ? fill * fill : 0;
+    return Math.min(1, Math.max(0, this.cfg.skew_strength ?? 0.5)) * f;
+  }
+
+  private pnl(book: GridBook, bid: number): number {
+    return book.realized + book.inventory * bid - book.cost;
+  }
+
+  /** Cancel resting orders, sell remaining inventory into the last bid and record the period's result */
+  private async close(book: GridBook, reason: string): Promise<void> {
+    book.closed = true;
+    for (const order of book.orders) {
+      try {
+        await this.trader.cancelOrder(order);
+      } catch (e) {
+        log(`Error cancelling grid order ${order.order_id}: ${String(e)}`);
+      }
+    }
+    book.orders = [];
+    if (book.inventory > 0 && book.last_bid != null) {
+      const bid = book.last_bid;
+      try {
+        const exit = await this.trader.placeOrder({
+          token_id: book.token_id,
+          token_type: book.token_type,
+          period_timestamp: book.period,
+          side: "SELL",
+          price: bid,
+          units: book.inventory,
+          reason: "grid_flatten",
+        });
+        if (exit) {
+          this.trader.markFilled(exit);
+          book.realized += (bid - book.cost / book.inventory) * book.inventory;
+          book.inventory = 0;
+          book.cost = 0;
+        }
+      } catch (e) {
+        log(`Error flattening grid ${tokenTypeDisplayName(book.token_type)}: ${String(e)}`);
+      }
+    }
+    log(
+      `🕸️ GRID ${tokenTypeDisplayName(book.token_type)} closed (${reason}): ${book.fills} fill(s), ` +
+        `realized $${book.realized.toFixed(2)}${book.inventory > 0 ? `, ${book.inventory} share(s) left to resolution` : ""}`
+    );
+    this.storage?.appendJsonl("grid.jsonl", {
+      period_timestamp: book.period,
+      token_type: book.token_type,
+      center: book.center,
+      fills: book.fills,
+      realized_pnl: book.realized,
+      inventory_left: book.inventory,
+      reason,
+    });
+  }
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
@@ -0,0 +1,80 @@
+import type { PolymarketApi } from "./api.js";
+import { clock } from "./clock.js";
+import type { Notifier } from "./notifier.js";
+import { DEFAULT_TEMPLATES, renderTemplate } from "./templates.js";
+
+const HALT_MESSAGE = /maintenance|trading (is )?(currently )?(disabled|halted|paused|suspended)|cancel[- ]only|not accepting orders/i;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** True for errors the CLOB returns during maintenance or a trading halt (HTTP 503/425 or a halt message) */
+export function isHaltError(e: unknown): boolean {
+  const status = (e as { response?: { status?: number } })?.response?.status ?? (e as { status?: number })?.status;
+  if (status === 503 || status === 425) return true;
+  const body = (e as { response?: { data?: unknown } })?.response?.data;
+  return HALT_MESSAGE.test(`${e instanceof Error ? e.message : String(e)} ${body != null ? JSON.stringify(body) : ""}`);
+}
+
+/**
+ * Exchange halt state. Entered on a halt error from an order or when the current market reports
+ * `accepting_orders: false`; while halted no entries are attempted and the market is re-checked every
+ * `recheckMs`, so a maintenance window costs one log line each way instead of a failure per order.
+ */
+export class HaltGuard {
+  private api: PolymarketApi;
+  private recheckMs: number;
+  private notifier: Notifier | null;
+  private reason: string | null = null;
+  private sinceMs = 0;
+  private lastCheckMs = -Infinity;
+
+  constructor(api: PolymarketApi, recheckMs: number, notifier: Notifier | null = null) {
+    this.api = api;
+    this.recheckMs = recheckMs;
+    this.notifier = notifier;
+  }
+
+  isHalted(): boolean {
+    return this.reason != null;
+  }
+
+  /** Enter the halted state if `e` is a halt error; returns whether it was */
+  noteError(e: unknown): boolean {
+    if (!isHaltError(e)) return false;
+    this.enter(`order failed: ${e instanceof Error ? e.message : String(e)}`);
+    return true;
+  }
+
+  /** Re-check `conditionId`'s market at most every `recheckMs` (always while halted) */
+  async check(conditionId: string): Promise<void> {
+    const now = clock().now();
+    if (now - this.lastCheckMs < this.recheckMs) return;
+    this.lastCheckMs = now;
+    try {
+      const market = await this.api.getMarketByConditionId(conditionId);
+      if (market.accepting_orders === false) this.enter("market not accepting orders");
+      else this.exit();
+    } catch (e) {
+      // A halt error keeps (or puts) us halted; anything else says nothing about the exchange state.
+      if (isHaltError(e)) this.enter(`CLOB unavailable: ${e instanceof Error ? e.message : String(e)}`);
+    }
+  }
+
+  private enter(reason: string): void {
+    if (this.reason != null) return;
+    this.reason = reason;
+    this.sinceMs = clock().now();
+    this.lastCheckMs = this.sinceMs;
+    const vars = { reason, recheck_seconds: Math.round(this.recheckMs / 1000) };
+    if (this.notifier) this.notifier.alert("exchange_halt", "exchange_halt", vars);
+    else log(`🚧 ${renderTemplate(DEFAULT_TEMPLATES.exchange_halt, vars)}`);
+  }
+
+  private exit(): void {
+    if (this.reason == null) return;
+    log(`✅ Exchange accepting orders again after ${Math.round((clock().now() - this.sinceMs) / 1000)}s - entries resumed`);
+    this.reason = null;
+  }
+}
@@ -0,0 +1,76 @@
+import { createServer } from "http";
+import type { IncomingMessage, Server, ServerResponse } from "http";
+import { mkdirSync, writeFileSync } from "fs";
+import { dirname } from "path";
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Liveness signal from the main loop: rewrites `heartbeat_file` and serves `GET /healthz` on
+ * `health_port`, which answers 503 once no beat arrived for `staleAfterMs` (hung loop).
+ */
+export class Heartbeat {
+  private file: string | null;
+  private port: number | null;
+  private staleAfterMs: number;
+  private server: Server | null = null;
+  private lastBeatMs: number = Date.now();
+  private lastInfo: Record<string, unknown> = {};
+  private dirReady = false;
+  private routes: Array<(req: IncomingMessage, res: ServerResponse) => boolean> = [];
+
+  constructor(file: string | null, port: number | null, staleAfterMs: number) {
+    this.file = file;
+    this.port = port;
+    this.staleAfterMs = staleAfterMs;
+  }
+
+  /** Extra handler tried before 404 (returns true when it answered the request) */
+  addRoute(handler: (req: IncomingMessage, res: ServerResponse) => boolean): void {
+    this.routes.push(handler);
+  }
+
+  start(): void {
+    if (this.port == null) return;
+    this.server = createServer((req, res) => {
+      if (req.url !== "/healthz") {
+        if (!this.routes.some((route) => route(req, res))) res.writeHead(404).end();
+        return;
+      }
+      const status = this.status();
+      res.writeHead(status.status === "ok" ? 200 : 503, { "Content-Type": "application/json" });
+      res.end(JSON.stringify(status));
+    });
+    this.server.on("error", (e) => log(`⚠️ Health endpoint error: ${String(e)}`));
+    this.server.listen(this.port, () => log(`💓 Liveness endpoint on http://0.0.0.0:${this.port}/healthz`));
+  }
+
+  /** What `GET /healthz` answers: ok/stale, age of the last beat and the loop info it carried */
+  status(): { status: "ok" | "stale"; last_beat_age_ms: number } & Record<string, unknown> {
+    const ageMs = Date.now() - this.lastBeatMs;
+    return { status: ageMs <= this.staleAfterMs ? "ok" : "stale", last_beat_age_ms: ageMs, ...this.lastInfo };
+  }
+
+  stop(): void {
+    this.server?.close();
+    this.server = null;
+  }
+
+  /** Called once per loop iteration; wall-clock based so it also works while replaying */
+  beat(info: Record<string, unknown>): void {
+    this.lastBeatMs = Date.now();
+    this.lastInfo = info;
+    if (!this.file) return;
+    try {
+      if (!this.dirReady) {
+        mkdirSync(dirname(this.file), { recursive: true });
+        this.dirReady = true;
+      }
+      writeFileSync(this.file, JSON.stringify({ ts: Math.floor(this.lastBeatMs / 1000), ...info }) + "\n");
+    } catch (e) {
+      log(`⚠️ Could not write heartbeat file ${this.file}: ${String(e)}`);
+    }
+  }
+}
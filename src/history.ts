@@ -0,0 +1,111 @@
+import type { MarketSnapshot } from "./types.js";
+
+export interface QuotePoint {
+  ts_ms: number;
+  bid: number | null;
+  ask: number | null;
+}
+
+/** Fixed-capacity ring of quotes for one token; the oldest point is overwritten when full */
+class QuoteRing {
+  private buf: QuotePoint[] = [];
+  private start = 0;
+  private capacity: number;
+
+  constructor(capacity: number) {
+    this.capacity = capacity;
+  }
+
+  push(point: QuotePoint): void {
+    if (this.buf.length < this.capacity) {
+      this.buf.push(point);
+      return;
+    }
+    this.buf[this.start] = point;
+    this.start = (this.start + 1) % this.capacity;
+  }
+
+  latestTs(): number {
+    return this.buf.length ? this.buf[(this.start + this.buf.length - 1) % this.buf.length].ts_ms : 0;
+  }
+
+  /** Points at or after `sinceMs`, oldest first */
+  since(sinceMs: number): QuotePoint[] {
+    const out: QuotePoint[] = [];
+    for (let i = 0; i < this.buf.length; i++) {
+      const p = this.buf[(this.start + i) % this.buf.length];
+      if (p.ts_ms >= sinceMs) out.push(p);
+    }
+    return out;
+  }
+}
+
+function mid(p: QuotePoint): number | null {
+  return p.bid != null && p.ask != null ? (p.bid + p.ask) / 2 : null;
+}
+
+/**
+ * Bid/ask history per token over the last `windowMs`, at most `capacity` points per token. Travels on
+ * each snapshot as `history` for short-term momentum/volatility; left out when snapshots are recorded.
+ */
+export class PriceHistory {
+  private windowMs: number;
+  private capacity: number;
+  private rings: Map<string, QuoteRing> = new Map();
+  private latestMs = 0;
+
+  constructor(windowMs: number, capacity: number = 5_000) {
+    this.windowMs = windowMs;
+    this.capacity = capacity;
+  }
+
+  record(snapshot: MarketSnapshot, tsMs: number): void {
+    this.latestMs = Math.max(this.latestMs, tsMs);
+    for (const market of [snapshot.btc_market, snapshot.eth_market, snapshot.solana_market, snapshot.xrp_market]) {
+      for (const token of [market.up_token, market.down_token]) {
+        if (!token) continue;
+        let ring = this.rings.get(token.token_id);
+        if (!ring) {
+          ring = new QuoteRing(this.capacity);
+          this.rings.set(token.token_id, ring);
+        }
+        ring.push({ ts_ms: tsMs, bid: token.bid, ask: token.ask });
+      }
+    }
+    // Tokens of resolved markets stop updating; drop them once their newest point leaves the window.
+    for (const [tokenId, ring] of this.rings) {
+      if (this.latestMs - ring.latestTs() > this.windowMs) this.rings.delete(tokenId);
+    }
+  }
+
+  /** Quotes for a token over the last `lookbackMs` (default: the whole window), oldest first */
+  series(tokenId: string, lookbackMs: number = this.windowMs): QuotePoint[] {
+    return this.rings.get(tokenId)?.since(this.latestMs - Math.min(lookbackMs, this.windowMs)) ?? [];
+  }
+
+  /** Mid-price change over the lookback, or null without two two-sided quotes */
+  change(tokenId: string, lookbackMs: number): number | null {
+    const mids = this.series(tokenId, lookbackMs).map(mid).filter((m): m is number => m != null);
+    return mids.length >= 2 ? mids[mids.length - 1] - mids[0] : null;
+  }
+
+  /** Average mid over the lookback, or null without a two-sided quote */
+  meanMid(tokenId: string, lookbackMs: number): number | null {
+    const mids = this.series(tokenId, lookbackMs).map(mid).filter((m): m is number => m != null);
+    return mids.length > 0 ? mids.reduce((s, m) => s + m, 0) / mids.length : null;
+  }
+
+  /** Standard deviation of tick-to-tick mid changes over the lookback, or null with fewer than three mids */
+  volatility(tokenId: string, lookbackMs: number): number | null {
+    const mids = this.series(tokenId, lookbackMs).map(mid).filter((m): m is number => m != null);
+    if (mids.length < 3) return null;
+    const diffs = mids.slice(1).map((m, i) => m - mids[i]);
+    const mean = diffs.reduce((s, d) => s + d, 0) / diffs.length;
+    return Math.sqrt(diffs.reduce((s, d) => s + (d - mean) ** 2, 0) / diffs.length);
+  }
+
+  /** Keep the history out of recorded snapshots */
+  toJSON(): undefined {
+    return undefined;
+  }
+}
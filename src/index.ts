@@ -0,0 +1,63 @@
+/**
+ * Library entry point: embed the bot in another project instead of forking the entry script.
+ * Everything exported here is the supported surface; other modules are internal and may change.
+ */
+export { loadConfig } from "./config.js";
+export type {
+  AlertsConfig,
+  Config,
+  LatencyArbConfig,
+  PolymarketConfig,
+  RedemptionConfig,
+  TradingConfig,
+} from "./config.js";
+export { BotRuntime, BotRuntimeBuilder } from "./runtime.js";
+export type { RuntimeOptions } from "./runtime.js";
+export type { Strategy } from "./strategy.js";
+export { PolymarketApi } from "./api.js";
+export { MarketMonitor, currentPeriodTimestamp } from "./monitor.js";
+export type { MonitoredMarkets } from "./monitor.js";
+export { getOrDiscoverMarkets } from "./discovery.js";
+export { Trader } from "./trader.js";
+export type { OrderState, PositionState, SellLeg, SessionTotals, Sizer, StrategyOrder, TraderState } from "./trader.js";
+export { RiskBudget } from "./risk.js";
+export { portfolioView } from "./portfolio.js";
+export { PredictionJournal } from "./journal.js";
+export type { Thesis } from "./journal.js";
+export type { Holding, MarketOutcome, PortfolioView } from "./portfolio.js";
+export { HaltGuard, isHaltError } from "./halt.js";
+export { TradeEventBus } from "./events.js";
+export type { EventSink, TradeEvent } from "./events.js";
+export { DEFAULT_SEVERITIES, Notifier, TelegramChannel, WebhookChannel } from "./notifier.js";
+export type { AlertChannel, AlertRoutingConfig, AlertSeverity } from "./notifier.js";
+export { DEFAULT_TEMPLATES, renderTemplate } from "./templates.js";
+export type { TemplateVars } from "./templates.js";
+export { Storage } from "./storage.js";
+export { ReplaySource, ReplaySpotFeed, recordTick } from "./replay.js";
+export { createSpotFeed } from "./spot.js";
+export type { SpotFeed, SpotFeedOptions } from "./spot.js";
+export { MqttPublisher, RedisPublisher } from "./pubsub.js";
+export { LatencyArbStrategy } from "./latency-arb.js";
+export { GridStrategy } from "./grid.js";
+export { PairTradeStrategy } from "./pair-trade.js";
+export { DualLimitStrategy } from "./dual-limit.js";
+export { fairUpProbability } from "./fair-value.js";
+export { VirtualClock, clock, nowSeconds, setClock } from "./clock.js";
+export type { Clock } from "./clock.js";
+export { formatTime, formatUsd } from "./money.js";
+export { configureLogging, logs } from "./logging.js";
+export { formatSummary } from "./summary.js";
+export { FillProbabilityModel } from "./fill-model.js";
+export type { FillQuery } from "./fill-model.js";
+export { SpotMomentumSignal, WebhookSignal } from "./direction.js";
+export type { Direction, DirectionSignal } from "./direction.js";
+export type { SummaryConfig, SummaryDestination, SummarySection } from "./summary.js";
+export type { LogClass, LogLevel, LoggingConfig } from "./logging.js";
+export {
+  assetForTokenType,
+  marketForTokenType,
+  oppositeTokenType,
+  tokenPriceForType,
+  tokenTypeDisplayName,
+} from "./types.js";
+export type { Asset, BuyOpportunity, Market, MarketData, MarketSnapshot, TokenPrice, TokenType } from "./types.js";
@@ -0,0 +1,146 @@
+import { clock } from "./clock.js";
+import type { TradeEvent, TradeEventBus } from "./events.js";
+import { formatTime, formatUsd } from "./money.js";
+import type { Storage } from "./storage.js";
+import type { BuyOpportunity, TokenType } from "./types.js";
+
+const JOURNAL_FILE = "journal.jsonl";
+const REPORT_ROWS = 30;
+
+/** What a strategy believed when it entered: signal values, fair value, EV, ... */
+export type Thesis = Record<string, number | string | boolean | null>;
+
+interface ThesisRow {
+  kind: "thesis";
+  entry_id: string;
+  ts_ms: number;
+  period_timestamp: number;
+  token_type: TokenType;
+  reason: string;
+  limit_price: number;
+  note: string | null;
+  thesis: Thesis;
+}
+
+/** A `journal.jsonl` row: the thesis at entry, then the outcome once the position closes or expires */
+type JournalRow =
+  | ThesisRow
+  | {
+      kind: "outcome";
+      entry_id: string;
+      ts_ms: number;
+      position_id: string;
+      filled: boolean;
+      /** Exit reasons in order (take_profit, stop_loss, resolution, ...) */
+      exits: string[];
+      pnl: number;
+    };
+
+interface OpenEntry {
+  entry_id: string;
+  reason: string;
+  filled: boolean;
+  exits: string[];
+}
+
+/**
+ * Research journal linking decisions to results. A strategy records its thesis for an order just before
+ * placing it (`BotRuntime.journal.record`); the thesis is written to `journal.jsonl` once the order is
+ * placed (a skipped or rejected order leaves nothing), then the journal follows the order's trade events
+ * and appends the outcome (unfilled, or realized PnL and how it exited) when the position is done.
+ */
+export class PredictionJournal {
+  private storage: Storage;
+  /** Theses waiting for their order, by period, token and reason */
+  private pending: Map<string, ThesisRow> = new Map();
+  private open: Map<string, OpenEntry> = new Map();
+  private seq = 0;
+
+  constructor(storage: Storage, events: TradeEventBus) {
+    this.storage = storage;
+    events.subscribe((event) => this.onEvent(event));
+  }
+
+  /** Record the thesis behind a buy about to be placed; a later thesis for the same order replaces it */
+  record(opp: BuyOpportunity, thesis: Thesis): void {
+    // A thesis from an earlier period whose order was never placed will not be.
+    for (const [key, row] of this.pending) if (row.period_timestamp < opp.period_timestamp) this.pending.delete(key);
+    const reason = opp.reason ?? "unspecified";
+    this.pending.set(`${opp.period_timestamp}_${opp.token_type}_${reason}`, {
+      kind: "thesis",
+      entry_id: `${opp.period_timestamp}-${opp.token_type}-${++this.seq}`,
+      ts_ms: clock().now(),
+      period_timestamp: opp.period_timestamp,
+      token_type: opp.token_type,
+      reason,
+      limit_price: opp.bid_price,
+      note: opp.note ?? null,
+      thesis,
+    });
+  }
+
+  private onEvent(event: TradeEvent): void {
+    const positionId = event.position_id;
+    if (!positionId) return;
+    if (event.kind === "placed") {
+      const key = `${event.period_timestamp}_${event.token_type}_${event.reason ?? "unspecified"}`;
+      const thesis = this.pending.get(key);
+      if (thesis == null || this.open.has(positionId)) return;
+      this.pending.delete(key);
+      this.append(thesis);
+      this.open.set(positionId, { entry_id: thesis.entry_id, reason: event.reason ?? "unspecified", filled: false, exits: [] });
+      return;
+    }
+    const entry = this.open.get(positionId);
+    if (!entry) return;
+    if (event.kind === "filled") entry.filled = true;
+    else if (event.kind === "exit" && event.detail) entry.exits.push(event.detail);
+    else if (event.kind === "closed" || (event.kind === "cancelled" && !entry.filled && event.reason === entry.reason)) {
+      this.open.delete(positionId);
+      this.append({
+        kind: "outcome",
+        entry_id: entry.entry_id,
+        ts_ms: clock().now(),
+        position_id: positionId,
+        filled: entry.filled,
+        exits: entry.exits,
+        pnl: event.kind === "closed" ? (event.pnl ?? 0) : 0,
+      });
+    }
+  }
+
+  private append(row: JournalRow): void {
+    this.storage.appendJsonl(JOURNAL_FILE, row);
+  }
+}
+
+/** Latest journal entries, thesis next to outcome, with win rate and PnL over the resolved ones */
+export function journalReport(storage: Storage): string {
+  const rows = storage.readJsonl<JournalRow>(JOURNAL_FILE);
+  const outcomes = new Map<string, Extract<JournalRow, { kind: "outcome" }>>();
+  for (const r of rows) if (r.kind === "outcome") outcomes.set(r.entry_id, r);
+  const theses = rows.filter((r): r is Extract<JournalRow, { kind: "thesis" }> => r.kind === "thesis");
+  const lines = ["Prediction journal (thesis at entry -> outcome)"];
+  if (theses.length === 0) {
+    lines.push("   (no entries yet - enable journal)");
+    return lines.join("\n");
+  }
+  for (const t of theses.slice(-REPORT_ROWS)) {
+    const o = outcomes.get(t.entry_id);
+    const thesis = Object.entries(t.thesis)
+      .map(([k, v]) => `${k}=${typeof v === "number" ? Number(v.toFixed(4)) : v}`)
+      .join(" ");
+    const result = !o ? "open" : !o.filled ? "unfilled" : `${formatUsd(o.pnl)} via ${o.exits.join(", ") || "?"}`;
+    lines.push(`   ${formatTime(t.period_timestamp * 1000)} ${t.token_type.padEnd(10)} @ $${t.limit_price.toFixed(2)}  ${thesis}`);
+    lines.push(`      -> ${result}`);
+  }
+  const done = theses.map((t) => outcomes.get(t.entry_id)).filter((o): o is NonNullable<typeof o> => o != null && o.filled);
+  const wins = done.filter((o) => o.pnl > 0).length;
+  const pnl = done.reduce((s, o) => s + o.pnl, 0);
+  lines.push(
+    `   ${theses.length} thesis(es), ${done.length} filled and closed: ${wins} profitable` +
+      (done.length > 0 ? ` (${((wins / done.length) * 100).toFixed(0)}%)` : "") +
+      `, total ${formatUsd(pnl)}`
+  );
+  return lines.join("\n");
+}
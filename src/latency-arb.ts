@@ -0,0 +1,108 @@
+import { clock } from "./clock.js";
+import type { Config, LatencyArbConfig } from "./config.js";
+import { fairUpProbability } from "./fair-value.js";
+import type { SpotFeed } from "./spot.js";
+import type { Strategy } from "./strategy.js";
+import type { Trader } from "./trader.js";
+import type { Asset, BuyOpportunity, MarketSnapshot, TokenType } from "./types.js";
+import { marketForTokenType, tokenTypeDisplayName } from "./types.js";
+
+const PERIOD_DURATION = 900;
+const ASSETS: Asset[] = ["BTC", "ETH", "SOL", "XRP"];
+const TOKEN_TYPES: Record<Asset, [TokenType, TokenType]> = {
+  BTC: ["BtcUp", "BtcDown"],
+  ETH: ["EthUp", "EthDown"],
+  SOL: ["SolanaUp", "SolanaDown"],
+  XRP: ["XrpUp", "XrpDown"],
+};
+
+/**
+ * Latency arbitrage: when spot moves sharply within `lookback_ms`, lift the Up/Down ask that has not
+ * repriced yet, as long as fair value minus the price paid clears `min_edge`.
+ */
+export class LatencyArbStrategy implements Strategy {
+  readonly name = "latency_arb";
+  private cfg: LatencyArbConfig;
+  private spotFeed: SpotFeed;
+  private trader: Trader;
+  private enabledAssets: Set<Asset>;
+  private targetMakerRatio: number | null;
+  private history: Map<Asset, Array<{ ts: number; price: number }>> = new Map();
+  private tradesThisPeriod: Map<string, number> = new Map();
+
+  constructor(cfg: LatencyArbConfig, trading: Config["trading"], spotFeed: SpotFeed, trader: Trader) {
+    this.cfg = cfg;
+    this.spotFeed = spotFeed;
+    this.trader = trader;
+    this.targetMakerRatio = trading.target_maker_ratio ?? null;
+    this.enabledAssets = new Set<Asset>(["BTC"]);
+    if (trading.enable_eth_trading) this.enabledAssets.add("ETH");
+    if (trading.enable_solana_trading) this.enabledAssets.add("SOL");
+    if (trading.enable_xrp_trading) this.enabledAssets.add("XRP");
+  }
+
+  async onSnapshot(snapshot: MarketSnapshot): Promise<void> {
+    const now = clock().now();
+    for (const asset of ASSETS) {
+      const spot = this.spotFeed.price(asset);
+      if (spot == null || !this.enabledAssets.has(asset)) continue;
+      const hist = this.history.get(asset) ?? [];
+      hist.push({ ts: now, price: spot });
+      while (hist.length > 0 && now - hist[0].ts > this.cfg.lookback_ms) hist.shift();
+      this.history.set(asset, hist);
+
+      const moveBps = ((spot - hist[0].price) / hist[0].price) * 10_000;
+      if (Math.abs(moveBps) < this.cfg.min_move_bps) continue;
+      const tradeKey = `${snapshot.period_timestamp}_${asset}`;
+      if ((this.tradesThisPeriod.get(tradeKey) ?? 0) >= (this.cfg.max_trades_per_period ?? 1)) continue;
+      const open = this.spotFeed.periodOpen(asset, snapshot.period_timestamp);
+      if (open == null) continue;
+
+      const fairUp = fairUpProbability(spot, open, snapshot.time_remaining_seconds, this.cfg.volatility_per_sqrt_sec);
+      const [upType, downType] = TOKEN_TYPES[asset];
+      const tokenType = moveBps > 0 ? upType : downType;
+      const fair = moveBps > 0 ? fairUp : 1 - fairUp;
+      const market = marketForTokenType(snapshot, tokenType);
+      const token = moveBps > 0 ? market.up_token : market.down_token;
+      if (!token || token.ask == null) continue;
+
+      const edge = fair - token.ask;
+      if (edge < this.cfg.min_edge) continue;
+      const limitPrice = Math.min(token.ask + this.cfg.max_slippage, fair - this.cfg.min_edge);
+      // Lifting the ask is a taker fill; hold off while the session is below its maker target.
+      const makerRatio = this.trader.makerRatio();
+      if (this.targetMakerRatio != null && makerRatio != null && makerRatio < this.targetMakerRatio) {
+        log(`⚡ Skipping latency arb ${tokenTypeDisplayName(tokenType)}: maker ratio ${(makerRatio * 100).toFixed(0)}% below target ${(this.targetMakerRatio * 100).toFixed(0)}%`);
+        continue;
+      }
+
+      log(
+        `⚡ LATENCY ARB ${tokenTypeDisplayName(tokenType)}: spot ${moveBps >= 0 ? "+" : ""}${moveBps.toFixed(1)}bps in ` +
+          `${now - hist[0].ts}ms, fair $${fair.toFixed(3)} vs ask $${token.ask.toFixed(2)} (edge $${edge.toFixed(3)}), limit $${limitPrice.toFixed(2)}`
+      );
+      const opp: BuyOpportunity = {
+        condition_id: market.condition_id,
+        token_id: token.token_id,
+        token_type: tokenType,
+        bid_price: limitPrice,
+        period_timestamp: snapshot.period_timestamp,
+        time_remaining_seconds: snapshot.time_remaining_seconds,
+        time_elapsed_seconds: PERIOD_DURATION - snapshot.time_remaining_seconds,
+        use_market_order: false,
+        decision_ms: now,
+        reason: "latency_arb",
+        note: `spot ${moveBps >= 0 ? "+" : ""}${moveBps.toFixed(1)}bps, fair ${fair.toFixed(3)}, edge ${edge.toFixed(3)}`,
+      };
+      this.tradesThisPeriod.set(tradeKey, (this.tradesThisPeriod.get(tradeKey) ?? 0) + 1);
+      try {
+        await this.trader.executeLimitBuy(opp, limitPrice, this.cfg.max_shares);
+      } catch (e) {
+        log("Error executing latency arb buy: " + String(e));
+      }
+    }
+  }
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
@@ -0,0 +1,86 @@
+const WINDOW = 500;
+
+interface OrderTiming {
+  decision_ms: number;
+  sent_ms: number | null;
+  ack_ms: number | null;
+  fill_ms: number | null;
+}
+
+type Stage = "decision_to_sent" | "sent_to_ack" | "ack_to_fill" | "decision_to_ack";
+
+function percentile(sorted: number[], p: number): number {
+  if (sorted.length === 0) return 0;
+  const idx = Math.min(sorted.length - 1, Math.ceil((p / 100) * sorted.length) - 1);
+  return sorted[Math.max(0, idx)];
+}
+
+/** Order placement latency: decision -> request sent -> exchange ack -> fill, per live order */
+export class LatencyTracker {
+  private orders: Map<string, OrderTiming> = new Map();
+  private samples: Record<Stage, number[]> = {
+    decision_to_sent: [],
+    sent_to_ack: [],
+    ack_to_fill: [],
+    decision_to_ack: [],
+  };
+  private warnP95Ms: number;
+
+  constructor(warnP95Ms: number) {
+    this.warnP95Ms = warnP95Ms;
+  }
+
+  /** Record send/ack for a placed order; returns the decision-to-ack time */
+  recordPlacement(orderId: string, decisionMs: number, sentMs: number, ackMs: number): number {
+    this.orders.set(orderId, { decision_ms: decisionMs, sent_ms: sentMs, ack_ms: ackMs, fill_ms: null });
+    this.push("decision_to_sent", sentMs - decisionMs);
+    this.push("sent_to_ack", ackMs - sentMs);
+    this.push("decision_to_ack", ackMs - decisionMs);
+    return ackMs - decisionMs;
+  }
+
+  recordFill(orderId: string, fillMs: number = Date.now()): void {
+    const timing = this.orders.get(orderId);
+    if (!timing || timing.fill_ms != null || timing.ack_ms == null) return;
+    timing.fill_ms = fillMs;
+    this.push("ack_to_fill", fillMs - timing.ack_ms);
+    this.orders.delete(orderId);
+  }
+
+  /** p50/p95/p99 per stage */
+  stats(): Record<Stage, { count: number; p50: number; p95: number; p99: number }> {
+    const out = {} as Record<Stage, { count: number; p50: number; p95: number; p99: number }>;
+    for (const stage of Object.keys(this.samples) as Stage[]) {
+      const sorted = [...this.samples[stage]].sort((a, b) => a - b);
+      out[stage] = {
+        count: sorted.length,
+        p50: percentile(sorted, 50),
+        p95: percentile(sorted, 95),
+        p99: percentile(sorted, 99),
+      };
+    }
+    return out;
+  }
+
+  /** Multi-line report, or null when no live orders were timed; flags p95 decision-to-ack over the threshold */
+  report(): string | null {
+    const stats = this.stats();
+    if (stats.decision_to_ack.count === 0) return null;
+    const lines = ["⏱️  Order latency (ms)        n     p50     p95     p99"];
+    for (const [stage, s] of Object.entries(stats)) {
+      lines.push(
+        `   ${stage.padEnd(22)} ${String(s.count).padStart(5)} ${s.p50.toFixed(0).padStart(7)} ${s.p95.toFixed(0).padStart(7)} ${s.p99.toFixed(0).padStart(7)}`
+      );
+    }
+    if (stats.decision_to_ack.p95 > this.warnP95Ms) {
+      lines.push(`⚠️  p95 decision-to-ack ${stats.decision_to_ack.p95.toFixed(0)}ms exceeds ${this.warnP95Ms}ms`);
+    }
+    return lines.join("\n");
+  }
+
+  private push(stage: Stage, ms: number): void {
+    const arr = this.samples[stage];
+    arr.push(ms);
+    if (arr.length > WINDOW) arr.shift();
+  }
+}
@@ -0,0 +1,42 @@
+/** Severity of a console line; `off` as a class level silences the class */
+export type LogLevel = "trace" | "debug" | "info" | "warn";
+
+/**
+ * Console line classes with their own level: per-tick prices, fill checks on resting orders, period
+ * summaries, fills and resolutions.
+ */
+export type LogClass = "ticks" | "fill_checks" | "summaries" | "fills" | "resolutions";
+
+export interface LoggingConfig {
+  /** Lowest level printed (default info) */
+  level?: LogLevel | null;
+  /** Level each class logs at, e.g. `{ "fill_checks": "trace", "fills": "info" }` */
+  classes?: Partial<Record<LogClass, LogLevel | "off">> | null;
+}
+
+const RANK: Record<LogLevel, number> = { trace: 0, debug: 1, info: 2, warn: 3 };
+const DEFAULT_CLASSES: Record<LogClass, LogLevel> = {
+  ticks: "info",
+  fill_checks: "trace",
+  summaries: "info",
+  fills: "info",
+  resolutions: "info",
+};
+
+let enabled: Record<LogClass, boolean> = { ticks: true, fill_checks: false, summaries: true, fills: true, resolutions: true };
+
+/** Apply `logging` settings; call once at startup */
+export function configureLogging(cfg: LoggingConfig | null | undefined): void {
+  const threshold = RANK[cfg?.level ?? "info"];
+  const next = {} as Record<LogClass, boolean>;
+  for (const cls of Object.keys(DEFAULT_CLASSES) as LogClass[]) {
+    const level = cfg?.classes?.[cls] ?? DEFAULT_CLASSES[cls];
+    next[cls] = level !== "off" && RANK[level] >= threshold;
+  }
+  enabled = next;
+}
+
+/** Whether lines of `cls` are printed; check before formatting them */
+export function logs(cls: LogClass): boolean {
+  return enabled[cls];
+}
@@ -0,0 +1,80 @@
+/** How account amounts and times are shown in logs and reports */
+export interface DisplayConfig {
+  /** BCP 47 locale for grouping and decimal separators (fixed, never the host default) */
+  locale?: string | null;
+  /** Extra currencies shown after the USD amount, e.g. `{ "code": "EUR", "per_usd": 0.92 }` */
+  currencies?: Array<{ code: string; per_usd: number }> | null;
+  /** Time zone for displayed times: `UTC` (default), `local` (the host's) or an IANA name like `America/New_York` */
+  timezone?: string | null;
+}
+
+const DEFAULT_LOCALE = "en-US";
+
+let locale = DEFAULT_LOCALE;
+let extra: Array<{ code: string; per_usd: number; format: Intl.NumberFormat }> = [];
+let usd = new Intl.NumberFormat(DEFAULT_LOCALE, { style: "currency", currency: "USD" });
+let timeZone: string | undefined = "UTC";
+let dateTime = dateTimeFormat();
+
+function dateTimeFormat(): Intl.DateTimeFormat {
+  return new Intl.DateTimeFormat("en-US", {
+    timeZone,
+    year: "numeric",
+    month: "2-digit",
+    day: "2-digit",
+    hour: "2-digit",
+    minute: "2-digit",
+    second: "2-digit",
+    hourCycle: "h23",
+    timeZoneName: "short",
+  });
+}
+
+function currencyFormat(code: string): Intl.NumberFormat {
+  return new Intl.NumberFormat(locale, { style: "currency", currency: code, minimumFractionDigits: 2, maximumFractionDigits: 2 });
+}
+
+/** Apply `display` settings; call once at startup */
+export function configureDisplay(cfg: DisplayConfig | null | undefined): void {
+  locale = cfg?.locale ?? DEFAULT_LOCALE;
+  usd = currencyFormat("USD");
+  extra = (cfg?.currencies ?? [])
+    .filter((c) => c.code.toUpperCase() !== "USD" && c.per_usd > 0)
+    .map((c) => ({ code: c.code.toUpperCase(), per_usd: c.per_usd, format: currencyFormat(c.code.toUpperCase()) }));
+  const tz = cfg?.timezone ?? "UTC";
+  timeZone = tz === "local" ? undefined : tz;
+  dateTime = dateTimeFormat();
+}
+
+/** IANA zone displayed times use; undefined for the host's local zone */
+export function displayTimeZone(): string | undefined {
+  return timeZone;
+}
+
+/**
+ * An epoch-milliseconds time in the display time zone: `2024-05-01 14:30:00 EDT`, or only
+ * `14:30:00 EDT` with `timeOnly`. Stored timestamps stay UTC; this is for what people read.
+ */
+export function formatTime(ms: number, timeOnly: boolean = false): string {
+  const p: Record<string, string> = {};
+  for (const part of dateTime.formatToParts(ms)) p[part.type] = part.value;
+  const time = `${p.hour}:${p.minute}:${p.second} ${p.timeZoneName}`;
+  return timeOnly ? time : `${p.year}-${p.month}-${p.day} ${time}`;
+}
+
+/**
+ * Round to whole cents in integer micro-units, half away from zero, so binary float artifacts
+ * (1.005 stored as 1.00499...) round the way the decimal amount reads.
+ */
+export function roundCents(amount: number): number {
+  const micros = Math.round(Math.abs(amount) * 1e6);
+  const cents = Math.floor(micros / 10_000) + (micros % 10_000 >= 5_000 ? 1 : 0);
+  return (Math.sign(amount) * cents) / 100;
+}
+
+/** A USDC amount in the display locale, followed by any configured currencies: `$1,234.50 (€1,135.74)` */
+export function formatUsd(amount: number): string {
+  const main = usd.format(roundCents(amount));
+  if (extra.length === 0) return main;
+  return `${main} (${extra.map((c) => c.format.format(roundCents(amount * c.per_usd))).join(", ")})`;
+}
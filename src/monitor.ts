@@ -1,5 +1,12 @@
 import { PolymarketApi } from "./api.js";
-import type { Market, MarketSnapshot, MarketData, TokenPrice } from "./types.js";
+import { clock, nowSeconds } from "./clock.js";
+import type { PhaseIntervalsConfig } from "./config.js";
+import type { BookRecorder } from "./books.js";
+import { PriceHistory } from "./history.js";
+import { TradeStatsTracker } from "./trades.js";
+import type { SpotFeed } from "./spot.js";
+import type { Asset, Market, MarketSnapshot, MarketData, PeriodOpen, TokenPrice } from "./types.js";
+import { outcomeSide, upDownTokenIds } from "./types.js";
 
 function parseNum(s: string | undefined): number | null {
   if (s == null) return null;
@@ -13,11 +20,11 @@ function marketToData(market: Market): MarketData {
   const tokens = market.tokens ?? [];
   for (const t of tokens) {
     const price = parseNum(t.price ?? "");
-    const tokenId = (t as { tokenId?: string }).tokenId ?? (t as { token_id?: string }).token_id ?? "";
-    const outcome = ((t as { outcome?: string }).outcome ?? "").toUpperCase();
+    const tokenId = t.tokenId ?? t.token_id ?? "";
+    const side = outcomeSide(t.outcome);
     const tp: TokenPrice = { token_id: tokenId, bid: price, ask: price };
-    if (outcome.includes("UP") || outcome === "1") up_token = tp;
-    else if (outcome.includes("DOWN") || outcome === "0") down_token = tp;
+    if (side === "up") up_token = tp;
+    else if (side === "down") down_token = tp;
   }
   return {
     condition_id: market.conditionId,
@@ -26,14 +33,30 @@ function marketToData(market: Market): MarketData {
   };
 }
 
+/** Sum of sizes over the best `levels` price levels (bids: highest first, asks: lowest first) */
+function depth(entries: Array<{ price: string; size: string }>, levels: number, bestFirst: (a: number, b: number) => number): number {
+  return entries
+    .map((e) => ({ price: parseFloat(e.price), size: parseFloat(e.size) }))
+    .filter((e) => Number.isFinite(e.price) && Number.isFinite(e.size))
+    .sort((a, b) => bestFirst(a.price, b.price))
+    .slice(0, levels)
+    .reduce((sum, e) => sum + e.size, 0);
+}
+
+type OrderBook = Awaited<ReturnType<PolymarketApi["getOrderBook"]>>;
+
 /** Get best bid/ask from CLOB order book. Best bid = highest price, best ask = lowest (API may sort either way). */
-async function fetchTokenPrice(api: PolymarketApi, tokenId: string): Promise<TokenPrice> {
+async function fetchTokenPrice(api: PolymarketApi, tokenId: string, depthLevels: number, onBook?: (book: OrderBook) => void): Promise<TokenPrice> {
   const book = await api.getOrderBook(tokenId);
+  onBook?.(book);
   const bidPrices = (book.bids ?? []).map((b) => parseFloat(b.price)).filter((n) => Number.isFinite(n));
   const askPrices = (book.asks ?? []).map((a) => parseFloat(a.price)).filter((n) => Number.isFinite(n));
   const bestBid = bidPrices.length > 0 ? Math.max(...bidPrices) : null;
   const bestAsk = askPrices.length > 0 ? Math.min(...askPrices) : null;
-  return { token_id: tokenId, bid: bestBid, ask: bestAsk };
+  const bidDepth = depth(book.bids ?? [], depthLevels, (a, b) => b - a);
+  const askDepth = depth(book.asks ?? [], depthLevels, (a, b) => a - b);
+  const total = bidDepth + askDepth;
+  return { token_id: tokenId, bid: bestBid, ask: bestAsk, book_imbalance: total > 0 ? (bidDepth - askDepth) / total : null };
 }
 
 /** Build snapshot with time remaining and period from market end or current time */
@@ -49,7 +72,7 @@ export function buildSnapshot(
   solanaPrices: { up: TokenPrice | null; down: TokenPrice | null },
   xrpPrices: { up: TokenPrice | null; down: TokenPrice | null }
 ): MarketSnapshot {
-  const now = Math.floor(Date.now() / 1000);
+  const now = nowSeconds();
   const endTime = periodTimestamp + periodDurationSec;
   const timeRemaining = Math.max(0, endTime - now);
   return {
@@ -79,31 +102,26 @@ export function buildSnapshot(
 }
 
 const PERIOD_DURATION = 900;
+/** Shortest silence reported as a coverage gap, whatever the polling interval */
+const MIN_GAP_MS = 5_000;
 
 /** Fetch order book prices for a market's up/down tokens */
 async function fetchMarketPrices(
   api: PolymarketApi,
-  market: Market
+  market: Market,
+  depthLevels: number
 ): Promise<{ up: TokenPrice | null; down: TokenPrice | null }> {
-  const tokens = market.tokens ?? [];
-  let upId: string | null = null;
-  let downId: string | null = null;
-  for (const t of tokens) {
-    const id = t.tokenId ?? t.token_id ?? "";
-    const outcome = (t.outcome ?? "").toUpperCase();
-    if (outcome.includes("UP") || outcome === "1") upId = id;
-    else if (outcome.includes("DOWN") || outcome === "0") downId = id;
-  }
+  const { upId, downId } = upDownTokenIds(market);
   const [up, down] = await Promise.all([
-    upId ? fetchTokenPrice(api, upId) : Promise.resolve(null),
-    downId ? fetchTokenPrice(api, downId) : Promise.resolve(null),
+    upId ? fetchTokenPrice(api, upId, depthLevels) : Promise.resolve(null),
+    downId ? fetchTokenPrice(api, downId, depthLevels) : Promise.resolve(null),
   ]);
   return { up, down };
 }
 
 /** Get current 15-min period timestamp */
 export function currentPeriodTimestamp(): number {
-  const now = Math.floor(Date.now() / 1000);
+  const now = nowSeconds();
   return Math.floor(now / PERIOD_DURATION) * PERIOD_DURATION;
 }
 
@@ -113,14 +131,15 @@ export async function fetchSnapshot(
   ethMarket: Market,
   btcMarket: Market,
   solanaMarket: Market,
-  xrpMarket: Market
+  xrpMarket: Market,
+  depthLevels: number = 5
 ): Promise<MarketSnapshot> {
   const period = currentPeriodTimestamp();
   const [btcPrices, ethPrices, solanaPrices, xrpPrices] = await Promise.all([
-    fetchMarketPrices(api, btcMarket),
-    fetchMarketPrices(api, ethMarket),
-    fetchMarketPrices(api, solanaMarket),
-    fetchMarketPrices(api, xrpMarket),
+    fetchMarketPrices(api, btcMarket, depthLevels),
+    fetchMarketPrices(api, ethMarket, depthLevels),
+    fetchMarketPrices(api, solanaMarket, depthLevels),
+    fetchMarketPrices(api, xrpMarket, depthLevels),
   ]);
   return buildSnapshot(
     period,
@@ -136,12 +155,402 @@ export async function fetchSnapshot(
   );
 }
 
+export interface MonitoredMarkets {
+  eth: Market;
+  btc: Market;
+  solana: Market;
+  xrp: Market;
+}
+
+export type DataQualityCounts = Record<"crossed" | "out_of_range" | "jump", number>;
+
+/**
+ * Sanity filter for book quotes: prices outside (0, 1) and crossed books (bid > ask) are discarded, and
+ * so is a mid that moves more than `maxJumpPct` in one tick unless the next tick confirms the new level.
+ * Discarded quotes keep their token with bid/ask null and a `quality_flag`.
+ */
+export class QuoteFilter {
+  private maxJumpPct: number | null;
+  private lastMid: Map<string, number> = new Map();
+  private pendingMid: Map<string, number> = new Map();
+  readonly counts: DataQualityCounts = { crossed: 0, out_of_range: 0, jump: 0 };
+
+  constructor(maxJumpPct: number | null) {
+    this.maxJumpPct = maxJumpPct;
+  }
+
+  apply(price: TokenPrice): TokenPrice {
+    const inRange = (p: number | null) => p == null || (p > 0 && p < 1);
+    if (!inRange(price.bid) || !inRange(price.ask)) return this.reject(price, "out_of_range");
+    if (price.bid != null && price.ask != null && price.bid > price.ask) return this.reject(price, "crossed");
+    if (price.bid == null || price.ask == null) return price;
+
+    const mid = (price.bid + price.ask) / 2;
+    const last = this.lastMid.get(price.token_id);
+    if (this.maxJumpPct != null && last != null && (Math.abs(mid - last) / last) * 100 > this.maxJumpPct) {
+      const pending = this.pendingMid.get(price.token_id);
+      if (pending == null || (Math.abs(mid - pending) / pending) * 100 > this.maxJumpPct) {
+        this.pendingMid.set(price.token_id, mid);
+        return this.reject(price, "jump");
+      }
+    }
+    this.pendingMid.delete(price.token_id);
+    this.lastMid.set(price.token_id, mid);
+    return price;
+  }
+
+  /** Drop state for tokens no longer monitored */
+  forget(tokenId: string): void {
+    this.lastMid.delete(tokenId);
+    this.pendingMid.delete(tokenId);
+  }
+
+  private reject(price: TokenPrice, flag: keyof DataQualityCounts): TokenPrice {
+    this.counts[flag]++;
+    return { ...price, bid: null, ask: null, book_imbalance: null, quality_flag: flag };
+  }
+}
+
+type MarketKey = keyof MonitoredMarkets;
+const MARKET_KEYS: MarketKey[] = ["eth", "btc", "solana", "xrp"];
+
+interface Subscription {
+  market: MarketKey;
+  side: "up" | "down";
+}
+
+export interface MarketMonitorOptions {
+  /** Rolling window for trade-print stats; null disables trade polling */
+  tradeStatsWindowSeconds?: number | null;
+  /** Book levels per side summed for `book_imbalance` */
+  bookDepthLevels?: number | null;
+  /** Faster polling around period open/close; unset polls at the `start()` interval throughout */
+  phaseIntervals?: PhaseIntervalsConfig | null;
+  /** Minutes of per-token bid/ask kept and attached to snapshots as `history`; 0 disables */
+  historyMinutes?: number | null;
+  /** Max one-tick mid move (%) accepted without confirmation; null disables the jump check */
+  maxTickJumpPct?: number | null;
+  /** Records full books around period boundaries */
+  bookRecorder?: BookRecorder | null;
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Holds the current BTC/ETH/SOL/XRP markets and builds snapshots for them. Once started it polls on a
+ * fixed cadence and keeps only the newest snapshot: a consumer slower than the interval gets the
+ * latest state, and every snapshot replaced before it was read counts as dropped.
+ */
+export class MarketMonitor {
+  private api: PolymarketApi;
+  private running = false;
+  private latest: MarketSnapshot | null = null;
+  private waiter: ((snapshot: MarketSnapshot) => void) | null = null;
+  private dropped = 0;
+  /** Bumped on every market swap so polls started against the old markets are discarded */
+  private generation = 0;
+  private markets: MonitoredMarkets;
+  private marketsPeriod: number;
+  /** Tokens whose books are fetched each poll, keyed by token_id */
+  private subscriptions: Map<string, Subscription> = new Map();
+  private tradeStats: TradeStatsTracker | null;
+  private bookDepthLevels: number;
+  private phaseIntervals: PhaseIntervalsConfig | null;
+  private history: PriceHistory | null;
+  private quoteFilter: QuoteFilter;
+  private bookRecorder: BookRecorder | null;
+  private staged: { markets: MonitoredMarkets; period: number } | null = null;
+
+  constructor(api: PolymarketApi, markets: MonitoredMarkets, opts: MarketMonitorOptions = {}) {
+    this.api = api;
+    this.markets = markets;
+    this.marketsPeriod = currentPeriodTimestamp();
+    this.syncSubscriptions();
+    this.tradeStats = opts.tradeStatsWindowSeconds ? new TradeStatsTracker(opts.tradeStatsWindowSeconds) : null;
+    this.bookDepthLevels = opts.bookDepthLevels ?? 5;
+    this.phaseIntervals = opts.phaseIntervals ?? null;
+    const historyMinutes = opts.historyMinutes ?? 0;
+    this.history = historyMinutes > 0 ? new PriceHistory(historyMinutes * 60_000) : null;
+    this.quoteFilter = new QuoteFilter(opts.maxTickJumpPct ?? null);
+    this.bookRecorder = opts.bookRecorder ?? null;
+  }
+
+  /** Stage markets for a coming period; the poller swaps them in with its first poll of that period */
+  stageMarkets(markets: MonitoredMarkets, period: number): void {
+    this.staged = { markets, period };
+  }
+
+  getMarkets(): MonitoredMarkets {
+    return this.markets;
+  }
+
+  /** Period the monitored markets belong to; a snapshot from a later period means they have resolved */
+  getMarketsPeriod(): number {
+    return this.marketsPeriod;
+  }
+
+  /** Swap in the markets for `period`: tokens of replaced or inactive markets are unsubscribed, new ones subscribed */
+  updateMarkets(markets: MonitoredMarkets, period: number = currentPeriodTimestamp()): void {
+    this.markets = markets;
+    this.marketsPeriod = period;
+    this.latest = null;
+    this.generation++;
+    this.syncSubscriptions();
+  }
+
+  subscribe(tokenId: string, market: MarketKey, side: "up" | "down"): void {
+    if (tokenId) this.subscriptions.set(tokenId, { market, side });
+  }
+
+  unsubscribe(tokenId: string): void {
+    this.subscriptions.delete(tokenId);
+    this.quoteFilter.forget(tokenId);
+  }
+
+  /** Quotes discarded as bad data since start, by reason */
+  dataQuality(): DataQualityCounts {
+    return { ...this.quoteFilter.counts };
+  }
+
+  subscribedTokenIds(): string[] {
+    return [...this.subscriptions.keys()];
+  }
+
+  /** Subscribe exactly the Up/Down tokens of active markets; disabled or closed markets cost no calls */
+  private syncSubscriptions(): void {
+    const wanted = new Map<string, Subscription>();
+    for (const key of MARKET_KEYS) {
+      const market = this.markets[key];
+      if (!market.active || market.closed) continue;
+      const { upId, downId } = upDownTokenIds(market);
+      if (upId) wanted.set(upId, { market: key, side: "up" });
+      if (downId) wanted.set(downId, { market: key, side: "down" });
+    }
+    for (const tokenId of this.subscribedTokenIds()) if (!wanted.has(tokenId)) this.unsubscribe(tokenId);
+    for (const [tokenId, sub] of wanted) this.subscribe(tokenId, sub.market, sub.side);
+  }
+
+  /** Polling interval at this point of the period: open/close phase rate, else the middle (default) rate */
+  intervalAt(nowSec: number, defaultMs: number): number {
+    const phases = this.phaseIntervals;
+    if (!phases) return defaultMs;
+    const elapsed = nowSec % PERIOD_DURATION;
+    if (elapsed < phases.open_seconds) return phases.open_ms;
+    if (PERIOD_DURATION - elapsed <= phases.close_seconds) return phases.close_ms;
+    return phases.middle_ms ?? defaultMs;
+  }
+
+  private msToNextPhase(nowMs: number): number {
+    const phases = this.phaseIntervals;
+    if (!phases) return Infinity;
+    const elapsedMs = nowMs % (PERIOD_DURATION * 1000);
+    const boundaries = [phases.open_seconds, PERIOD_DURATION - phases.close_seconds, PERIOD_DURATION].map((s) => s * 1000);
+    return Math.min(...boundaries.filter((b) => b > elapsedMs)) - elapsedMs;
+  }
+
+  /**
+   * Poll every `intervalMs`, or per phase with `phaseIntervals` (measured start to start, fetches never
+   * overlap) until `stop()`.
+   */
+  start(intervalMs: number): void {
+    if (this.running) return;
+    this.running = true;
+    void (async () => {
+      let lastOkMs: number | null = null;
+      while (this.running) {
+        const startedMs = clock().now();
+        try {
+          this.applyStaged();
+          const generation = this.generation;
+          const snapshot = await this.fetchSnapshot();
+          const okMs = clock().now();
+          const expectedMs = this.intervalAt(Math.floor(startedMs / 1000), intervalMs);
+          if (lastOkMs != null && okMs - lastOkMs > Math.max(MIN_GAP_MS, expectedMs * 3)) {
+            // Every poll reads full books, so this snapshot already is the fresh state after the gap.
+            snapshot.gap = { from_ms: lastOkMs, to_ms: okMs };
+            log(`🕳️ No market data for ${((okMs - lastOkMs) / 1000).toFixed(1)}s - resuming from fresh books`);
+          }
+          lastOkMs = okMs;
+          if (generation === this.generation) this.publish(snapshot);
+        } catch (e) {
+          log(`⚠️ Snapshot fetch failed: ${String(e)}`);
+        }
+        const waitMs = this.intervalAt(Math.floor(startedMs / 1000), intervalMs) - (clock().now() - startedMs);
+        // Never sleep past a phase boundary, so the fast phase starts on time, nor past a staged switch.
+        const untilStagedMs = this.staged ? this.staged.period * 1000 - clock().now() : Infinity;
+        await clock().sleep(Math.max(0, Math.min(waitMs, this.msToNextPhase(clock().now()), untilStagedMs)));
+      }
+    })();
+  }
+
+  stop(): void {
+    this.running = false;
+  }
+
+  /** Next snapshot not yet handed out; waits for the poller if the consumer is ahead */
+  next(): Promise<MarketSnapshot> {
+    const ready = this.latest;
+    if (ready) {
+      this.latest = null;
+      return Promise.resolve(ready);
+    }
+    return new Promise((resolve) => {
+      this.waiter = resolve;
+    });
+  }
+
+  /** Snapshots overwritten before the consumer read them, since start */
+  droppedSnapshots(): number {
+    return this.dropped;
+  }
+
+  private publish(snapshot: MarketSnapshot): void {
+    if (this.waiter) {
+      const resolve = this.waiter;
+      this.waiter = null;
+      resolve(snapshot);
+      return;
+    }
+    if (this.latest) {
+      this.dropped++;
+      // Keep the gap marker if the snapshot carrying it is replaced before anyone read it.
+      if (this.latest.gap && !snapshot.gap) snapshot.gap = this.latest.gap;
+    }
+    this.latest = snapshot;
+  }
+
+  /** Swap in staged markets once their period has started */
+  private applyStaged(): void {
+    if (!this.staged || currentPeriodTimestamp() < this.staged.period) return;
+    const { markets, period } = this.staged;
+    this.staged = null;
+    this.updateMarkets(markets, period);
+    log(`🔀 Switched to pre-discovered markets for period ${period}`);
+  }
+
+  async fetchSnapshot(): Promise<MarketSnapshot> {
+    // Period as of the request, so a poll straddling the boundary isn't labelled with the next period.
+    const period = currentPeriodTimestamp();
+    const { eth, btc, solana, xrp } = this.markets;
+    const subscribed = [...this.subscriptions];
+    const recorder = this.bookRecorder;
+    const phase = recorder?.phase(period, nowSeconds()) ?? null;
+    const prices = await Promise.all(
+      subscribed.map(([tokenId, sub]) =>
+        fetchTokenPrice(
+          this.api,
+          tokenId,
+          this.bookDepthLevels,
+          recorder && phase ? (book) => recorder.record(period, phase, clock().now(), sub.market, sub.side, tokenId, book) : undefined
+        )
+      )
+    );
+    // One pass over the subscriptions, filtering in place, instead of a scan per market.
+    const sides: Record<MarketKey, { up: TokenPrice | null; down: TokenPrice | null }> = {
+      eth: { up: null, down: null },
+      btc: { up: null, down: null },
+      solana: { up: null, down: null },
+      xrp: { up: null, down: null },
+    };
+    for (let i = 0; i < subscribed.length; i++) {
+      const sub = subscribed[i][1];
+      sides[sub.market][sub.side] = this.quoteFilter.apply(prices[i]);
+    }
+    const snapshot = buildSnapshot(period, PERIOD_DURATION, eth, btc, solana, xrp, sides.eth, sides.btc, sides.solana, sides.xrp);
+    if (this.tradeStats) await this.attachTradeStats(snapshot, this.tradeStats);
+    if (this.history) {
+      this.history.record(snapshot, clock().now());
+      snapshot.history = this.history;
+    }
+    return snapshot;
+  }
+
+  /** Poll recent prints per live market and attach rolling volume/imbalance to each token */
+  private async attachTradeStats(snapshot: MarketSnapshot, tracker: TradeStatsTracker): Promise<void> {
+    const live = [snapshot.btc_market, snapshot.eth_market, snapshot.solana_market, snapshot.xrp_market].filter(
+      (m) => m.up_token || m.down_token
+    );
+    await Promise.all(
+      live.map(async (m) => {
+        try {
+          tracker.ingest(await this.api.getTrades(m.condition_id));
+        } catch {
+          /* keep previous window on transient errors */
+        }
+      })
+    );
+    tracker.prune();
+    for (const m of live) {
+      if (m.up_token) m.up_token.trade_stats = tracker.stats(m.up_token.token_id);
+      if (m.down_token) m.down_token.trade_stats = tracker.stats(m.down_token.token_id);
+    }
+  }
+}
+
+/**
+ * Attaches `period_open` to each market of a snapshot: the first Up/Down quotes observed in the period
+ * (kept for the rest of it) and the spot feed's period open, so strategies and reports can work relative
+ * to the open without tracking baselines themselves.
+ */
+export class PeriodOpenTracker {
+  private spotFeed: SpotFeed | null;
+  /** `${period}_${condition_id}` -> open */
+  private opens: Map<string, PeriodOpen> = new Map();
+  private prunedPeriod: number | null = null;
+
+  constructor(spotFeed: SpotFeed | null) {
+    this.spotFeed = spotFeed;
+  }
+
+  enrich(snapshot: MarketSnapshot): void {
+    const period = snapshot.period_timestamp;
+    const markets: Array<[Asset, MarketData]> = [
+      ["BTC", snapshot.btc_market],
+      ["ETH", snapshot.eth_market],
+      ["SOL", snapshot.solana_market],
+      ["XRP", snapshot.xrp_market],
+    ];
+    for (const [asset, market] of markets) {
+      if (!market.up_token && !market.down_token) continue;
+      const key = `${period}_${market.condition_id}`;
+      let open = this.opens.get(key) ?? market.period_open ?? null;
+      if (!open) {
+        open = {
+          up_bid: market.up_token?.bid ?? null,
+          up_ask: market.up_token?.ask ?? null,
+          down_bid: market.down_token?.bid ?? null,
+          down_ask: market.down_token?.ask ?? null,
+          spot: null,
+          observed_elapsed_seconds: PERIOD_DURATION - snapshot.time_remaining_seconds,
+        };
+      }
+      open.spot ??= this.spotFeed?.periodOpen(asset, period) ?? null;
+      this.opens.set(key, open);
+      market.period_open = open;
+    }
+    if (this.prunedPeriod === period) return;
+    this.prunedPeriod = period;
+    for (const key of this.opens.keys()) {
+      if (Number(key.split("_")[0]) < period - PERIOD_DURATION) this.opens.delete(key);
+    }
+  }
+}
+
+/** "$0.00".."$1.00" by cent, so the per-tick prices line doesn't format numbers */
+const CENT_LABELS = Array.from({ length: 101 }, (_, c) => `$${(c / 100).toFixed(2)}`);
+
+function fmtPrice(price: number | null): string {
+  if (price == null) return "N/A";
+  const cents = Math.round(price * 100);
+  return cents >= 0 && cents <= 100 ? CENT_LABELS[cents] : `$${price.toFixed(2)}`;
+}
+
 /** Format one token as "bid/ask" e.g. "$0.13/$0.14" */
 function fmtBidAsk(token: TokenPrice | null | undefined): string {
   if (!token) return "N/A";
-  const bid = token.bid != null ? `$${token.bid.toFixed(2)}` : "N/A";
-  const ask = token.ask != null ? `$${token.ask.toFixed(2)}` : "N/A";
-  return `${bid}/${ask}`;
+  return `${fmtPrice(token.bid)}/${fmtPrice(token.ask)}`;
 }
 
 export function formatPrices(snap: MarketSnapshot): string {
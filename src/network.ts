@@ -0,0 +1,197 @@
+import axios from "axios";
+import { Resolver, setServers } from "dns";
+import http from "http";
+import https from "https";
+import { connect as netConnect, isIP } from "net";
+import type { Socket } from "net";
+import { connect as tlsConnect } from "tls";
+import type { ConnectionOptions } from "tls";
+
+/** Egress settings applied to every HTTP client in the process (Polymarket APIs, spot feeds, Polygon RPC) */
+export interface NetworkConfig {
+  /** Default proxy: http://[user:pass@]host:port or socks5://[user:pass@]host:port */
+  proxy_url?: string | null;
+  /** Per-host proxy: exact host or ".suffix" -> proxy URL, or null to connect directly */
+  proxy_overrides?: Record<string, string | null> | null;
+  /** Resolve hostnames through these DNS servers instead of the system resolver */
+  dns_servers?: string[] | null;
+}
+
+type SocketCallback = (err: Error | null, socket?: Socket) => void;
+type LookupCallback = (err: Error | null, address: string | Array<{ address: string; family: number }>, family?: number) => void;
+type Lookup = (hostname: string, options: { all?: boolean }, callback: LookupCallback) => void;
+
+let network: NetworkConfig | null = null;
+let lookup: Lookup | undefined;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Proxy URL for a host after overrides, or null for a direct connection */
+function proxyFor(host: string): URL | null {
+  if (!network) return null;
+  const overrides = network.proxy_overrides ?? {};
+  const key = Object.keys(overrides)
+    .filter((k) => k === host || (k.startsWith(".") && host.endsWith(k)))
+    .sort((a, b) => b.length - a.length)[0];
+  const url = key !== undefined ? overrides[key] : network.proxy_url;
+  return url ? new URL(url) : null;
+}
+
+/** Whether traffic to this URL goes through a proxy (the global WebSocket cannot, so callers fall back to polling) */
+export function isProxied(url: string): boolean {
+  return proxyFor(new URL(url).hostname) != null;
+}
+
+function resolverLookup(resolver: Resolver): Lookup {
+  return (hostname, options, callback) => {
+    const done = (address: string, family: number) =>
+      options.all ? callback(null, [{ address, family }]) : callback(null, address, family);
+    if (isIP(hostname)) return done(hostname, isIP(hostname));
+    resolver.resolve4(hostname, (err, v4) => {
+      if (!err && v4.length) return done(v4[0], 4);
+      resolver.resolve6(hostname, (err6, v6) => {
+        if (!err6 && v6.length) return done(v6[0], 6);
+        callback(err ?? err6, "", 4);
+      });
+    });
+  };
+}
+
+/** Resolve with the first `need(buf)` bytes the peer sends; anything after is pushed back onto the socket */
+function readUntil(socket: Socket, need: (buf: Buffer) => number): Promise<Buffer> {
+  return new Promise((resolve, reject) => {
+    let buf = Buffer.alloc(0);
+    const cleanup = () => {
+      socket.off("data", onData);
+      socket.off("error", onError);
+      socket.off("close", onClose);
+      socket.pause();
+    };
+    const onData = (chunk: Buffer) => {
+      buf = Buffer.concat([buf, chunk]);
+      const n = need(buf);
+      if (n < 0) return;
+      cleanup();
+      if (n < buf.length) socket.unshift(buf.subarray(n));
+      resolve(buf.subarray(0, n));
+    };
+    const onError = (e: Error) => {
+      cleanup();
+      reject(e);
+    };
+    const onClose = () => {
+      cleanup();
+      reject(new Error("proxy closed the connection"));
+    };
+    socket.on("data", onData);
+    socket.once("error", onError);
+    socket.once("close", onClose);
+  });
+}
+
+async function httpConnect(socket: Socket, proxy: URL, host: string, port: number): Promise<void> {
+  const auth = proxy.username
+    ? `Proxy-Authorization: Basic ${Buffer.from(`${decodeURIComponent(proxy.username)}:${decodeURIComponent(proxy.password)}`).toString("base64")}\r\n`
+    : "";
+  socket.write(`CONNECT ${host}:${port} HTTP/1.1\r\nHost: ${host}:${port}\r\n${auth}\r\n`);
+  const head = await readUntil(socket, (b) => {
+    const end = b.indexOf("\r\n\r\n");
+    return end < 0 ? -1 : end + 4;
+  });
+  const statusLine = head.toString("latin1").split("\r\n")[0];
+  if (statusLine.split(" ")[1] !== "200") throw new Error(`proxy CONNECT ${host}:${port} failed: ${statusLine}`);
+}
+
+/** SOCKS5 CONNECT by hostname (the proxy resolves it), with optional username/password auth */
+async function socks5Connect(socket: Socket, proxy: URL, host: string, port: number): Promise<void> {
+  const user = decodeURIComponent(proxy.username);
+  const pass = decodeURIComponent(proxy.password);
+  socket.write(Buffer.from(user ? [5, 2, 0, 2] : [5, 1, 0]));
+  const [, method] = await readUntil(socket, (b) => (b.length >= 2 ? 2 : -1));
+  if (method === 2) {
+    const u = Buffer.from(user);
+    const p = Buffer.from(pass);
+    socket.write(Buffer.concat([Buffer.from([1, u.length]), u, Buffer.from([p.length]), p]));
+    const [, status] = await readUntil(socket, (b) => (b.length >= 2 ? 2 : -1));
+    if (status !== 0) throw new Error("SOCKS5 authentication failed");
+  } else if (method !== 0) {
+    throw new Error("SOCKS5 proxy accepted none of the offered auth methods");
+  }
+  const h = Buffer.from(host);
+  socket.write(Buffer.concat([Buffer.from([5, 1, 0, 3, h.length]), h, Buffer.from([port >> 8, port & 0xff])]));
+  const reply = await readUntil(socket, (b) => {
+    if (b.length < 5) return -1;
+    const addrLen = b[3] === 1 ? 4 : b[3] === 4 ? 16 : 1 + b[4];
+    return b.length >= 6 + addrLen ? 6 + addrLen : -1;
+  });
+  if (reply[1] !== 0) throw new Error(`SOCKS5 connect to ${host}:${port} failed (code ${reply[1]})`);
+}
+
+/** TCP connection to host:port, tunnelled through the host's proxy when one applies */
+function openSocket(host: string, port: number, callback: SocketCallback): void {
+  const proxy = proxyFor(host);
+  if (!proxy) {
+    callback(null, netConnect({ host, port, lookup }));
+    return;
+  }
+  const socks = proxy.protocol.startsWith("socks");
+  const proxySocket = netConnect({ host: proxy.hostname, port: Number(proxy.port) || (socks ? 1080 : 80), lookup });
+  const fail = (e: Error) => {
+    proxySocket.destroy();
+    callback(e);
+  };
+  proxySocket.once("error", fail);
+  proxySocket.once("connect", () => {
+    proxySocket.off("error", fail);
+    (socks ? socks5Connect : httpConnect)(proxySocket, proxy, host, port).then(() => callback(null, proxySocket), fail);
+  });
+}
+
+/**
+ * Route all outgoing HTTP(S) through `network` settings: replaces the global agents (used by axios, the
+ * CLOB client and ethers) with ones that honour proxies and custom DNS. No-op when nothing is configured.
+ */
+export function configureNetwork(cfg: NetworkConfig | null | undefined): void {
+  if (!cfg || (!cfg.proxy_url && !cfg.proxy_overrides && !cfg.dns_servers?.length)) return;
+  network = cfg;
+  if (cfg.dns_servers?.length) {
+    const resolver = new Resolver();
+    resolver.setServers(cfg.dns_servers);
+    setServers(cfg.dns_servers);
+    lookup = resolverLookup(resolver);
+  }
+
+  const httpAgent = new http.Agent({ keepAlive: true });
+  Object.assign(httpAgent, {
+    createConnection: (opts: { host?: string | null; port?: number | string | null }, callback: SocketCallback) => {
+      openSocket(opts.host ?? "localhost", Number(opts.port) || 80, callback);
+      return undefined;
+    },
+  });
+  const httpsAgent = new https.Agent({ keepAlive: true });
+  Object.assign(httpsAgent, {
+    createConnection: (opts: ConnectionOptions & { host?: string | null; port?: number | string | null }, callback: SocketCallback) => {
+      const host = opts.host ?? "localhost";
+      openSocket(host, Number(opts.port) || 443, (err, socket) => {
+        if (err || !socket) return callback(err ?? new Error(`could not connect to ${host}`));
+        callback(null, tlsConnect({ ...opts, host, socket, servername: opts.servername ?? (isIP(host) ? undefined : host) }));
+      });
+      return undefined;
+    },
+  });
+
+  http.globalAgent = httpAgent;
+  https.globalAgent = httpsAgent;
+  axios.defaults.proxy = false;
+  axios.defaults.httpAgent = httpAgent;
+  axios.defaults.httpsAgent = httpsAgent;
+
+  const overrides = Object.keys(cfg.proxy_overrides ?? {}).length;
+  log(
+    `🌐 Network: proxy ${cfg.proxy_url ? new URL(cfg.proxy_url).host : "none"}` +
+      (overrides ? `, ${overrides} host override(s)` : "") +
+      (cfg.dns_servers?.length ? `, DNS ${cfg.dns_servers.join(", ")}` : "")
+  );
+}
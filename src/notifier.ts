@@ -0,0 +1,148 @@
+import axios from "axios";
+import { NotificationTemplates } from "./templates.js";
+import type { TemplateVars } from "./templates.js";
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** How urgent an alert kind is: `low` is logged only, `medium` and `critical` also go to channels that accept them */
+export type AlertSeverity = "low" | "medium" | "critical";
+
+/** Routing of alerts to channels by severity (`alerts.routing`) */
+export interface AlertRoutingConfig {
+  /** Severity per alert kind, overriding `DEFAULT_SEVERITIES` */
+  severities?: Record<string, AlertSeverity> | null;
+  /** Lowest severity each channel (by name: `webhook`, `telegram`, custom) receives; default `medium`, `telegram` `critical` */
+  channels?: Record<string, AlertSeverity> | null;
+}
+
+const SEVERITY_RANK: Record<AlertSeverity, number> = { low: 0, medium: 1, critical: 2 };
+
+/** Built-in severity per alert kind; kinds not listed are `medium` */
+export const DEFAULT_SEVERITIES: Record<string, AlertSeverity> = {
+  endpoint_failover: "low",
+  snapshot_gap: "medium",
+  slippage: "low",
+  fill_deviation: "low",
+  uma_dispute_over: "low",
+  failover_standby: "critical",
+  failover_takeover: "critical",
+  exchange_halt: "critical",
+  auth_failure: "critical",
+  redemption_failed: "critical",
+  uma_dispute: "critical",
+};
+
+const DEFAULT_CHANNEL_SEVERITY: Record<string, AlertSeverity> = { telegram: "critical" };
+
+/** Where alerts go besides the log; register custom ones with `BotRuntimeBuilder.alertChannel` */
+export interface AlertChannel {
+  readonly name: string;
+  send(key: string, text: string): unknown;
+}
+
+/** POST to a Slack/Discord-style incoming webhook (body carries both `text` and `content`) */
+export class WebhookChannel implements AlertChannel {
+  readonly name = "webhook";
+  private url: string;
+
+  constructor(url: string) {
+    this.url = url;
+  }
+
+  send(_key: string, text: string): Promise<unknown> {
+    return axios.post(this.url, { text, content: text }, { timeout: 5_000 });
+  }
+}
+
+/** Telegram bot message to one chat (`alerts.telegram`) */
+export class TelegramChannel implements AlertChannel {
+  readonly name = "telegram";
+  private botToken: string;
+  private chatId: string;
+
+  constructor(botToken: string, chatId: string) {
+    this.botToken = botToken;
+    this.chatId = chatId;
+  }
+
+  send(_key: string, text: string): Promise<unknown> {
+    return axios.post(`https://api.telegram.org/bot${this.botToken}/sendMessage`, { chat_id: this.chatId, text }, { timeout: 5_000 });
+  }
+}
+
+/**
+ * Operator alerts: always logged, and sent to every channel (the `webhook_url` one when configured).
+ * Each alert key fires at most once per `minIntervalMs`; suppressed repeats are counted into the next message.
+ * Wording comes from templates per alert kind (`alerts.templates` over `DEFAULT_TEMPLATES`); which channels
+ * get an alert depends on its kind's severity (`alerts.routing`).
+ */
+export class Notifier {
+  private channels: AlertChannel[] = [];
+  private minIntervalMs: number;
+  private templates: NotificationTemplates;
+  private routing: AlertRoutingConfig;
+  private lastSent: Map<string, number> = new Map();
+  private suppressed: Map<string, number> = new Map();
+
+  constructor(
+    webhookUrl: string | null,
+    minIntervalMs: number,
+    templates: Record<string, string> | null = null,
+    routing: AlertRoutingConfig | null = null
+  ) {
+    if (webhookUrl) this.channels.push(new WebhookChannel(webhookUrl));
+    this.minIntervalMs = minIntervalMs;
+    this.templates = new NotificationTemplates(templates);
+    this.routing = routing ?? {};
+  }
+
+  /** Configured severity of an alert kind */
+  severity(kind: string): AlertSeverity {
+    return this.routing.severities?.[kind] ?? DEFAULT_SEVERITIES[kind] ?? "medium";
+  }
+
+  private accepts(channel: AlertChannel, severity: AlertSeverity): boolean {
+    const min = this.routing.channels?.[channel.name] ?? DEFAULT_CHANNEL_SEVERITY[channel.name] ?? "medium";
+    return SEVERITY_RANK[severity] >= SEVERITY_RANK[min];
+  }
+
+  addChannel(channel: AlertChannel): void {
+    this.channels.push(channel);
+  }
+
+  /** Alert of template `kind` filled from `vars`; `key` (e.g. kind plus asset) is what gets throttled */
+  alert(key: string, kind: string, vars: TemplateVars = {}): void {
+    const now = Date.now();
+    const last = this.lastSent.get(key);
+    if (last != null && now - last < this.minIntervalMs) {
+      this.suppressed.set(key, (this.suppressed.get(key) ?? 0) + 1);
+      return;
+    }
+    const repeats = this.suppressed.get(key) ?? 0;
+    this.lastSent.set(key, now);
+    this.suppressed.delete(key);
+    const text =
+      this.templates.render("alert", { message: this.templates.render(kind, vars) }) +
+      (repeats > 0 ? this.templates.render("suppressed", { count: repeats }) : "");
+    const severity = this.severity(kind);
+    log(text);
+    this.send(key, text, (channel) => this.accepts(channel, severity));
+  }
+
+  /** Send an informational message (e.g. a periodic summary) to every channel: not logged, not throttled */
+  info(key: string, text: string): void {
+    this.send(key, text);
+  }
+
+  private send(key: string, text: string, accepts: (channel: AlertChannel) => boolean = () => true): void {
+    for (const channel of this.channels) {
+      if (!accepts(channel)) continue;
+      // A failing channel only costs its own delivery.
+      Promise.resolve()
+        .then(() => channel.send(key, text))
+        .catch((e) => log(`⚠️ Alert channel ${channel.name} failed: ${String(e)}`));
+    }
+  }
+}
@@ -0,0 +1,118 @@
+import { ethers } from "ethers";
+import { createWallet } from "./clob.js";
+import type { Config } from "./config.js";
+
+/** Polymarket contracts on Polygon mainnet */
+export const CTF_ADDRESS = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+export const USDC_ADDRESS = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+const EXCHANGE_SPENDERS = [
+  "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E", // CTF Exchange
+  "0xC5d563A36AE78145C45a50134d48A1215220f80a", // Neg Risk CTF Exchange
+  "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296", // Neg Risk Adapter
+];
+
+const ERC20_ABI = [
+  "function allowance(address owner, address spender) view returns (uint256)",
+  "function approve(address spender, uint256 amount) returns (bool)",
+];
+const CTF_ABI = [
+  "function isApprovedForAll(address owner, address operator) view returns (bool)",
+  "function setApprovalForAll(address operator, bool approved)",
+  "function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets)",
+];
+const SAFE_ABI = [
+  "function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures) payable returns (bool)",
+];
+
+export const erc20 = new ethers.utils.Interface(ERC20_ABI);
+export const ctf = new ethers.utils.Interface(CTF_ABI);
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Account that holds positions and collateral; `exec` sends a call from it (directly or through its proxy) */
+export interface OnchainAccount {
+  readonly address: string;
+  readonly signer: ethers.Wallet;
+  exec(to: string, data: string): Promise<ethers.providers.TransactionReceipt>;
+}
+
+/** Plain EOA: the signing key holds funds and sends its own transactions */
+class EoaAccount implements OnchainAccount {
+  readonly address: string;
+  readonly signer: ethers.Wallet;
+
+  constructor(signer: ethers.Wallet) {
+    this.signer = signer;
+    this.address = signer.address;
+  }
+
+  async exec(to: string, data: string): Promise<ethers.providers.TransactionReceipt> {
+    const tx = await this.signer.sendTransaction({ to, data });
+    return tx.wait();
+  }
+}
+
+/**
+ * Polymarket browser-wallet proxy (signature type 2): a Gnosis Safe owned by the signing key. Calls go
+ * through `execTransaction`, sent by the owner, which pays gas. Because the owner is the sender, the
+ * Safe accepts a pre-validated signature (r = owner, s = 0, v = 1) without signing the Safe tx hash.
+ */
+class SafeAccount implements OnchainAccount {
+  readonly address: string;
+  readonly signer: ethers.Wallet;
+  private safe: ethers.Contract;
+
+  constructor(signer: ethers.Wallet, safeAddress: string) {
+    this.signer = signer;
+    this.address = ethers.utils.getAddress(safeAddress);
+    this.safe = new ethers.Contract(this.address, SAFE_ABI, signer);
+  }
+
+  async exec(to: string, data: string): Promise<ethers.providers.TransactionReceipt> {
+    const signature = ethers.utils.hexConcat([
+      ethers.utils.hexZeroPad(this.signer.address, 32),
+      ethers.constants.HashZero,
+      "0x01",
+    ]);
+    const zero = ethers.constants.AddressZero;
+    // safeTxGas = gasPrice = 0 makes the Safe revert the whole tx if the inner call fails.
+    const tx = await this.safe.execTransaction(to, 0, data, 0, 0, 0, 0, zero, zero, signature);
+    return tx.wait();
+  }
+}
+
+/** Account for on-chain operations: the proxy Safe for signature type 2, else the signing EOA */
+export function createOnchainAccount(cfg: Config["polymarket"], rpcUrl: string): OnchainAccount {
+  if (!cfg.private_key) throw new Error("private_key is required for on-chain operations");
+  const signer = createWallet(cfg.private_key).connect(new ethers.providers.JsonRpcProvider(rpcUrl));
+  if (cfg.proxy_wallet_address) {
+    if (cfg.signature_type === 2) return new SafeAccount(signer, cfg.proxy_wallet_address);
+    throw new Error(
+      `On-chain operations through proxy wallets are supported for signature_type 2 (Safe) only, got ${cfg.signature_type ?? "unset"}`
+    );
+  }
+  return new EoaAccount(signer);
+}
+
+/** Grant the Polymarket exchanges USDC allowance and CTF operator approval where missing */
+export async function ensureApprovals(account: OnchainAccount): Promise<number> {
+  const usdc = new ethers.Contract(USDC_ADDRESS, ERC20_ABI, account.signer);
+  const tokens = new ethers.Contract(CTF_ADDRESS, CTF_ABI, account.signer);
+  let sent = 0;
+  for (const spender of EXCHANGE_SPENDERS) {
+    const allowance = (await usdc.allowance(account.address, spender)) as ethers.BigNumber;
+    if (allowance.lt(ethers.constants.MaxUint256.div(2))) {
+      const receipt = await account.exec(USDC_ADDRESS, erc20.encodeFunctionData("approve", [spender, ethers.constants.MaxUint256]));
+      log(`✅ USDC approved for ${spender} (tx ${receipt.transactionHash})`);
+      sent++;
+    }
+    if (!(await tokens.isApprovedForAll(account.address, spender))) {
+      const receipt = await account.exec(CTF_ADDRESS, ctf.encodeFunctionData("setApprovalForAll", [spender, true]));
+      log(`✅ Outcome tokens approved for ${spender} (tx ${receipt.transactionHash})`);
+      sent++;
+    }
+  }
+  return sent;
+}
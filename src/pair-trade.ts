@@ -0,0 +1,132 @@
+import { clock } from "./clock.js";
+import type { PairTradeConfig } from "./config.js";
+import type { ClosedPositionRecord } from "./reports.js";
+import { formatUsd } from "./money.js";
+import type { Storage } from "./storage.js";
+import type { Strategy } from "./strategy.js";
+import type { Trader } from "./trader.js";
+import type { BuyOpportunity, MarketSnapshot, TokenPrice, TokenType } from "./types.js";
+import { marketForTokenType, tokenPriceForType, tokenTypeDisplayName } from "./types.js";
+
+const PERIOD_DURATION = 900;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+function mid(p: TokenPrice | null | undefined): number | null {
+  return p?.bid != null && p.ask != null ? (p.bid + p.ask) / 2 : null;
+}
+
+/**
+ * Correlation pair trade: BTC and ETH Up usually price alike, so when BTC Up mid minus ETH Up mid sits
+ * `entry_z` standard deviations from its rolling mean, buy the cheap asset's Up and the rich asset's
+ * Down, equal shares each so a move both assets share pays one leg either way. The two legs carry the
+ * same `pair=<id>` note and are reported together (`--report pairs`).
+ */
+export class PairTradeStrategy implements Strategy {
+  readonly name = "pair_trade";
+  private cfg: PairTradeConfig;
+  private trader: Trader;
+  private storage: Storage;
+  private spreads: number[] = [];
+  private pairsThisPeriod: Map<number, number> = new Map();
+  private pairSeq = 0;
+
+  constructor(cfg: PairTradeConfig, trader: Trader, storage: Storage) {
+    this.cfg = cfg;
+    this.trader = trader;
+    this.storage = storage;
+  }
+
+  async onSnapshot(snapshot: MarketSnapshot): Promise<void> {
+    const btcUp = mid(snapshot.btc_market.up_token);
+    const ethUp = mid(snapshot.eth_market.up_token);
+    if (btcUp == null || ethUp == null) return;
+    const spread = btcUp - ethUp;
+    const n = this.spreads.length;
+    const mean = n > 0 ? this.spreads.reduce((s, v) => s + v, 0) / n : 0;
+    const sd = n > 1 ? Math.sqrt(this.spreads.reduce((s, v) => s + (v - mean) ** 2, 0) / (n - 1)) : 0;
+    this.spreads.push(spread);
+    if (this.spreads.length > (this.cfg.lookback_samples ?? 1800)) this.spreads.shift();
+    if (n < (this.cfg.min_samples ?? 300) || sd <= 0) return;
+
+    const elapsed = PERIOD_DURATION - snapshot.time_remaining_seconds;
+    if (elapsed < (this.cfg.start_seconds ?? 60) || elapsed > (this.cfg.stop_seconds ?? 600)) return;
+    const period = snapshot.period_timestamp;
+    if ((this.pairsThisPeriod.get(period) ?? 0) >= (this.cfg.max_pairs_per_period ?? 1)) return;
+    const z = (spread - mean) / sd;
+    if (Math.abs(z) < this.cfg.entry_z) return;
+
+    // BTC Up rich against ETH Up: buy ETH Up and BTC Down; the other way round otherwise.
+    const legs: [TokenType, TokenType] = z > 0 ? ["EthUp", "BtcDown"] : ["BtcUp", "EthDown"];
+    if (legs.some((t) => this.trader.hasActivePosition(period, t))) return;
+    const asks = legs.map((t) => tokenPriceForType(snapshot, t)?.ask ?? null);
+    if (asks[0] == null || asks[1] == null) return;
+    const cost = asks[0] + asks[1];
+    if (cost > (this.cfg.max_pair_cost ?? 1)) return;
+
+    const pairId = `pair-${++this.pairSeq}`;
+    this.pairsThisPeriod.set(period, (this.pairsThisPeriod.get(period) ?? 0) + 1);
+    const note = `pair=${pairId} z=${z.toFixed(2)} spread=${spread.toFixed(4)} mean=${mean.toFixed(4)}`;
+    log(
+      `🔀 PAIR ${pairId}: BTC Up - ETH Up mid ${spread.toFixed(4)} vs mean ${mean.toFixed(4)} (z ${z.toFixed(2)}) - ` +
+        `buying ${this.cfg.shares} ${tokenTypeDisplayName(legs[0])} @ $${asks[0].toFixed(2)} + ${tokenTypeDisplayName(legs[1])} @ $${asks[1].toFixed(2)}`
+    );
+    this.storage.appendJsonl("pairs.jsonl", {
+      ts_ms: clock().now(),
+      pair_id: pairId,
+      period_timestamp: period,
+      legs: legs.map((t, i) => ({ token_type: t, price: asks[i], shares: this.cfg.shares })),
+      z,
+      spread,
+      mean,
+    });
+    for (let i = 0; i < legs.length; i++) {
+      const market = marketForTokenType(snapshot, legs[i]);
+      const token = legs[i].endsWith("Up") ? market.up_token : market.down_token;
+      if (!token) continue;
+      const opp: BuyOpportunity = {
+        condition_id: market.condition_id,
+        token_id: token.token_id,
+        token_type: legs[i],
+        bid_price: asks[i] as number,
+        period_timestamp: period,
+        time_remaining_seconds: snapshot.time_remaining_seconds,
+        time_elapsed_seconds: elapsed,
+        use_market_order: false,
+        decision_ms: clock().now(),
+        reason: "pair_trade",
+        note,
+      };
+      try {
+        await this.trader.executeLimitBuy(opp, opp.bid_price, this.cfg.shares);
+      } catch (e) {
+        log(`Error executing pair ${pairId} leg ${tokenTypeDisplayName(legs[i])}: ${String(e)}`);
+      }
+    }
+  }
+}
+
+/** Closed pair trades as one unit: both legs' cost and realized PnL, per pair and in total */
+export function pairsReport(storage: Storage): string {
+  const byPair = new Map<string, ClosedPositionRecord[]>();
+  for (const p of storage.readJsonl<ClosedPositionRecord>("positions.jsonl")) {
+    const id = p.reason === "pair_trade" ? /pair=(\S+)/.exec(p.note ?? "")?.[1] : undefined;
+    if (id) byPair.set(id, [...(byPair.get(id) ?? []), p]);
+  }
+  const lines = ["Pair trades (both legs as one position)", "   pair        legs                      cost         PnL"];
+  let total = 0;
+  let wins = 0;
+  for (const [id, legs] of byPair) {
+    const cost = legs.reduce((s, l) => s + l.investment_amount, 0);
+    const pnl = legs.reduce((s, l) => s + l.realized_pnl, 0);
+    total += pnl;
+    if (pnl > 0) wins++;
+    const names = legs.map((l) => l.token_type).join(" + ");
+    lines.push(`   ${id.padEnd(10)}  ${names.padEnd(22)} ${formatUsd(cost).padStart(10)} ${formatUsd(pnl).padStart(11)}${legs.length < 2 ? "  (one leg)" : ""}`);
+  }
+  if (byPair.size === 0) lines.push("   (no closed pair trades yet)");
+  else lines.push(`   ${byPair.size} pair(s), ${wins} profitable, total ${formatUsd(total)}`);
+  return lines.join("\n");
+}
@@ -0,0 +1,63 @@
+import type { Asset, TokenType } from "./types.js";
+import { assetForTokenType } from "./types.js";
+
+/** Shares held (or bid for) in one token and what they cost */
+export interface Holding {
+  period_timestamp: number;
+  token_type: TokenType;
+  units: number;
+  cost: number;
+}
+
+/** One market (asset and period) in the portfolio view: PnL if it resolves each way */
+export interface MarketOutcome {
+  period_timestamp: number;
+  asset: Asset;
+  pnl_if_up: number;
+  pnl_if_down: number;
+}
+
+/** Worst-case PnL of everything open, over every combination of Up/Down resolutions */
+export interface PortfolioView {
+  markets: MarketOutcome[];
+  /** Total PnL in the worst combination (a loss is negative) */
+  worst_case_pnl: number;
+  /** Total PnL in the best combination */
+  best_case_pnl: number;
+  /** The worst combination, `<asset>@<period>` -> side it resolves to */
+  worst_case: Record<string, "up" | "down">;
+  /** Cost of shares not tied to a market here (strategy-managed inventory), counted as lost either way */
+  unattributed_cost: number;
+}
+
+/**
+ * Resolve every holding both ways per market. Markets resolve independently and each one's PnL depends
+ * only on its own outcome, so the worst of the 2^n combinations is each market's worse side summed;
+ * an Up position hedged by the same market's Down counts only the net loss, not both notionals.
+ */
+export function portfolioView(holdings: Holding[], unattributedCost = 0): PortfolioView {
+  const byMarket = new Map<string, MarketOutcome>();
+  for (const h of holdings) {
+    if (!(h.units > 0)) continue;
+    const asset = assetForTokenType(h.token_type);
+    const key = `${asset}@${h.period_timestamp}`;
+    const m = byMarket.get(key) ?? { period_timestamp: h.period_timestamp, asset, pnl_if_up: 0, pnl_if_down: 0 };
+    const up = h.token_type.endsWith("Up");
+    m.pnl_if_up += (up ? h.units : 0) - h.cost;
+    m.pnl_if_down += (up ? 0 : h.units) - h.cost;
+    byMarket.set(key, m);
+  }
+  const view: PortfolioView = {
+    markets: [...byMarket.values()],
+    worst_case_pnl: -unattributedCost,
+    best_case_pnl: -unattributedCost,
+    worst_case: {},
+    unattributed_cost: unattributedCost,
+  };
+  for (const [key, m] of byMarket) {
+    view.worst_case_pnl += Math.min(m.pnl_if_up, m.pnl_if_down);
+    view.best_case_pnl += Math.max(m.pnl_if_up, m.pnl_if_down);
+    view.worst_case[key] = m.pnl_if_up <= m.pnl_if_down ? "up" : "down";
+  }
+  return view;
+}
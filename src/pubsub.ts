@@ -0,0 +1,165 @@
+import { connect as netConnect } from "net";
+import type { Socket } from "net";
+import { connect as tlsConnect } from "tls";
+import type { PubSubConfig } from "./config.js";
+import type { EventSink, TradeEvent } from "./events.js";
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Fire-and-forget publisher over one lazily opened socket. Events published while disconnected are
+ * dropped (a feed for live consumers, not a journal; `data_dir` keeps the record) and the next event
+ * reconnects.
+ */
+abstract class SocketPublisher implements EventSink {
+  abstract readonly name: string;
+  protected url: URL;
+  protected prefix: string;
+  private socket: Socket | null = null;
+  private dropped = 0;
+
+  constructor(url: string, prefix: string) {
+    this.url = new URL(url);
+    this.prefix = prefix;
+  }
+
+  /** Bytes sent once per connection before any publish (handshake, auth) */
+  protected abstract handshake(): Buffer[];
+  protected abstract frame(event: TradeEvent): Buffer;
+  protected abstract defaultPort(): number;
+  protected abstract tls(): boolean;
+
+  write(event: TradeEvent): void {
+    const socket = this.socket ?? this.open();
+    if (!socket.writable) {
+      if (this.dropped++ === 0) log(`⚠️ ${this.name}: not connected - dropping events until it reconnects`);
+      return;
+    }
+    socket.write(this.frame(event));
+  }
+
+  private open(): Socket {
+    const host = this.url.hostname;
+    const port = Number(this.url.port) || this.defaultPort();
+    const socket = this.tls() ? tlsConnect({ host, port, servername: host }) : netConnect({ host, port });
+    this.socket = socket;
+    socket.setNoDelay(true);
+    socket.on("error", (e) => log(`⚠️ ${this.name} ${host}:${port}: ${String(e)}`));
+    socket.on("close", () => {
+      if (this.socket === socket) this.socket = null;
+    });
+    // Replies are not needed; reading keeps the socket from backing up.
+    socket.on("data", () => undefined);
+    socket.on(this.tls() ? "secureConnect" : "connect", () => {
+      if (this.dropped > 0) log(`🔌 ${this.name}: reconnected (${this.dropped} event(s) dropped)`);
+      this.dropped = 0;
+    });
+    for (const chunk of this.handshake()) socket.write(chunk);
+    return socket;
+  }
+
+  close(): void {
+    this.socket?.end();
+    this.socket = null;
+  }
+}
+
+function respCommand(...args: string[]): Buffer {
+  const parts = [`*${args.length}\r\n`];
+  for (const a of args) parts.push(`$${Buffer.byteLength(a)}\r\n${a}\r\n`);
+  return Buffer.from(parts.join(""));
+}
+
+/** Mirrors trade events to Redis pub/sub: `PUBLISH <prefix>:<kind> <event json>`. URL `redis://[user:pass@]host:port[/db]`, `rediss://` for TLS */
+export class RedisPublisher extends SocketPublisher {
+  readonly name = "redis";
+
+  protected handshake(): Buffer[] {
+    const out: Buffer[] = [];
+    const password = decodeURIComponent(this.url.password);
+    const user = decodeURIComponent(this.url.username);
+    if (password) out.push(user ? respCommand("AUTH", user, password) : respCommand("AUTH", password));
+    return out;
+  }
+
+  protected frame(event: TradeEvent): Buffer {
+    return respCommand("PUBLISH", `${this.prefix}:${event.kind}`, JSON.stringify(event));
+  }
+
+  protected defaultPort(): number {
+    return 6379;
+  }
+
+  protected tls(): boolean {
+    return this.url.protocol === "rediss:";
+  }
+}
+
+function mqttLength(n: number): Buffer {
+  const bytes: number[] = [];
+  do {
+    let b = n % 128;
+    n = Math.floor(n / 128);
+    if (n > 0) b |= 0x80;
+    bytes.push(b);
+  } while (n > 0);
+  return Buffer.from(bytes);
+}
+
+function mqttString(s: string): Buffer {
+  const body = Buffer.from(s, "utf-8");
+  const len = Buffer.alloc(2);
+  len.writeUInt16BE(body.length);
+  return Buffer.concat([len, body]);
+}
+
+function mqttPacket(type: number, body: Buffer): Buffer {
+  return Buffer.concat([Buffer.from([type]), mqttLength(body.length), body]);
+}
+
+/**
+ * Mirrors trade events to MQTT (3.1.1, QoS 0): topic `<prefix>/<kind>`, payload the event JSON.
+ * URL `mqtt://[user:pass@]host:port`, `mqtts://` for TLS.
+ */
+export class MqttPublisher extends SocketPublisher {
+  readonly name = "mqtt";
+  private clientId = `polymarket-bot-${process.pid}`;
+
+  protected handshake(): Buffer[] {
+    const user = decodeURIComponent(this.url.username);
+    const password = decodeURIComponent(this.url.password);
+    // Clean session; keep-alive 0 so the broker never expects pings from a publisher that may sit idle.
+    let flags = 0x02;
+    if (user) flags |= 0x80;
+    if (password) flags |= 0x40;
+    const variable = Buffer.concat([mqttString("MQTT"), Buffer.from([0x04, flags, 0x00, 0x00])]);
+    const payload = [mqttString(this.clientId)];
+    if (user) payload.push(mqttString(user));
+    if (password) payload.push(mqttString(password));
+    return [mqttPacket(0x10, Buffer.concat([variable, ...payload]))];
+  }
+
+  protected frame(event: TradeEvent): Buffer {
+    return mqttPacket(0x30, Buffer.concat([mqttString(`${this.prefix}/${event.kind}`), Buffer.from(JSON.stringify(event), "utf-8")]));
+  }
+
+  protected defaultPort(): number {
+    return this.tls() ? 8883 : 1883;
+  }
+
+  protected tls(): boolean {
+    return this.url.protocol === "mqtts:";
+  }
+}
+
+/** Publishers configured under `pubsub` */
+export function createPubSubSinks(cfg: PubSubConfig | null | undefined): EventSink[] {
+  if (!cfg) return [];
+  const prefix = cfg.topic_prefix ?? "polymarket-bot";
+  const sinks: EventSink[] = [];
+  if (cfg.redis_url) sinks.push(new RedisPublisher(cfg.redis_url, prefix));
+  if (cfg.mqtt_url) sinks.push(new MqttPublisher(cfg.mqtt_url, prefix));
+  return sinks;
+}
@@ -0,0 +1,88 @@
+import { ethers } from "ethers";
+import { clock } from "./clock.js";
+import type { RedemptionConfig } from "./config.js";
+import { CTF_ADDRESS, USDC_ADDRESS, ctf } from "./onchain.js";
+import type { OnchainAccount } from "./onchain.js";
+import type { Notifier } from "./notifier.js";
+
+/** Binary Up/Down markets: outcome slots 0b01 and 0b10 */
+const BINARY_INDEX_SETS = [1, 2];
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Redeems resolved positions for USDC through the ConditionalTokens contract. Resolved conditions are
+ * queued and submitted together once Polygon gas is at or below `max_gas_gwei`, or regardless of gas
+ * when the oldest has waited `max_delay_minutes`.
+ */
+export class RedemptionScheduler {
+  private cfg: RedemptionConfig;
+  private account: OnchainAccount;
+  private notifier: Notifier | null;
+  /** condition_id -> queued at (ms) */
+  private queue: Map<string, number> = new Map();
+  private lastCheckMs = 0;
+  private busy = false;
+
+  constructor(cfg: RedemptionConfig, account: OnchainAccount, notifier: Notifier | null = null) {
+    this.cfg = cfg;
+    this.account = account;
+    this.notifier = notifier;
+  }
+
+  enqueue(conditionId: string): void {
+    if (this.queue.has(conditionId)) return;
+    this.queue.set(conditionId, clock().now());
+    log(`🧾 Queued redemption for ${conditionId} (${this.queue.size} pending)`);
+  }
+
+  /** Called every loop iteration; checks gas at most every `check_interval_seconds` */
+  async tick(): Promise<void> {
+    const now = clock().now();
+    if (this.busy || this.queue.size === 0) return;
+    if (now - this.lastCheckMs < (this.cfg.check_interval_seconds ?? 60) * 1000) return;
+    this.lastCheckMs = now;
+
+    const oldest = Math.min(...this.queue.values());
+    const overdue = now - oldest >= (this.cfg.max_delay_minutes ?? 60) * 60_000;
+    let gasGwei: number;
+    try {
+      gasGwei = parseFloat(ethers.utils.formatUnits(await this.account.signer.getGasPrice(), "gwei"));
+    } catch (e) {
+      log(`⚠️ Gas price unavailable, redemptions deferred: ${String(e)}`);
+      return;
+    }
+    const maxGwei = this.cfg.max_gas_gwei ?? 100;
+    if (gasGwei > maxGwei && !overdue) {
+      log(`⛽ Gas ${gasGwei.toFixed(1)} gwei > ${maxGwei} - holding ${this.queue.size} redemption(s)`);
+      return;
+    }
+    log(`⛽ Gas ${gasGwei.toFixed(1)} gwei${overdue ? " (max delay reached)" : ""} - redeeming ${this.queue.size} condition(s)`);
+    this.busy = true;
+    try {
+      for (const conditionId of [...this.queue.keys()]) {
+        try {
+          await this.redeem(conditionId);
+          this.queue.delete(conditionId);
+        } catch (e) {
+          log(`❌ Redemption failed for ${conditionId}: ${String(e)}`);
+          this.notifier?.alert(`redemption_failed_${conditionId}`, "redemption_failed", {
+            condition_id: conditionId,
+            waiting_minutes: Math.round((now - (this.queue.get(conditionId) ?? now)) / 60_000),
+            error: String(e),
+          });
+        }
+      }
+    } finally {
+      this.busy = false;
+    }
+  }
+
+  private async redeem(conditionId: string): Promise<void> {
+    const data = ctf.encodeFunctionData("redeemPositions", [USDC_ADDRESS, ethers.constants.HashZero, conditionId, BINARY_INDEX_SETS]);
+    const receipt = await this.account.exec(CTF_ADDRESS, data);
+    log(`✅ Redeemed ${conditionId} (tx ${receipt.transactionHash}, gas used ${receipt.gasUsed.toString()})`);
+  }
+}
@@ -0,0 +1,35 @@
+/** Why the CLOB refused an order, from its error message */
+export type RejectionType =
+  | "price_out_of_range"
+  | "min_size"
+  | "insufficient_balance"
+  | "market_closed"
+  | "duplicate"
+  | "rate_limited"
+  | "unauthorized"
+  | "other";
+
+const PATTERNS: Array<[RejectionType, RegExp]> = [
+  ["insufficient_balance", /not enough balance|insufficient (balance|funds)|allowance/i],
+  ["min_size", /min(imum)? (order )?size|size .*(lower|less) than|too small/i],
+  ["price_out_of_range", /invalid price|price .*(range|tick)|tick size|breaks minimum tick/i],
+  ["market_closed", /market (is )?(closed|resolved|not found)|orderbook .*does not exist|not accepting orders/i],
+  ["duplicate", /duplicate|already (exists|placed)/i],
+  ["rate_limited", /rate limit|too many requests|\b429\b/i],
+  ["unauthorized", /unauthori[sz]ed|invalid (api )?(key|signature|credentials)|\b401\b|forbidden|\b403\b/i],
+];
+
+/**
+ * Types for which resubmitting the same order in the same market cannot succeed; the trader blocks
+ * further entries in that token for the rest of the period instead of retrying into the same error.
+ */
+export const PERMANENT_REJECTIONS: ReadonlySet<RejectionType> = new Set<RejectionType>(["price_out_of_range", "min_size", "market_closed"]);
+
+export function classifyRejection(e: unknown): RejectionType {
+  const body = (e as { response?: { data?: unknown } })?.response?.data;
+  const text = `${e instanceof Error ? e.message : String(e)} ${body != null ? JSON.stringify(body) : ""}`;
+  const status = (e as { response?: { status?: number } })?.response?.status;
+  if (status === 429) return "rate_limited";
+  for (const [type, pattern] of PATTERNS) if (pattern.test(text)) return type;
+  return "other";
+}
@@ -0,0 +1,129 @@
+import { existsSync, readdirSync, readFileSync, statSync } from "fs";
+import { join } from "path";
+import type { VirtualClock } from "./clock.js";
+import type { PriceHistory } from "./history.js";
+import { formatTime } from "./money.js";
+import type { SpotFeed } from "./spot.js";
+import type { Storage } from "./storage.js";
+import type { Asset, MarketSnapshot } from "./types.js";
+
+const ASSETS: Asset[] = ["BTC", "ETH", "SOL", "XRP"];
+/** Spacing between recorded ticks treated as missing coverage (e.g. the recorder was down) */
+const UNMARKED_GAP_MS = 10_000;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** One recorded tick: the snapshot as seen live plus spot/period-open when a feed was running */
+export interface RecordedTick {
+  ts_ms: number;
+  snapshot: MarketSnapshot;
+  spot?: Partial<Record<Asset, { price: number; open: number | null }>>;
+}
+
+/** Append the tick to `snapshots/<period>.jsonl` */
+export function recordTick(storage: Storage, snapshot: MarketSnapshot, tsMs: number, spotFeed: SpotFeed | null): void {
+  const tick: RecordedTick = { ts_ms: tsMs, snapshot };
+  if (spotFeed) {
+    tick.spot = {};
+    for (const a of ASSETS) {
+      const price = spotFeed.price(a);
+      if (price != null) tick.spot[a] = { price, open: spotFeed.periodOpen(a, snapshot.period_timestamp) };
+    }
+  }
+  storage.appendJsonl(`snapshots/${snapshot.period_timestamp}.jsonl`, tick);
+}
+
+/** Spot feed that serves the prices stored alongside recorded snapshots */
+export class ReplaySpotFeed implements SpotFeed {
+  readonly name = "replay";
+  private prices: Map<Asset, number> = new Map();
+  private opens: Map<string, number> = new Map();
+
+  start(): void {}
+
+  stop(): void {}
+
+  price(asset: Asset): number | null {
+    return this.prices.get(asset) ?? null;
+  }
+
+  periodOpen(asset: Asset, periodTimestamp: number): number | null {
+    return this.opens.get(`${asset}_${periodTimestamp}`) ?? null;
+  }
+
+  async loadPeriodOpen(asset: Asset, periodTimestamp: number): Promise<number | null> {
+    return this.periodOpen(asset, periodTimestamp);
+  }
+
+  apply(tick: RecordedTick): void {
+    for (const a of ASSETS) {
+      const s = tick.spot?.[a];
+      if (!s) continue;
+      this.prices.set(a, s.price);
+      if (s.open != null) this.opens.set(`${a}_${tick.snapshot.period_timestamp}`, s.open);
+    }
+  }
+}
+
+/** Feeds recorded ticks in time order, advancing the virtual clock to each tick */
+export class ReplaySource {
+  private files: string[];
+  private fileIdx = 0;
+  private ticks: RecordedTick[] = [];
+  private tickIdx = 0;
+  private clock: VirtualClock;
+  private spotFeed: ReplaySpotFeed;
+  private history: PriceHistory | null;
+  private lastTsMs: number | null = null;
+  private gapMs = 0;
+  private gapCount = 0;
+
+  constructor(path: string, clock: VirtualClock, spotFeed: ReplaySpotFeed, history: PriceHistory | null = null) {
+    if (!existsSync(path)) throw new Error(`Replay path not found: ${path}`);
+    this.files = statSync(path).isDirectory()
+      ? readdirSync(path)
+          .filter((f) => f.endsWith(".jsonl"))
+          .sort()
+          .map((f) => join(path, f))
+      : [path];
+    this.clock = clock;
+    this.spotFeed = spotFeed;
+    this.history = history;
+  }
+
+  async next(): Promise<MarketSnapshot | null> {
+    while (this.tickIdx >= this.ticks.length) {
+      if (this.fileIdx >= this.files.length) return null;
+      this.ticks = readFileSync(this.files[this.fileIdx++], "utf-8")
+        .split("\n")
+        .filter((line) => line.trim().length > 0)
+        .map((line) => JSON.parse(line) as RecordedTick);
+      this.tickIdx = 0;
+    }
+    const tick = this.ticks[this.tickIdx++];
+    if (!tick.snapshot.gap && this.lastTsMs != null && tick.ts_ms - this.lastTsMs > UNMARKED_GAP_MS) {
+      tick.snapshot.gap = { from_ms: this.lastTsMs, to_ms: tick.ts_ms };
+    }
+    if (tick.snapshot.gap) {
+      const { from_ms, to_ms } = tick.snapshot.gap;
+      this.gapMs += to_ms - from_ms;
+      this.gapCount++;
+      log(`🕳️ Coverage gap ${formatTime(from_ms)} - ${formatTime(to_ms, true)} (${((to_ms - from_ms) / 1000).toFixed(1)}s)`);
+    }
+    this.lastTsMs = tick.ts_ms;
+    this.clock.set(tick.ts_ms);
+    this.spotFeed.apply(tick);
+    if (this.history) {
+      this.history.record(tick.snapshot, tick.ts_ms);
+      tick.snapshot.history = this.history;
+    }
+    return tick.snapshot;
+  }
+
+  /** Intervals without recorded data seen so far */
+  coverageGaps(): { count: number; seconds: number } {
+    return { count: this.gapCount, seconds: this.gapMs / 1000 };
+  }
+}
@@ -0,0 +1,273 @@
+import { bookHeatmapReport } from "./books.js";
+import { fillCalibrationReport } from "./fill-model.js";
+import { pairsReport } from "./pair-trade.js";
+import { journalReport } from "./journal.js";
+import { slippageReport } from "./slippage.js";
+import { disputesReport } from "./disputes.js";
+import { equityReport } from "./capital.js";
+import { adverseSelectionReport } from "./adverse.js";
+import { divergenceReport } from "./divergence.js";
+import { formatUsd } from "./money.js";
+import type { Storage } from "./storage.js";
+import type { Asset, TokenType } from "./types.js";
+import { assetForTokenType } from "./types.js";
+
+/** Closed position as written to `positions.jsonl` by Trader */
+export interface ClosedPositionRecord {
+  ts: number;
+  /** Absent in files written before positions had ids */
+  position_id?: string;
+  period_timestamp: number;
+  token_type: string;
+  token_id: string;
+  units: number;
+  /** Average entry price per share */
+  purchase_price: number;
+  /** Total cost in USDC: `units * purchase_price` */
+  investment_amount: number;
+  /** USDC received over all exit legs (a $1 resolution pays the units; an adverse-selection leg pays nothing); absent in older files */
+  proceeds?: number;
+  fill_elapsed_seconds: number | null;
+  realized_pnl: number;
+  legs: Array<{ reason: string; units: number; price: number; pnl: number }>;
+  /** Entry reason code; absent in files written before reason codes */
+  reason?: string;
+  note?: string | null;
+  /** Set when the market's UMA resolution was disputed while the position was held */
+  dispute?: { since_ms: number; resolved_ms: number | null } | null;
+}
+
+/** Per-asset order count, fill rate and realized PnL split by side */
+export interface AssetAttribution {
+  orders: number;
+  filled: number;
+  fill_rate: number | null;
+  realized_pnl_up: number;
+  realized_pnl_down: number;
+}
+
+/** Attribute orders by asset and side; `realized_pnl` is the PnL realized so far on that order's position */
+export function attributeByAsset(
+  orders: Array<{ token_type: TokenType; filled: boolean; realized_pnl: number }>
+): Partial<Record<Asset, AssetAttribution>> {
+  const out: Partial<Record<Asset, AssetAttribution>> = {};
+  for (const o of orders) {
+    const asset = assetForTokenType(o.token_type);
+    const a = (out[asset] ??= { orders: 0, filled: 0, fill_rate: null, realized_pnl_up: 0, realized_pnl_down: 0 });
+    a.orders++;
+    if (o.filled) a.filled++;
+    if (o.token_type.endsWith("Up")) a.realized_pnl_up += o.realized_pnl;
+    else a.realized_pnl_down += o.realized_pnl;
+  }
+  for (const a of Object.values(out)) a.fill_rate = a.orders > 0 ? a.filled / a.orders : null;
+  return out;
+}
+
+/** Realized PnL by asset and side, with fill rate, from the placement, fill and position logs */
+export function attributionReport(storage: Storage): string {
+  const key = (r: { period_timestamp: number; token_type: string }) => `${r.period_timestamp}_${r.token_type}`;
+  const filled = new Set(storage.readJsonl<{ period_timestamp: number; token_type: string }>("fills.jsonl").map(key));
+  const pnl = new Map<string, number>();
+  for (const p of storage.readJsonl<ClosedPositionRecord>("positions.jsonl")) pnl.set(key(p), (pnl.get(key(p)) ?? 0) + p.realized_pnl);
+  const placements = storage.readJsonl<{ period_timestamp: number; token_type: TokenType }>("placements.jsonl");
+  const byAsset = attributeByAsset(
+    placements.map((p) => ({ token_type: p.token_type, filled: filled.has(key(p)), realized_pnl: pnl.get(key(p)) ?? 0 }))
+  );
+  const lines = ["PnL attribution", "   asset   orders  fill%      up pnl    down pnl       total"];
+  for (const [asset, a] of Object.entries(byAsset)) {
+    lines.push(
+      `   ${asset.padEnd(5)} ${String(a.orders).padStart(8)} ${(a.fill_rate != null ? (a.fill_rate * 100).toFixed(1) : "-").padStart(6)}% ` +
+        `${formatUsd(a.realized_pnl_up).padStart(10)}  ${formatUsd(a.realized_pnl_down).padStart(10)}  ` +
+        `${formatUsd(a.realized_pnl_up + a.realized_pnl_down).padStart(10)}`
+    );
+  }
+  if (lines.length === 2) lines.push("   (no placements recorded yet)");
+  return lines.join("\n");
+}
+
+/** Maker vs taker split of live fills by asset, from `fills.jsonl` */
+export function liquidityReport(storage: Storage): string {
+  const fills = storage.readJsonl<{ token_type: TokenType; units: number; price: number; liquidity?: "maker" | "taker" | null }>("fills.jsonl");
+  type Side = { fills: number; notional: number };
+  const byAsset = new Map<Asset, Record<"maker" | "taker", Side>>();
+  for (const f of fills) {
+    if (!f.liquidity) continue;
+    const asset = assetForTokenType(f.token_type);
+    const row = byAsset.get(asset) ?? { maker: { fills: 0, notional: 0 }, taker: { fills: 0, notional: 0 } };
+    row[f.liquidity].fills++;
+    row[f.liquidity].notional += f.units * f.price;
+    byAsset.set(asset, row);
+  }
+  const lines = ["Maker/taker split (live fills)", "   asset    maker   taker  maker%   maker notional   taker notional"];
+  for (const [asset, r] of byAsset) {
+    lines.push(
+      `   ${asset.padEnd(5)} ${String(r.maker.fills).padStart(8)} ${String(r.taker.fills).padStart(7)} ` +
+        `${((r.maker.fills / (r.maker.fills + r.taker.fills)) * 100).toFixed(1).padStart(6)}% ` +
+        `${formatUsd(r.maker.notional).padStart(16)} ${formatUsd(r.taker.notional).padStart(16)}`
+    );
+  }
+  if (lines.length === 2) lines.push("   (no classified live fills yet)");
+  return lines.join("\n");
+}
+
+const ENTRY_BUCKETS: Array<[number, number, string]> = [
+  [0, 3, "0-2s"],
+  [3, 11, "3-10s"],
+  [11, 61, "11-60s"],
+  [61, 301, "1-5m"],
+  [301, 601, "5-10m"],
+  [601, Infinity, "10m+"],
+];
+
+/** Win rate and PnL of closed positions by seconds-into-period at fill */
+export function entryTimingReport(storage: Storage): string {
+  const closed = storage.readJsonl<ClosedPositionRecord>("positions.jsonl").filter((p) => p.fill_elapsed_seconds != null);
+  const lines = [
+    "Entry timing (closed positions)",
+    "   bucket        n    win%        pnl    avg pnl",
+  ];
+  for (const [lo, hi, label] of ENTRY_BUCKETS) {
+    const rows = closed.filter((p) => (p.fill_elapsed_seconds as number) >= lo && (p.fill_elapsed_seconds as number) < hi);
+    if (rows.length === 0) continue;
+    const wins = rows.filter((p) => p.realized_pnl > 0).length;
+    const pnl = rows.reduce((sum, p) => sum + p.realized_pnl, 0);
+    lines.push(
+      `   ${label.padEnd(8)} ${String(rows.length).padStart(6)} ${((wins / rows.length) * 100).toFixed(1).padStart(6)}% ` +
+        `${formatUsd(pnl).padStart(10)} ${formatUsd(pnl / rows.length).padStart(10)}`
+    );
+  }
+  if (lines.length === 2) lines.push("   (no closed positions with fill timing yet)");
+  return lines.join("\n");
+}
+
+const REJECTION_HINTS: Record<string, string> = {
+  price_out_of_range: "check the limit price against the market's tick size and the 0.01-0.99 range",
+  min_size: "raise the order size (shares or fixed_trade_amount) above the market minimum",
+  insufficient_balance: "fund the wallet or lower order sizes; check the USDC allowance",
+  market_closed: "orders reached a market after it closed; check discovery and flat_periods",
+  duplicate: "the same order was sent twice; check order_concurrency and re-entry settings",
+  rate_limited: "too many requests; raise check_interval_ms or lower order_concurrency",
+  unauthorized: "API credentials or signature rejected; check private_key, api_key/secret/passphrase and signature_type",
+};
+
+/** Order rejections by type (and asset) from `rejections.jsonl`, with a hint for each recurring cause */
+export function rejectionsReport(storage: Storage): string {
+  const rows = storage.readJsonl<{ token_type: TokenType; type: string; message: string }>("rejections.jsonl");
+  const lines = ["Order rejections by type", "   type                      n  by asset"];
+  const byType = new Map<string, { n: number; assets: Map<Asset, number>; last: string }>();
+  for (const r of rows) {
+    const row = byType.get(r.type) ?? { n: 0, assets: new Map<Asset, number>(), last: "" };
+    row.n++;
+    const asset = assetForTokenType(r.token_type);
+    row.assets.set(asset, (row.assets.get(asset) ?? 0) + 1);
+    row.last = r.message;
+    byType.set(r.type, row);
+  }
+  for (const [type, r] of [...byType].sort((a, b) => b[1].n - a[1].n)) {
+    const assets = [...r.assets].map(([asset, n]) => `${asset} ${n}`).join(", ");
+    lines.push(`   ${type.padEnd(22)} ${String(r.n).padStart(6)}  ${assets}`);
+    lines.push(`      last: ${r.last.slice(0, 120)}`);
+    const hint = REJECTION_HINTS[type];
+    if (hint) lines.push(`      hint: ${hint}`);
+  }
+  if (rows.length === 0) lines.push("   (no rejections recorded)");
+  return lines.join("\n");
+}
+
+/** Holding to resolution versus selling into the last bid seen, per asset, from `resolutions.jsonl` */
+export function resolutionEdgeReport(storage: Storage): string {
+  const rows = storage.readJsonl<{ token_type: TokenType; units: number; last_bid: number; payout: number; resolution_edge: number }>(
+    "resolutions.jsonl"
+  );
+  const lines = [
+    "Resolution edge (payout minus last bid, positions held to resolution)",
+    "   asset        n   held won    edge/share       total",
+  ];
+  const byAsset = new Map<Asset, typeof rows>();
+  for (const r of rows) {
+    const asset = assetForTokenType(r.token_type);
+    byAsset.set(asset, [...(byAsset.get(asset) ?? []), r]);
+  }
+  const line = (label: string, group: typeof rows) => {
+    const units = group.reduce((sum, r) => sum + r.units, 0);
+    const total = group.reduce((sum, r) => sum + r.resolution_edge, 0);
+    const better = group.filter((r) => r.resolution_edge > 0).length;
+    lines.push(
+      `   ${label.padEnd(6)} ${String(group.length).padStart(6)} ${((better / group.length) * 100).toFixed(1).padStart(9)}% ` +
+        `${(units > 0 ? total / units : 0).toFixed(4).padStart(13)} ${formatUsd(total).padStart(11)}`
+    );
+  };
+  for (const [asset, group] of byAsset) line(asset, group);
+  if (rows.length === 0) {
+    lines.push("   (no resolved positions with a last mark yet)");
+    return lines.join("\n");
+  }
+  line("all", rows);
+  const total = rows.reduce((sum, r) => sum + r.resolution_edge, 0);
+  lines.push(`   Holding to resolution ${total >= 0 ? "beat" : "trailed"} exiting at the last bid by ${formatUsd(Math.abs(total))}`);
+  return lines.join("\n");
+}
+
+/** PnL of closed positions by the decision that opened them, and of exit legs by the decision that closed them */
+export function reasonsReport(storage: Storage): string {
+  const closed = storage.readJsonl<ClosedPositionRecord>("positions.jsonl");
+  const entries = new Map<string, { n: number; wins: number; units: number; cost: number; pnl: number }>();
+  const exits = new Map<string, { n: number; units: number; proceeds: number; pnl: number }>();
+  for (const p of closed) {
+    const reason = p.reason ?? "unspecified";
+    const row = entries.get(reason) ?? { n: 0, wins: 0, units: 0, cost: 0, pnl: 0 };
+    row.n++;
+    if (p.realized_pnl > 0) row.wins++;
+    row.units += p.units;
+    row.cost += p.investment_amount;
+    row.pnl += p.realized_pnl;
+    entries.set(reason, row);
+    for (const leg of p.legs) {
+      const exit = exits.get(leg.reason) ?? { n: 0, units: 0, proceeds: 0, pnl: 0 };
+      exit.n++;
+      exit.units += leg.units;
+      if (leg.reason !== "adverse_selection") exit.proceeds += leg.units * leg.price;
+      exit.pnl += leg.pnl;
+      exits.set(leg.reason, exit);
+    }
+  }
+  const lines = [
+    "PnL by entry reason (closed positions)",
+    "   reason                    n    win%      shares  avg price        cost        pnl    avg pnl",
+  ];
+  for (const [reason, r] of [...entries].sort((a, b) => b[1].pnl - a[1].pnl)) {
+    lines.push(
+      `   ${reason.padEnd(22)} ${String(r.n).padStart(6)} ${((r.wins / r.n) * 100).toFixed(1).padStart(6)}% ` +
+        `${r.units.toFixed(2).padStart(11)} ${(r.units > 0 ? r.cost / r.units : 0).toFixed(4).padStart(10)} ${formatUsd(r.cost).padStart(11)} ` +
+        `${formatUsd(r.pnl).padStart(10)} ${formatUsd(r.pnl / r.n).padStart(10)}`
+    );
+  }
+  if (entries.size === 0) lines.push("   (no closed positions yet)");
+  lines.push("", "PnL by exit reason (sell legs)", "   reason                 legs      shares  avg price    proceeds        pnl");
+  for (const [reason, r] of [...exits].sort((a, b) => b[1].pnl - a[1].pnl)) {
+    lines.push(
+      `   ${reason.padEnd(22)} ${String(r.n).padStart(6)} ${r.units.toFixed(2).padStart(11)} ` +
+        `${(r.units > 0 ? r.proceeds / r.units : 0).toFixed(4).padStart(10)} ${formatUsd(r.proceeds).padStart(11)} ${formatUsd(r.pnl).padStart(10)}`
+    );
+  }
+  if (exits.size === 0) lines.push("   (no exit legs yet)");
+  return lines.join("\n");
+}
+
+export const REPORTS: Record<string, (storage: Storage) => string> = {
+  "entry-timing": entryTimingReport,
+  attribution: attributionReport,
+  liquidity: liquidityReport,
+  reasons: reasonsReport,
+  "resolution-edge": resolutionEdgeReport,
+  rejections: rejectionsReport,
+  "book-heatmap": bookHeatmapReport,
+  "fill-calibration": fillCalibrationReport,
+  pairs: pairsReport,
+  journal: journalReport,
+  slippage: slippageReport,
+  disputes: disputesReport,
+  equity: equityReport,
+  "adverse-selection": adverseSelectionReport,
+  divergence: divergenceReport,
+};
@@ -0,0 +1,136 @@
+import { closeSync, mkdirSync, openSync, readdirSync, rmSync, statSync } from "fs";
+import { formatUsd } from "./money.js";
+import type { Storage } from "./storage.js";
+
+const RISK_DIR = "risk";
+const LOCK_FILE = "risk/budget.lock";
+/** A lock older than this belongs to a crashed process and is broken */
+const STALE_LOCK_MS = 5_000;
+const LOCK_RETRY_MS = 10;
+const LOCK_ATTEMPTS = 200;
+
+interface ExposureRecord {
+  pid: number;
+  exposure: number;
+  updated_ms: number;
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+function sleepSync(ms: number): void {
+  Atomics.wait(new Int32Array(new SharedArrayBuffer(4)), 0, 0, ms);
+}
+
+function isAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (e) {
+    return (e as NodeJS.ErrnoException).code === "EPERM";
+  }
+}
+
+/**
+ * Account-wide exposure cap shared by every bot process using the same `data_dir`. Each process
+ * publishes its open cost to `risk/<pid>.json`; reservations are checked against the sum of all live
+ * processes under a lock file, so two processes cannot both take the last of the budget.
+ */
+export class RiskBudget {
+  private storage: Storage;
+  private maxExposure: number;
+  private own = 0;
+
+  constructor(storage: Storage, maxExposure: number) {
+    this.storage = storage;
+    this.maxExposure = maxExposure;
+  }
+
+  /** Reserve `amount` of budget for a new order; false (nothing reserved) if the account cap would be exceeded */
+  tryReserve(amount: number): boolean {
+    return this.withLock(() => {
+      const others = this.othersExposure();
+      if (others + this.own + amount > this.maxExposure + 1e-9) {
+        log(
+          `🛑 Shared risk budget: ${formatUsd(others + this.own)} of ${formatUsd(this.maxExposure)} in use ` +
+            `(this process ${formatUsd(this.own)}), cannot add ${formatUsd(amount)}`
+        );
+        return false;
+      }
+      this.publish(this.own + amount);
+      return true;
+    });
+  }
+
+  /** Give back a reservation whose order was never placed */
+  release(amount: number): void {
+    this.withLock(() => this.publish(Math.max(0, this.own - amount)));
+  }
+
+  /** Replace this process's exposure with its actual open cost (positions plus resting orders) */
+  sync(exposure: number): void {
+    if (Math.abs(exposure - this.own) < 1e-9) return;
+    this.withLock(() => this.publish(exposure));
+  }
+
+  /** Remove this process's record on shutdown */
+  close(): void {
+    rmSync(this.storage.path(RISK_DIR, `${process.pid}.json`), { force: true });
+  }
+
+  private publish(exposure: number): void {
+    this.own = exposure;
+    const record: ExposureRecord = { pid: process.pid, exposure, updated_ms: Date.now() };
+    this.storage.writeJson(`${RISK_DIR}/${process.pid}.json`, record);
+  }
+
+  /** Sum over other live processes; records of dead processes are removed */
+  private othersExposure(): number {
+    let files: string[] = [];
+    try {
+      files = readdirSync(this.storage.path(RISK_DIR)).filter((f) => /^\d+\.json$/.test(f));
+    } catch {
+      return 0;
+    }
+    let total = 0;
+    for (const f of files) {
+      const pid = parseInt(f, 10);
+      if (pid === process.pid) continue;
+      if (!isAlive(pid)) {
+        rmSync(this.storage.path(RISK_DIR, f), { force: true });
+        continue;
+      }
+      try {
+        total += this.storage.readJson<ExposureRecord>(`${RISK_DIR}/${f}`)?.exposure ?? 0;
+      } catch {
+        /* mid-write; next check sees it */
+      }
+    }
+    return total;
+  }
+
+  private withLock<T>(fn: () => T): T {
+    const lock = this.storage.path(LOCK_FILE);
+    mkdirSync(this.storage.path(RISK_DIR), { recursive: true });
+    for (let attempt = 0; attempt < LOCK_ATTEMPTS; attempt++) {
+      try {
+        closeSync(openSync(lock, "wx"));
+      } catch {
+        try {
+          if (Date.now() - statSync(lock).mtimeMs > STALE_LOCK_MS) rmSync(lock, { force: true });
+        } catch {
+          /* released meanwhile */
+        }
+        sleepSync(LOCK_RETRY_MS);
+        continue;
+      }
+      try {
+        return fn();
+      } finally {
+        rmSync(lock, { force: true });
+      }
+    }
+    throw new Error(`Could not acquire ${lock}`);
+  }
+}
@@ -0,0 +1,16 @@
+/** A fresh 32-bit seed, for runs that did not configure one (record it to reproduce the run) */
+export function randomSeed(): number {
+  return Math.floor(Math.random() * 0x100000000);
+}
+
+/** Seeded uniform [0, 1) generator (mulberry32): the same seed gives the same draws on every run */
+export function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 0x100000000;
+  };
+}
@@ -0,0 +1,725 @@
+import { renameSync } from "fs";
+import type { AlertsConfig, Config } from "./config.js";
+import { PolymarketApi } from "./api.js";
+import { createClobClient } from "./clob.js";
+import { Trader } from "./trader.js";
+import type { Sizer } from "./trader.js";
+import { createSpotFeed } from "./spot.js";
+import type { SpotFeed } from "./spot.js";
+import { Storage } from "./storage.js";
+import { PENDING_IMPORT_FILE, STATE_FILE } from "./state.js";
+import type { SavedState } from "./state.js";
+import { SessionRecorder } from "./session.js";
+import { RiskBudget } from "./risk.js";
+import { HaltGuard } from "./halt.js";
+import { FailoverGuard } from "./failover.js";
+import { createPubSubSinks } from "./pubsub.js";
+import { BotControl } from "./control.js";
+import { ControlSocket } from "./control-socket.js";
+import { Heartbeat } from "./health.js";
+import { TradeEventBus } from "./events.js";
+import type { EventSink } from "./events.js";
+import { Blotter } from "./blotter.js";
+import { BookRecorder } from "./books.js";
+import { createDirectionSignal } from "./direction.js";
+import type { DirectionSignal } from "./direction.js";
+import { PredictionJournal } from "./journal.js";
+import { CapitalFlowTracker } from "./capital.js";
+import { calibrateAdverseSelection } from "./adverse.js";
+import { DivergenceMonitor } from "./divergence.js";
+import { SimFillRule } from "./sim-fill.js";
+import { FILL_MODEL_FILE, FillProbabilityModel } from "./fill-model.js";
+import type { FillQuery } from "./fill-model.js";
+import { formatSummary } from "./summary.js";
+import type { SummaryConfig } from "./summary.js";
+import { Notifier, TelegramChannel } from "./notifier.js";
+import type { AlertChannel } from "./notifier.js";
+import { RedemptionScheduler } from "./redeem.js";
+import { createOnchainAccount } from "./onchain.js";
+import { formatTime, formatUsd } from "./money.js";
+import { VirtualClock, clock, nowSeconds, setClock } from "./clock.js";
+import { ReplaySource, ReplaySpotFeed, recordTick } from "./replay.js";
+import { PriceHistory } from "./history.js";
+import { logs } from "./logging.js";
+import { MarketMonitor, PeriodOpenTracker, formatPrices, currentPeriodTimestamp } from "./monitor.js";
+import type { MonitoredMarkets } from "./monitor.js";
+import { getOrDiscoverMarkets, missingMarkets, retryLateMarkets } from "./discovery.js";
+import type { Strategy } from "./strategy.js";
+import type { Asset, MarketData, MarketSnapshot } from "./types.js";
+import { upDownTokenIds } from "./types.js";
+
+const PERIOD_DURATION = 900;
+const LATE_MARKET_RETRY_MS = 1_500;
+/** Look up the next period's markets this long before the current period ends */
+const PREDISCOVER_SECONDS = 60;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+function hasAsk(m: MarketData): boolean {
+  return m.up_token?.ask != null || m.down_token?.ask != null;
+}
+
+/** Spot vs period open per asset, e.g. "BTC $97000.00 (+0.12%)" */
+function formatSpot(feed: SpotFeed, periodTimestamp: number): string {
+  const assets: Asset[] = ["BTC", "ETH", "SOL", "XRP"];
+  return assets
+    .map((a) => {
+      const spot = feed.price(a);
+      const open = feed.periodOpen(a, periodTimestamp);
+      if (spot == null) return `${a} N/A`;
+      const move = open != null ? ` (${spot >= open ? "+" : ""}${(((spot - open) / open) * 100).toFixed(2)}%)` : "";
+      return `${a} $${spot.toFixed(a === "XRP" ? 4 : 2)}${move}`;
+    })
+    .join(" | ");
+}
+
+/** Exchange spot vs oracle print per asset, e.g. "BTC oracle $96990.00 (-0.01%)" */
+function formatOracleBasis(spot: SpotFeed, oracle: SpotFeed): string {
+  const assets: Asset[] = ["BTC", "ETH", "SOL", "XRP"];
+  return assets
+    .map((a) => {
+      const s = spot.price(a);
+      const o = oracle.price(a);
+      if (o == null) return `${a} oracle N/A`;
+      const basis = s != null ? ` (${o >= s ? "+" : ""}${(((o - s) / s) * 100).toFixed(2)}%)` : "";
+      return `${a} oracle $${o.toFixed(a === "XRP" ? 4 : 2)}${basis}`;
+    })
+    .join(" | ");
+}
+
+/** Authenticate (when a key is configured), discover markets and build the live monitor */
+async function connectLive(api: PolymarketApi, config: Config, storage: Storage, simulation: boolean, observe: boolean): Promise<MarketMonitor> {
+  log("\n═══════════════════════════════════════════════════════════");
+  log("🔐 Authenticating with Polymarket CLOB API...");
+  log("═══════════════════════════════════════════════════════════");
+  if (observe) {
+    log("🔭 Observer mode - skipping authentication, market data only");
+  } else if (config.polymarket.private_key) {
+    try {
+      const client = await createClobClient(config.polymarket);
+      await client.getOk();
+      log("✅ Successfully authenticated with Polymarket CLOB API");
+      log("   ✓ Private key: Valid");
+      log("   ✓ API credentials: Valid");
+      log("   ✓ Trading account: EOA (private key account)");
+    } catch (e) {
+      log("❌ Authentication failed: " + String(e));
+      if (!simulation) throw e;
+      log("   (Continuing in simulation mode with read-only market data.)");
+    }
+  } else {
+    log("⚠️ No private_key in config - only simulation/read-only will work.");
+  }
+  log("✅ Authentication successful!");
+  log("═══════════════════════════════════════════════════════════");
+
+  log("🔍 Discovering BTC, ETH, Solana, XRP markets...");
+  const { eth, btc, solana, xrp } = await getOrDiscoverMarkets(api, config.trading);
+
+  if (btc.tokens?.length) {
+    const { upId, downId } = upDownTokenIds(btc);
+    if (upId) log(`BTC Up token_id: ${upId}`);
+    if (downId) log(`BTC Down token_id: ${downId}`);
+  }
+
+  return new MarketMonitor(
+    api,
+    { eth, btc, solana, xrp },
+    {
+      tradeStatsWindowSeconds: config.trading.trade_stats_window_seconds,
+      bookDepthLevels: config.trading.book_depth_levels,
+      phaseIntervals: config.trading.phase_intervals,
+      historyMinutes: config.trading.price_history_minutes ?? 5,
+      maxTickJumpPct: config.trading.max_tick_jump_pct === undefined ? 50 : config.trading.max_tick_jump_pct,
+      bookRecorder: config.trading.book_heatmap ? new BookRecorder(storage, config.trading.book_heatmap) : null,
+    }
+  );
+}
+
+/** What {@link BotRuntimeBuilder} collects */
+export interface RuntimeOptions {
+  strategy: string;
+  simulation: boolean;
+  observe: boolean;
+  replay: string | null;
+  alertChannels: AlertChannel[];
+  eventSinks: EventSink[];
+  sizer: Sizer | null;
+  directionSignal: DirectionSignal | null;
+}
+
+/** Builder for {@link BotRuntime}: pick the mode and strategy name, register plugins, then `build()` */
+export class BotRuntimeBuilder {
+  private config: Config;
+  private opts: RuntimeOptions = {
+    strategy: "custom",
+    simulation: true,
+    observe: false,
+    replay: null,
+    alertChannels: [],
+    eventSinks: [],
+    sizer: null,
+    directionSignal: null,
+  };
+
+  constructor(config: Config) {
+    this.config = config;
+  }
+
+  /** Recorded in the session log */
+  strategy(name: string): this {
+    this.opts.strategy = name;
+    return this;
+  }
+
+  simulation(on: boolean): this {
+    this.opts.simulation = on;
+    return this;
+  }
+
+  /** Never place orders (implies simulation) */
+  observe(on: boolean): this {
+    this.opts.observe = on;
+    return this;
+  }
+
+  /** Replay recorded snapshots on a virtual clock instead of monitoring live (implies simulation) */
+  replay(path: string | null): this {
+    this.opts.replay = path;
+    return this;
+  }
+
+  /** Also deliver alerts here (alongside the log and `alerts.webhook_url`); same throttling applies */
+  alertChannel(channel: AlertChannel): this {
+    this.opts.alertChannels.push(channel);
+    return this;
+  }
+
+  /** Receive every trade event (placed, rejected, filled, cancelled, exit, closed) */
+  eventSink(sink: EventSink): this {
+    this.opts.eventSinks.push(sink);
+    return this;
+  }
+
+  /** Size orders instead of `fixed_trade_amount` / `dual_limit_shares` */
+  sizer(sizer: Sizer): this {
+    this.opts.sizer = sizer;
+    return this;
+  }
+
+  /** Direction signal for `one_sided` mode, replacing the configured one */
+  directionSignal(signal: DirectionSignal): this {
+    this.opts.directionSignal = signal;
+    return this;
+  }
+
+  build(): Promise<BotRuntime> {
+    return BotRuntime.create(this.config, this.opts);
+  }
+}
+
+/**
+ * Everything a strategy runs on: API, monitor (or replay source), trader with its safety rails, feeds,
+ * storage, alerts, session log and liveness endpoint. `next()` hands out snapshots with the shared
+ * per-tick work done (market rollover, recording, position management), so a strategy only decides entries.
+ */
+export class BotRuntime {
+  readonly config: Config;
+  readonly storage: Storage;
+  readonly api: PolymarketApi;
+  readonly trader: Trader;
+  readonly monitor: MarketMonitor | null;
+  readonly replaySource: ReplaySource | null;
+  readonly spotFeed: SpotFeed | null;
+  readonly oracleFeed: SpotFeed | null;
+  /** Side picker for `one_sided` mode; null when the mode is off */
+  directionSignal: DirectionSignal | null = null;
+  /** Thesis/outcome journal (`journal`); null when off */
+  readonly journal: PredictionJournal | null;
+  readonly notifier: Notifier;
+  readonly tradeEvents: TradeEventBus;
+  readonly simulation: boolean;
+  readonly observe: boolean;
+  private alerts: AlertsConfig;
+  private redemptions: RedemptionScheduler | null = null;
+  private blotter: Blotter;
+  private halt: HaltGuard | null;
+  private failover: FailoverGuard | null = null;
+  private riskBudget: RiskBudget | null = null;
+  private control: BotControl;
+  private session: SessionRecorder;
+  private heartbeat: Heartbeat;
+  private periodOpens: PeriodOpenTracker;
+  private recordSnapshots: boolean;
+  private logTicks: boolean;
+  private lastSummaryMs: number | null = null;
+  private snapshotWatchdog: ReturnType<typeof setInterval> | null;
+  private lastPricedSnapshotMs = Date.now();
+  private positionSnapshotMs: number;
+  private lastPositionSnapshotMs: number | null = null;
+  private capitalFlows: CapitalFlowTracker | null = null;
+  private divergence: DivergenceMonitor | null = null;
+  private latest: MarketSnapshot | null = null;
+  private controlSocket: ControlSocket | null = null;
+  private realizedAtPeriodStart = 0;
+  private droppedAtPeriodStart = 0;
+  private reportedPeriod: number | null = null;
+  private lateMarkets: Array<keyof MonitoredMarkets> = [];
+  private knownMarketsPeriod: number | null = null;
+  private prediscoveredPeriod: number | null = null;
+  private lastLateMarketRetryMs = -Infinity;
+  private lateMarketRetrying = false;
+  /** Condition ids of late markets swapped in, until the first snapshot that includes them */
+  private lateMarketsFound: string[] = [];
+  private lateMarketAdded = false;
+
+  static builder(config: Config): BotRuntimeBuilder {
+    return new BotRuntimeBuilder(config);
+  }
+
+  static async create(config: Config, opts: RuntimeOptions): Promise<BotRuntime> {
+    const { observe, replay } = opts;
+    const storage = new Storage(config.trading.data_dir ?? "data");
+    let replaySource: ReplaySource | null = null;
+    let replaySpot: ReplaySpotFeed | null = null;
+    if (replay) {
+      const virtualClock = new VirtualClock(0);
+      setClock(virtualClock);
+      replaySpot = new ReplaySpotFeed();
+      const historyMinutes = config.trading.price_history_minutes ?? 5;
+      replaySource = new ReplaySource(replay, virtualClock, replaySpot, historyMinutes > 0 ? new PriceHistory(historyMinutes * 60_000) : null);
+    }
+    const simulation = opts.simulation || observe || replaySource != null;
+    log("Mode: " + (replaySource ? `REPLAY (${replay})` : observe ? "OBSERVE (no orders)" : simulation ? "SIMULATION" : "PRODUCTION"));
+    const api = new PolymarketApi(config.polymarket);
+    const monitor = replaySource ? null : await connectLive(api, config, storage, simulation, observe);
+    const runtime = new BotRuntime(config, storage, api, monitor, replaySource, replaySpot, simulation, observe);
+    runtime.register(opts);
+    await runtime.startSession(opts.strategy);
+    return runtime;
+  }
+
+  private constructor(
+    config: Config,
+    storage: Storage,
+    api: PolymarketApi,
+    monitor: MarketMonitor | null,
+    replaySource: ReplaySource | null,
+    replaySpot: ReplaySpotFeed | null,
+    simulation: boolean,
+    observe: boolean
+  ) {
+    this.config = config;
+    this.storage = storage;
+    this.api = api;
+    this.monitor = monitor;
+    this.replaySource = replaySource;
+    this.simulation = simulation;
+    this.observe = observe;
+
+    const trader = new Trader(api, config.trading, simulation);
+    this.trader = trader;
+    const spotOpts = {
+      pollIntervalMs: config.trading.spot_poll_interval_ms ?? 500,
+      polygonRpcUrl: config.trading.polygon_rpc_url,
+      chainlinkFeeds: config.trading.chainlink_feeds,
+    };
+    this.spotFeed = replaySpot ?? createSpotFeed(config.trading.spot_feed, spotOpts);
+    this.oracleFeed = replaySource ? null : createSpotFeed(config.trading.oracle_feed, spotOpts);
+    if (this.spotFeed) {
+      this.spotFeed.start();
+      trader.setSpotFeed(this.spotFeed);
+      log(`📡 Spot feed: ${this.spotFeed.name}`);
+    }
+    if (this.oracleFeed) {
+      this.oracleFeed.start();
+      log(`🔗 Oracle feed: ${this.oracleFeed.name}`);
+    }
+    trader.setStorage(storage);
+    const adverse = config.trading.sim_adverse_selection;
+    if (simulation && adverse != null) {
+      const haircut = adverse === "auto" ? calibrateAdverseSelection(storage).haircut : adverse;
+      if (haircut == null) log(`⚠️ sim_adverse_selection "auto": not enough live and simulated history to calibrate - no haircut`);
+      else {
+        trader.setAdverseSelectionHaircut(haircut);
+        log(`🎯 Adverse selection: ${(haircut * 100).toFixed(1)}% of each winning simulated fill treated as unfilled`);
+      }
+    }
+    const fillModel = FillProbabilityModel.load(storage);
+    if (fillModel) {
+      trader.setFillModel(fillModel);
+      log(`🎲 Fill-probability model loaded from ${storage.path(FILL_MODEL_FILE)}`);
+    }
+    const imported = replaySource ? null : storage.readJson<SavedState>(PENDING_IMPORT_FILE);
+    if (imported) {
+      log(`📥 Restoring state imported from another host (saved ${formatTime(imported.saved_at)})`);
+      trader.restore(imported.trader);
+      renameSync(storage.path(PENDING_IMPORT_FILE), storage.path("state-import.applied.json"));
+    }
+    if (!replaySource) {
+      const markers = trader.loadPlacementMarkers(currentPeriodTimestamp());
+      if (markers > 0) log(`🔒 ${markers} order(s) already placed this period by an earlier run - not placing them again`);
+    }
+    this.alerts = config.trading.alerts ?? {};
+    const notifier = new Notifier(
+      this.alerts.webhook_url ?? null,
+      (this.alerts.min_interval_seconds ?? 300) * 1000,
+      this.alerts.templates ?? null,
+      this.alerts.routing ?? null
+    );
+    if (this.alerts.telegram) notifier.addChannel(new TelegramChannel(this.alerts.telegram.bot_token, this.alerts.telegram.chat_id));
+    this.notifier = notifier;
+    trader.setNotifier(notifier);
+    api.onFailover((name, from, to) => notifier.alert(`failover_${name}`, "endpoint_failover", { endpoint: name, from, to }));
+    const redemptionCfg = config.trading.redemption;
+    if (redemptionCfg?.enabled && !simulation && config.polymarket.private_key) {
+      const account = createOnchainAccount(config.polymarket, config.trading.polygon_rpc_url ?? "https://polygon-rpc.com");
+      this.redemptions = new RedemptionScheduler(redemptionCfg, account, notifier);
+      trader.setRedemptions(this.redemptions);
+      log(
+        `⛽ Auto-redemption on for ${account.address}: gas <= ${redemptionCfg.max_gas_gwei ?? 100} gwei ` +
+          `or after ${redemptionCfg.max_delay_minutes ?? 60} min`
+      );
+    }
+    this.tradeEvents = new TradeEventBus();
+    trader.setEventBus(this.tradeEvents);
+    this.blotter = new Blotter(this.tradeEvents);
+    this.journal = config.trading.journal ? new PredictionJournal(storage, this.tradeEvents) : null;
+    if (this.journal) log(`📓 Prediction journal: ${storage.path("journal.jsonl")}`);
+    const minFlow = config.trading.capital_flow_min_usd === undefined ? 5 : config.trading.capital_flow_min_usd;
+    if (minFlow != null && !simulation && !replaySource) this.capitalFlows = new CapitalFlowTracker(storage, this.tradeEvents, minFlow);
+    if (config.trading.divergence_monitor && !simulation && !replaySource) {
+      this.divergence = new DivergenceMonitor(
+        api,
+        storage,
+        this.tradeEvents,
+        new SimFillRule(config.trading.sim_fill_epsilon ?? 0.0001, config.trading.sim_fill_at_limit ?? "fill"),
+        config.trading.sim_fill_requires_print ?? false,
+        config.trading.market_closure_check_interval_seconds * 1000
+      );
+      log(`🪞 Divergence monitor: shadowing live buys with simulated fills (${storage.path("divergence.jsonl")})`);
+    }
+    this.halt = simulation ? null : new HaltGuard(api, (config.trading.halt_recheck_seconds ?? 30) * 1000, notifier);
+    if (this.halt) trader.setHaltGuard(this.halt);
+    const failoverCfg = config.trading.failover;
+    if (failoverCfg && !replaySource) {
+      this.failover = new FailoverGuard(
+        failoverCfg.lease_file,
+        failoverCfg.role,
+        (failoverCfg.takeover_after_seconds ?? 30) * 1000,
+        () => trader.reconcileOpenOrders(),
+        notifier
+      );
+      trader.setFailover(this.failover);
+    }
+    const maxExposure = config.trading.max_account_exposure ?? null;
+    if (maxExposure != null && !replaySource) {
+      this.riskBudget = new RiskBudget(storage, maxExposure);
+      trader.setRiskBudget(this.riskBudget);
+      log(`🛡️ Shared risk budget: ${formatUsd(maxExposure)} across all bots using ${storage.path("risk")}`);
+    }
+    this.control = new BotControl(storage);
+    this.control.refresh();
+    trader.setControl(this.control);
+    this.session = new SessionRecorder(storage, Date.now());
+
+    this.periodOpens = new PeriodOpenTracker(this.spotFeed);
+    this.recordSnapshots = !replaySource && (config.trading.record_snapshots === true || observe);
+    if (this.recordSnapshots) log(`💾 Recording snapshots to ${storage.path("snapshots")}`);
+    this.logTicks = (config.trading.log_ticks ?? true) && logs("ticks");
+    this.positionSnapshotMs = (config.trading.position_snapshot_seconds ?? 60) * 1000;
+
+    this.heartbeat = new Heartbeat(
+      config.trading.heartbeat_file ?? null,
+      config.trading.health_port ?? null,
+      config.trading.health_stale_after_ms ?? 60_000
+    );
+    this.heartbeat.addRoute((req, res) => this.blotter.handle(req, res));
+    this.heartbeat.addRoute((req, res) => {
+      if (req.url !== "/state") return false;
+      this.trader
+        .state()
+        .then((state) => res.writeHead(200, { "Content-Type": "application/json" }).end(JSON.stringify(state)))
+        .catch((e) => res.writeHead(500).end(String(e)));
+      return true;
+    });
+    this.heartbeat.start();
+    const socketPath = config.trading.control_socket;
+    if (socketPath && !replaySource) {
+      this.controlSocket = new ControlSocket(socketPath, this, () => this.heartbeat.status());
+      this.controlSocket.start();
+    }
+
+    const snapshotGapMs = (this.alerts.snapshot_gap_seconds ?? 60) * 1000;
+    this.snapshotWatchdog = replaySource
+      ? null
+      : setInterval(() => {
+          const gapMs = Date.now() - this.lastPricedSnapshotMs;
+          if (gapMs > snapshotGapMs) notifier.alert("snapshot_gap", "snapshot_gap", { seconds: Math.round(gapMs / 1000) });
+        }, 5_000);
+    this.snapshotWatchdog?.unref();
+  }
+
+  private register(opts: RuntimeOptions): void {
+    for (const channel of opts.alertChannels) {
+      this.notifier.addChannel(channel);
+      log(`🔔 Alert channel: ${channel.name}`);
+    }
+    for (const sink of [...opts.eventSinks, ...createPubSubSinks(this.config.trading.pubsub)]) {
+      this.tradeEvents.subscribe((event) => {
+        Promise.resolve()
+          .then(() => sink.write(event))
+          .catch((e) => log(`⚠️ Event sink ${sink.name} failed: ${String(e)}`));
+      });
+      log(`🧩 Event sink: ${sink.name}`);
+    }
+    if (opts.sizer) this.trader.setSizer(opts.sizer);
+    const oneSided = this.config.trading.one_sided;
+    if (oneSided) {
+      this.directionSignal = opts.directionSignal ?? createDirectionSignal(oneSided, this.spotFeed);
+      log(`🧭 One-sided mode: period-start buys follow the ${this.directionSignal?.name} signal`);
+    }
+  }
+
+  private async startSession(strategy: string): Promise<void> {
+    this.session.start({
+      mode: this.replaySource ? "replay" : this.observe ? "observe" : this.simulation ? "simulation" : "production",
+      strategy,
+      config: this.config,
+      seed: this.trader.seed,
+      starting_balance: await this.trader.collateralBalance(),
+    });
+    log(`🧾 Session ${this.session.id} recorded in ${this.storage.path("sessions.jsonl")}`);
+    for (const signal of ["SIGINT", "SIGTERM"] as const) {
+      process.once(signal, () => {
+        log(`\n🛑 ${signal} received - writing session footer`);
+        void this.endSession(signal).finally(() => process.exit(0));
+      });
+    }
+  }
+
+  private async endSession(reason: string): Promise<void> {
+    this.riskBudget?.close();
+    // Picked up by `--state export` to move the deployment to another host.
+    if (!this.replaySource) this.storage.writeJson(STATE_FILE, { saved_at: Date.now(), trader: this.trader.checkpoint() });
+    this.session.end({ reason, ending_balance: await this.trader.collateralBalance(), totals: this.trader.sessionTotals() });
+  }
+
+  /** Start polling (live) */
+  start(checkIntervalMs: number): void {
+    log("Starting market monitoring...");
+    if (this.monitor) {
+      const period = currentPeriodTimestamp();
+      log(`⏰ Current market period: ${period} (${formatTime(period * 1000)}), next period starts in ${period + PERIOD_DURATION - nowSeconds()} seconds`);
+    }
+    this.monitor?.start(checkIntervalMs);
+  }
+
+  /** Run one strategy step; a throw is logged and alerted, and the loop carries on with the next snapshot */
+  async isolate(step: string, fn: () => unknown): Promise<void> {
+    try {
+      await fn();
+    } catch (e) {
+      log(`💥 ${step} failed: ${e instanceof Error ? (e.stack ?? e.message) : String(e)}`);
+      this.notifier.alert(`strategy_error_${step}`, "strategy_error", { step, error: e instanceof Error ? e.message : String(e) });
+    }
+  }
+
+  /** The snapshot `next()` returned last; null before the first one */
+  lastSnapshot(): MarketSnapshot | null {
+    return this.latest;
+  }
+
+  /** Probability that a limit buy fills within its period, from the trained fill model; null without one */
+  estimateFillProbability(q: FillQuery): number | null {
+    return this.trader.estimateFillProbability(q);
+  }
+
+  /** A market listed late was just added; the strategy may place its period-start orders for it */
+  hasLateMarket(): boolean {
+    return this.lateMarketAdded;
+  }
+
+  clearLateMarket(): void {
+    this.lateMarketAdded = false;
+  }
+
+  /**
+   * Next snapshot for the strategy, after the shared per-tick work: heartbeat, market rollover and
+   * pre-discovery, control reload, recording, position management, risk/halt checks and redemptions.
+   * Null once a replay is exhausted.
+   */
+  async next(): Promise<MarketSnapshot | null> {
+    for (;;) {
+      const snapshot = this.replaySource ? await this.replaySource.next() : await (this.monitor as MarketMonitor).next();
+      if (!snapshot) return null;
+      this.heartbeat.beat({
+        period_timestamp: snapshot.period_timestamp,
+        time_remaining_seconds: snapshot.time_remaining_seconds,
+        dropped_snapshots: this.monitor?.droppedSnapshots() ?? 0,
+        discarded_quotes: this.monitor?.dataQuality() ?? null,
+      });
+      const failover = this.failover;
+      if (failover) await this.isolate("failover", () => failover.tick());
+      if (this.monitor && !(await this.trackMarkets(this.monitor, snapshot))) continue;
+      this.periodOpens.enrich(snapshot);
+      await this.isolate("control", () => this.control.refresh());
+      if (hasAsk(snapshot.btc_market) || hasAsk(snapshot.eth_market) || hasAsk(snapshot.solana_market) || hasAsk(snapshot.xrp_market)) {
+        this.lastPricedSnapshotMs = Date.now();
+      }
+      if (this.recordSnapshots) await this.isolate("recordTick", () => recordTick(this.storage, snapshot, clock().now(), this.spotFeed));
+      if (this.logTicks) {
+        log("📊 " + formatPrices(snapshot));
+        if (this.spotFeed) log("💹 " + formatSpot(this.spotFeed, snapshot.period_timestamp));
+        if (this.spotFeed && this.oracleFeed) log("🔗 " + formatOracleBasis(this.spotFeed, this.oracleFeed));
+      }
+      await this.isolate("checkPositions", () => this.trader.checkPositions(snapshot));
+      const divergence = this.divergence;
+      if (divergence) await this.isolate("divergence", () => divergence.onSnapshot(snapshot));
+      const riskBudget = this.riskBudget;
+      if (riskBudget) await this.isolate("riskBudget", () => riskBudget.sync(this.trader.openExposure()));
+      const halt = this.halt;
+      if (halt) await this.isolate("halt", () => halt.check(snapshot.btc_market.condition_id));
+      if (this.positionSnapshotMs > 0 && (this.lastPositionSnapshotMs == null || clock().now() - this.lastPositionSnapshotMs >= this.positionSnapshotMs)) {
+        this.lastPositionSnapshotMs = clock().now();
+        await this.isolate("positionSnapshot", async () => {
+          const balance = this.capitalFlows ? await this.trader.collateralBalance() : null;
+          if (balance != null) this.capitalFlows?.sample(balance);
+          this.trader.recordPositionSnapshot(snapshot, balance != null ? (this.capitalFlows?.mark(balance) ?? null) : null);
+        });
+      }
+      // Runs in the background so waiting for receipts never stalls the loop.
+      if (this.redemptions) void this.isolate("redemptions", () => this.redemptions?.tick());
+      const summary = this.config.trading.summary;
+      if (summary && (this.lastSummaryMs == null || clock().now() - this.lastSummaryMs >= (summary.interval_seconds ?? 30) * 1000)) {
+        this.lastSummaryMs = clock().now();
+        await this.isolate("summary", () => this.publishSummary(summary));
+      }
+      if (snapshot.time_remaining_seconds > 0) this.reportPeriod(snapshot.period_timestamp);
+      this.latest = snapshot;
+      return snapshot;
+    }
+  }
+
+  /**
+   * Keep the monitored markets on the snapshot's period: discover at rollover, pre-discover the next
+   * period, retry markets listed late. False when this snapshot predates a market switch and is skipped.
+   */
+  private async trackMarkets(monitor: MarketMonitor, snapshot: MarketSnapshot): Promise<boolean> {
+    if (snapshot.period_timestamp !== monitor.getMarketsPeriod()) {
+      // Last period's markets resolved: stop polling their tokens and subscribe the new period's.
+      log(`🔄 Period ${snapshot.period_timestamp} (${formatTime(snapshot.period_timestamp * 1000, true)}) started - switching to its markets`);
+      await this.isolate("rollover", async () => {
+        // Only this period's markets: last period's may still be listed as active for a while.
+        const markets = await getOrDiscoverMarkets(this.api, this.config.trading, false);
+        monitor.updateMarkets(markets, snapshot.period_timestamp);
+      });
+      return false;
+    }
+    if (monitor.getMarketsPeriod() !== this.knownMarketsPeriod) {
+      // New markets are in, either from the rollover above or switched to by the monitor at the boundary.
+      this.knownMarketsPeriod = monitor.getMarketsPeriod();
+      this.trader.noteMarketsSubscribed();
+      log(`📡 Subscribed to ${monitor.subscribedTokenIds().length} token(s)`);
+      this.lateMarkets = missingMarkets(monitor.getMarkets(), this.config.trading);
+      this.lateMarketsFound = [];
+    }
+    if (this.lateMarketsFound.length > 0) {
+      const inSnapshot = [snapshot.btc_market, snapshot.eth_market, snapshot.solana_market, snapshot.xrp_market].map((m) => m.condition_id);
+      if (this.lateMarketsFound.every((id) => inSnapshot.includes(id))) {
+        // The first snapshot that includes the late market: the strategy places on this one.
+        this.lateMarketsFound = [];
+        this.lateMarketAdded = true;
+        this.trader.noteMarketsSubscribed();
+      }
+    }
+    const nextPeriod = snapshot.period_timestamp + PERIOD_DURATION;
+    if (snapshot.time_remaining_seconds <= PREDISCOVER_SECONDS && this.prediscoveredPeriod !== nextPeriod) {
+      this.prediscoveredPeriod = nextPeriod;
+      // In the background: the closing window is no time to stall the loop on Gamma.
+      void this.isolate("prediscovery", async () => {
+        const markets = await getOrDiscoverMarkets(this.api, this.config.trading, false, nextPeriod);
+        monitor.stageMarkets(markets, nextPeriod);
+        log(`🔭 Pre-discovered period ${nextPeriod} markets; switching at the boundary`);
+      });
+    }
+    if (this.lateMarkets.length > 0 && !this.lateMarketRetrying && clock().now() - this.lastLateMarketRetryMs >= LATE_MARKET_RETRY_MS) {
+      this.lastLateMarketRetryMs = clock().now();
+      this.lateMarketRetrying = true;
+      const period = snapshot.period_timestamp;
+      // In the background like pre-discovery: Gamma retries must not stall the markets already trading.
+      void this.isolate("lateMarkets", async () => {
+        try {
+          const { found, missing } = await retryLateMarkets(this.api, monitor, this.lateMarkets, period, this.config.trading.discovery_series ?? {});
+          if (monitor.getMarketsPeriod() !== period) return;
+          this.lateMarkets = missing;
+          this.lateMarketsFound.push(...found);
+        } finally {
+          this.lateMarketRetrying = false;
+        }
+      });
+    }
+    return true;
+  }
+
+  /** Send the periodic summary to its configured destinations */
+  private async publishSummary(cfg: SummaryConfig): Promise<void> {
+    const text = formatSummary(await this.trader.state(), cfg);
+    const destinations = cfg.destinations ?? ["log"];
+    if (destinations.includes("log") && logs("summaries")) log(text);
+    if (destinations.includes("notifier")) this.notifier.info("summary", text);
+    if (destinations.includes("dashboard")) this.blotter.summary(text);
+  }
+
+  /** Once per period change: latency, dropped snapshots, discarded quotes and the period PnL alert */
+  private reportPeriod(period: number): void {
+    if (this.reportedPeriod === period) return;
+    const first = this.reportedPeriod === null;
+    this.reportedPeriod = period;
+    if (first) return;
+    const summaries = logs("summaries");
+    const latencyReport = summaries ? this.trader.latencyReport() : null;
+    if (latencyReport) log(latencyReport);
+    const dropped = (this.monitor?.droppedSnapshots() ?? 0) - this.droppedAtPeriodStart;
+    this.droppedAtPeriodStart += dropped;
+    if (dropped > 0 && summaries) log(`📉 ${dropped} snapshot(s) dropped last period - strategy slower than check_interval_ms`);
+    const quality = this.monitor?.dataQuality();
+    if (summaries && quality && quality.crossed + quality.out_of_range + quality.jump > 0) {
+      log(`🧹 Discarded quotes so far: ${quality.crossed} crossed, ${quality.out_of_range} out of range, ${quality.jump} unconfirmed jumps`);
+    }
+    const realized = this.trader.sessionTotals().realized_pnl;
+    const periodPnl = realized - this.realizedAtPeriodStart;
+    this.realizedAtPeriodStart = realized;
+    const maxPeriodPnl = this.alerts.max_period_pnl;
+    if (maxPeriodPnl != null && Math.abs(periodPnl) > maxPeriodPnl) {
+      this.notifier.alert("period_pnl", "period_pnl", { pnl: periodPnl, limit: maxPeriodPnl });
+    }
+  }
+
+  /** Drive a strategy until the snapshots run out (end of replay), then shut down */
+  async run(strategy: Strategy, checkIntervalMs = this.config.trading.check_interval_ms ?? 1000): Promise<void> {
+    this.start(checkIntervalMs);
+    for (let snapshot = await this.next(); snapshot; snapshot = await this.next()) {
+      const current = snapshot;
+      await this.isolate(strategy.name, () => strategy.onSnapshot(current, this));
+    }
+    if (strategy.onFinish) await this.isolate(strategy.name, () => strategy.onFinish?.(this));
+    await this.shutdown("replay_finished");
+  }
+
+  /** Stop polling and background work and write the session footer */
+  async shutdown(reason: string): Promise<void> {
+    this.monitor?.stop();
+    this.blotter.close();
+    this.heartbeat.stop();
+    this.controlSocket?.stop();
+    if (this.snapshotWatchdog) clearInterval(this.snapshotWatchdog);
+    await this.endSession(reason);
+    if (this.replaySource) {
+      const gaps = this.replaySource.coverageGaps();
+      if (gaps.count > 0) log(`🕳️ ${gaps.count} coverage gap(s), ${gaps.seconds.toFixed(0)}s without data - results there assume nothing happened`);
+    }
+  }
+}
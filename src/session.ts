@@ -0,0 +1,82 @@
+import { execSync } from "child_process";
+import { readFileSync } from "fs";
+import type { Config } from "./config.js";
+import type { Storage } from "./storage.js";
+
+const SECRET_KEYS = ["private_key", "api_key", "api_secret", "api_passphrase"] as const;
+
+/** Package version and short git commit of the running code (null when not in a checkout) */
+function buildInfo(): { version: string | null; git_commit: string | null } {
+  let version: string | null = null;
+  let gitCommit: string | null = null;
+  try {
+    version = (JSON.parse(readFileSync(new URL("../package.json", import.meta.url), "utf-8")) as { version?: string }).version ?? null;
+  } catch {
+    /* bundled without package.json */
+  }
+  try {
+    gitCommit = execSync("git rev-parse --short HEAD", { stdio: ["ignore", "pipe", "ignore"] }).toString().trim() || null;
+    const dirty = execSync("git status --porcelain --untracked-files=no", { stdio: ["ignore", "pipe", "ignore"] }).toString().trim();
+    if (gitCommit && dirty) gitCommit += "-dirty";
+  } catch {
+    /* not a git checkout */
+  }
+  return { version, git_commit: gitCommit };
+}
+
+/** Config as recorded in session headers, with credentials masked */
+function redactConfig(config: Config): Config {
+  const polymarket = { ...config.polymarket };
+  for (const key of SECRET_KEYS) {
+    if (polymarket[key]) polymarket[key] = "***";
+  }
+  return { ...config, polymarket };
+}
+
+/**
+ * Start/end records for one run of the bot in `sessions.jsonl`, so every result in the data
+ * directory can be traced back to the code and configuration that produced it.
+ */
+export class SessionRecorder {
+  readonly id: string;
+  private storage: Storage;
+  private startedAt: number;
+  private ended = false;
+
+  constructor(storage: Storage, startedAtMs: number) {
+    this.storage = storage;
+    this.startedAt = startedAtMs;
+    this.id = new Date(startedAtMs).toISOString().replace(/[-:.]/g, "");
+  }
+
+  start(details: { mode: string; strategy: string; config: Config; seed: number | null; starting_balance: number | null }): void {
+    this.storage.appendJsonl("sessions.jsonl", {
+      event: "start",
+      session_id: this.id,
+      ts: Math.floor(this.startedAt / 1000),
+      ...buildInfo(),
+      mode: details.mode,
+      strategy: details.strategy,
+      // Fills and prices are deterministic; the seed reproduces the randomized parts (`order_jitter`).
+      seed: details.seed,
+      starting_balance: details.starting_balance,
+      config: redactConfig(details.config),
+    });
+  }
+
+  /** Write the footer once; later calls (e.g. a signal after a normal exit) are ignored */
+  end(details: { reason: string; ending_balance: number | null; totals: Record<string, unknown> }): void {
+    if (this.ended) return;
+    this.ended = true;
+    const endedAt = Date.now();
+    this.storage.appendJsonl("sessions.jsonl", {
+      event: "end",
+      session_id: this.id,
+      ts: Math.floor(endedAt / 1000),
+      duration_seconds: Math.round((endedAt - this.startedAt) / 1000),
+      reason: details.reason,
+      ending_balance: details.ending_balance,
+      totals: details.totals,
+    });
+  }
+}
@@ -0,0 +1,25 @@
+/** How a simulated order treats a touch exactly at its limit: fill it, or wait for the price to trade through */
+export type SimTieBreak = "fill" | "trade_through";
+
+/**
+ * Simulated fill rule shared by every resting-order check: a buy fills when the touch (ask, or a print)
+ * is below its limit, a sell when the touch is above it. Prices within `epsilon` of the limit count as
+ * equal (prices arrive as parsed decimals and averaged costs) and are settled by `tieBreak`.
+ */
+export class SimFillRule {
+  readonly epsilon: number;
+  readonly tieBreak: SimTieBreak;
+
+  constructor(epsilon: number, tieBreak: SimTieBreak) {
+    this.epsilon = Math.max(0, epsilon);
+    this.tieBreak = tieBreak;
+  }
+
+  /** True when an order on `side` at `limit` fills against `touch` */
+  crosses(side: "BUY" | "SELL", touch: number | null, limit: number): boolean {
+    if (touch == null) return false;
+    const through = side === "BUY" ? limit - touch : touch - limit;
+    if (Math.abs(through) <= this.epsilon) return this.tieBreak === "fill";
+    return through > 0;
+  }
+}
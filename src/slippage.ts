@@ -0,0 +1,83 @@
+import { formatUsd } from "./money.js";
+import type { Storage } from "./storage.js";
+import type { Asset, TokenType } from "./types.js";
+
+export const SLIPPAGE_FILE = "slippage.jsonl";
+
+/** One live fill as written to `slippage.jsonl` */
+export interface SlippageRecord {
+  ts: number;
+  order_id: string;
+  period_timestamp: number;
+  token_type: TokenType;
+  asset: Asset;
+  /** Reason code of the order (period_start_limit, grid_buy, ...) */
+  order_type: string;
+  side: "BUY" | "SELL";
+  liquidity: "maker" | "taker" | null;
+  target_price: number;
+  fill_price: number;
+  units: number;
+  /** Per share, positive when the fill was worse than the target (paid more / sold for less) */
+  slippage: number;
+  /** `slippage` x units, in USDC */
+  cost: number;
+}
+
+/** Rolling per-asset average slippage over the last `window` live fills, checked against a ceiling */
+export class SlippageMonitor {
+  private window: number;
+  private maxAvg: number | null;
+  private recent: Map<Asset, number[]> = new Map();
+
+  constructor(window: number, maxAvg: number | null) {
+    this.window = window;
+    this.maxAvg = maxAvg;
+  }
+
+  /** Add a fill; returns the rolling average when a full window is above the ceiling, else null */
+  add(asset: Asset, slippage: number): number | null {
+    const values = this.recent.get(asset) ?? [];
+    values.push(slippage);
+    if (values.length > this.window) values.shift();
+    this.recent.set(asset, values);
+    if (this.maxAvg == null || values.length < this.window) return null;
+    const avg = values.reduce((s, v) => s + v, 0) / values.length;
+    return avg > this.maxAvg ? avg : null;
+  }
+}
+
+/**
+ * Execution cost of live fills: target (decision) price vs executed price per asset and order type, and
+ * maker vs taker. Strategy edge is measured against the target; this is what execution took off it.
+ */
+export function slippageReport(storage: Storage): string {
+  const rows = storage.readJsonl<SlippageRecord>(SLIPPAGE_FILE);
+  const lines = ["Slippage: target vs executed price of live fills (+ = worse than target)"];
+  if (rows.length === 0) {
+    lines.push("   (no live fills recorded yet)");
+    return lines.join("\n");
+  }
+  const groups = new Map<string, SlippageRecord[]>();
+  const add = (key: string, r: SlippageRecord) => groups.set(key, [...(groups.get(key) ?? []), r]);
+  for (const r of rows) {
+    add(`${r.asset} ${r.order_type}`, r);
+    if (r.liquidity) add(`all ${r.liquidity}`, r);
+  }
+  lines.push("   group                            fills   avg (c/sh)   worst (c/sh)        cost");
+  for (const [key, rs] of [...groups].sort(([a], [b]) => a.localeCompare(b))) {
+    const avg = rs.reduce((s, r) => s + r.slippage, 0) / rs.length;
+    const worst = Math.max(...rs.map((r) => r.slippage));
+    const cost = rs.reduce((s, r) => s + r.cost, 0);
+    lines.push(
+      `   ${key.padEnd(32)} ${String(rs.length).padStart(5)} ${(avg * 100).toFixed(2).padStart(12)} ${(worst * 100).toFixed(2).padStart(14)} ${formatUsd(cost).padStart(11)}`
+    );
+  }
+  const total = rows.reduce((s, r) => s + r.cost, 0);
+  const notional = rows.reduce((s, r) => s + r.target_price * r.units, 0);
+  lines.push(
+    `   ${rows.length} fill(s), execution cost ${formatUsd(total)}` +
+      (notional > 0 ? ` (${((total / notional) * 100).toFixed(2)}% of target notional)` : "")
+  );
+  return lines.join("\n");
+}
@@ -0,0 +1,341 @@
+import axios from "axios";
+import { ethers } from "ethers";
+import { isProxied } from "./network.js";
+import type { Asset } from "./types.js";
+
+const PERIOD_DURATION = 900;
+const ASSETS: Asset[] = ["BTC", "ETH", "SOL", "XRP"];
+/** A feed started this close after a period boundary still counts as having seen the open */
+const OPEN_SLACK_SECONDS = 2;
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/** Underlying spot price source for BTC/ETH/SOL/XRP */
+export interface SpotFeed {
+  readonly name: string;
+  start(): void;
+  stop(): void;
+  /** Latest spot price, or null before the first print */
+  price(asset: Asset): number | null;
+  /**
+   * First price observed at or after the period start (the reference Up/Down resolves against); null
+   * for a period that was already running when the feed started, whose open it did not see
+   */
+  periodOpen(asset: Asset, periodTimestamp: number): number | null;
+  /** `periodOpen`, fetching the period's candle from the exchange when it was not observed; null when unavailable */
+  loadPeriodOpen(asset: Asset, periodTimestamp: number): Promise<number | null>;
+}
+
+interface WsLike {
+  onopen: (() => void) | null;
+  onmessage: ((ev: { data: unknown }) => void) | null;
+  onclose: (() => void) | null;
+  onerror: ((ev: unknown) => void) | null;
+  send(data: string): void;
+  close(): void;
+}
+type WsCtor = new (url: string) => WsLike;
+
+/** Global WebSocket (Node >= 22, or Node 20 with --experimental-websocket) */
+const WebSocketCtor = (globalThis as unknown as { WebSocket?: WsCtor }).WebSocket;
+
+/**
+ * Shared price/period-open bookkeeping. Streams over WebSocket when the feed has a stream URL, the
+ * runtime has a WebSocket and the host is not proxied; otherwise polls every `pollIntervalMs`.
+ */
+abstract class BaseSpotFeed implements SpotFeed {
+  abstract readonly name: string;
+  private latest: Map<Asset, number> = new Map();
+  private opens: Map<string, number> = new Map();
+  private ws: WsLike | null = null;
+  private timer: NodeJS.Timeout | null = null;
+  private stopped = true;
+  private pollIntervalMs: number;
+  private disconnectedAtMs: number | null = null;
+  /** Unix seconds the feed started; a period that began earlier has no observed open */
+  private startedSec = Infinity;
+
+  constructor(pollIntervalMs: number) {
+    this.pollIntervalMs = pollIntervalMs;
+  }
+
+  protected abstract poll(): Promise<Array<[Asset, number, number]>>;
+
+  protected wsUrl(): string | null {
+    return null;
+  }
+
+  protected subscribeMessage(): string | null {
+    return null;
+  }
+
+  /** Open of the period's candle from the exchange's history, or null when the source has none */
+  protected async fetchPeriodOpen(_asset: Asset, _periodTimestamp: number): Promise<number | null> {
+    return null;
+  }
+
+  /** Parse one WebSocket message into zero or more (asset, price, unix seconds) prints */
+  protected parseMessage(_data: string): Array<[Asset, number, number]> {
+    return [];
+  }
+
+  start(): void {
+    this.stopped = false;
+    this.startedSec = Math.min(this.startedSec, Math.floor(Date.now() / 1000));
+    const url = this.wsUrl();
+    if (WebSocketCtor && url && !isProxied(url)) this.connect(url);
+    else this.schedulePoll();
+  }
+
+  stop(): void {
+    this.stopped = true;
+    this.ws?.close();
+    this.ws = null;
+    if (this.timer) clearTimeout(this.timer);
+    this.timer = null;
+  }
+
+  price(asset: Asset): number | null {
+    return this.latest.get(asset) ?? null;
+  }
+
+  periodOpen(asset: Asset, periodTimestamp: number): number | null {
+    return this.opens.get(`${asset}_${periodTimestamp}`) ?? null;
+  }
+
+  async loadPeriodOpen(asset: Asset, periodTimestamp: number): Promise<number | null> {
+    const known = this.periodOpen(asset, periodTimestamp);
+    if (known != null) return known;
+    let open: number | null = null;
+    try {
+      open = await this.fetchPeriodOpen(asset, periodTimestamp);
+    } catch (e) {
+      log(`⚠️ ${this.name}: could not fetch the ${asset} open of period ${periodTimestamp}: ${String(e)}`);
+    }
+    if (open != null && Number.isFinite(open) && open > 0) this.opens.set(`${asset}_${periodTimestamp}`, open);
+    return this.periodOpen(asset, periodTimestamp);
+  }
+
+  protected record(asset: Asset, price: number, timestampSec: number): void {
+    if (!Number.isFinite(price) || price <= 0) return;
+    this.latest.set(asset, price);
+    const period = Math.floor(timestampSec / PERIOD_DURATION) * PERIOD_DURATION;
+    const key = `${asset}_${period}`;
+    // Launched mid-period, the first print is not the open: that period's open stays unknown.
+    if (!this.opens.has(key) && period + OPEN_SLACK_SECONDS >= this.startedSec) {
+      this.opens.set(key, price);
+      this.opens.delete(`${asset}_${period - 4 * PERIOD_DURATION}`);
+    }
+  }
+
+  private connect(url: string): void {
+    if (!WebSocketCtor || this.stopped) return;
+    const ws = new WebSocketCtor(url);
+    this.ws = ws;
+    ws.onopen = () => {
+      const sub = this.subscribeMessage();
+      if (sub) ws.send(sub);
+      if (this.disconnectedAtMs != null) {
+        log(`🔌 ${this.name} stream reconnected after ${((Date.now() - this.disconnectedAtMs) / 1000).toFixed(1)}s - back-filling prices`);
+        this.disconnectedAtMs = null;
+        void this.backfill();
+      }
+    };
+    ws.onmessage = (ev) => {
+      try {
+        for (const [asset, price, ts] of this.parseMessage(String(ev.data))) this.record(asset, price, ts);
+      } catch {
+        /* ignore malformed frames */
+      }
+    };
+    ws.onerror = () => ws.close();
+    ws.onclose = () => {
+      if (this.stopped) return;
+      this.disconnectedAtMs ??= Date.now();
+      setTimeout(() => this.connect(url), 1000);
+    };
+  }
+
+  /** One REST poll so prices missed while the stream was down are current again */
+  private async backfill(): Promise<void> {
+    try {
+      for (const [asset, price, ts] of await this.poll()) this.record(asset, price, ts);
+    } catch {
+      /* the stream will catch up */
+    }
+  }
+
+  private schedulePoll(): void {
+    if (this.stopped) return;
+    this.timer = setTimeout(async () => {
+      try {
+        for (const [asset, price, ts] of await this.poll()) this.record(asset, price, ts);
+      } catch {
+        /* keep last known prices on transient errors */
+      }
+      this.schedulePoll();
+    }, this.pollIntervalMs);
+  }
+}
+
+const BINANCE_SYMBOLS: Record<Asset, string> = { BTC: "BTCUSDT", ETH: "ETHUSDT", SOL: "SOLUSDT", XRP: "XRPUSDT" };
+
+export class BinanceSpotFeed extends BaseSpotFeed {
+  readonly name = "binance";
+
+  protected wsUrl(): string {
+    const streams = ASSETS.map((a) => `${BINANCE_SYMBOLS[a].toLowerCase()}@trade`).join("/");
+    return `wss://stream.binance.com:9443/stream?streams=${streams}`;
+  }
+
+  protected parseMessage(data: string): Array<[Asset, number, number]> {
+    const msg = JSON.parse(data) as { data?: { s?: string; p?: string; T?: number } };
+    const asset = ASSETS.find((a) => BINANCE_SYMBOLS[a] === msg.data?.s);
+    if (!asset || msg.data?.p == null) return [];
+    return [[asset, parseFloat(msg.data.p), Math.floor((msg.data.T ?? Date.now()) / 1000)]];
+  }
+
+  protected async poll(): Promise<Array<[Asset, number, number]>> {
+    const symbols = JSON.stringify(ASSETS.map((a) => BINANCE_SYMBOLS[a]));
+    const { data } = await axios.get<Array<{ symbol: string; price: string }>>(
+      "https://api.binance.com/api/v3/ticker/price",
+      { params: { symbols }, timeout: 5_000 }
+    );
+    const now = Math.floor(Date.now() / 1000);
+    const out: Array<[Asset, number, number]> = [];
+    for (const row of data ?? []) {
+      const asset = ASSETS.find((a) => BINANCE_SYMBOLS[a] === row.symbol);
+      if (asset) out.push([asset, parseFloat(row.price), now]);
+    }
+    return out;
+  }
+
+  protected async fetchPeriodOpen(asset: Asset, periodTimestamp: number): Promise<number | null> {
+    const { data } = await axios.get<Array<[number, string]>>("https://api.binance.com/api/v3/klines", {
+      params: { symbol: BINANCE_SYMBOLS[asset], interval: "15m", startTime: periodTimestamp * 1000, limit: 1 },
+      timeout: 5_000,
+    });
+    const candle = data?.[0];
+    return candle && candle[0] === periodTimestamp * 1000 ? parseFloat(candle[1]) : null;
+  }
+}
+
+const COINBASE_PRODUCTS: Record<Asset, string> = { BTC: "BTC-USD", ETH: "ETH-USD", SOL: "SOL-USD", XRP: "XRP-USD" };
+
+export class CoinbaseSpotFeed extends BaseSpotFeed {
+  readonly name = "coinbase";
+
+  protected wsUrl(): string {
+    return "wss://ws-feed.exchange.coinbase.com";
+  }
+
+  protected subscribeMessage(): string | null {
+    return JSON.stringify({
+      type: "subscribe",
+      product_ids: ASSETS.map((a) => COINBASE_PRODUCTS[a]),
+      channels: ["ticker"],
+    });
+  }
+
+  protected parseMessage(data: string): Array<[Asset, number, number]> {
+    const msg = JSON.parse(data) as { type?: string; product_id?: string; price?: string; time?: string };
+    if (msg.type !== "ticker" || msg.price == null) return [];
+    const asset = ASSETS.find((a) => COINBASE_PRODUCTS[a] === msg.product_id);
+    if (!asset) return [];
+    const ts = msg.time ? Date.parse(msg.time) : Date.now();
+    return [[asset, parseFloat(msg.price), Math.floor(ts / 1000)]];
+  }
+
+  protected async poll(): Promise<Array<[Asset, number, number]>> {
+    const rows = await Promise.all(
+      ASSETS.map(async (asset) => {
+        const { data } = await axios.get<{ price?: string; time?: string }>(
+          `https://api.exchange.coinbase.com/products/${COINBASE_PRODUCTS[asset]}/ticker`,
+          { timeout: 5_000 }
+        );
+        const ts = data?.time ? Date.parse(data.time) : Date.now();
+        return [asset, parseFloat(data?.price ?? ""), Math.floor(ts / 1000)] as [Asset, number, number];
+      })
+    );
+    return rows;
+  }
+
+  protected async fetchPeriodOpen(asset: Asset, periodTimestamp: number): Promise<number | null> {
+    // Candles are [time, low, high, open, close, volume], newest first.
+    const { data } = await axios.get<Array<[number, number, number, number, number, number]>>(
+      `https://api.exchange.coinbase.com/products/${COINBASE_PRODUCTS[asset]}/candles`,
+      {
+        params: {
+          granularity: PERIOD_DURATION,
+          start: new Date(periodTimestamp * 1000).toISOString(),
+          end: new Date((periodTimestamp + PERIOD_DURATION) * 1000).toISOString(),
+        },
+        timeout: 5_000,
+      }
+    );
+    const candle = (data ?? []).find((c) => c[0] === periodTimestamp);
+    return candle ? candle[3] : null;
+  }
+}
+
+/** Chainlink USD aggregators on Polygon mainnet */
+export const DEFAULT_CHAINLINK_FEEDS: Record<Asset, string> = {
+  BTC: "0xc907E116054Ad103354f2D350FD2514433D57F6f",
+  ETH: "0xF9680D99D6C9589e2a93a78A04A279e509205945",
+  SOL: "0x10C8264C0935b3B9870013e057f330Ff3e9C56dC",
+  XRP: "0x785ba89291f676b5386652eB12b30cF361020694",
+};
+
+const AGGREGATOR_ABI = [
+  "function decimals() view returns (uint8)",
+  "function latestRoundData() view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)",
+];
+
+/** On-chain oracle prints read from Polygon Chainlink aggregators (polling only) */
+export class ChainlinkSpotFeed extends BaseSpotFeed {
+  readonly name = "chainlink";
+  private aggregators: Array<[Asset, ethers.Contract]>;
+  private decimals: Map<Asset, number> = new Map();
+
+  constructor(rpcUrl: string, feeds: Partial<Record<Asset, string>>, pollIntervalMs: number) {
+    super(pollIntervalMs);
+    const provider = new ethers.providers.JsonRpcProvider(rpcUrl);
+    const addresses = { ...DEFAULT_CHAINLINK_FEEDS, ...feeds };
+    this.aggregators = ASSETS.map((a) => [a, new ethers.Contract(addresses[a], AGGREGATOR_ABI, provider)] as [Asset, ethers.Contract]);
+  }
+
+  protected async poll(): Promise<Array<[Asset, number, number]>> {
+    const rows = await Promise.all(
+      this.aggregators.map(async ([asset, contract]) => {
+        let decimals = this.decimals.get(asset);
+        if (decimals == null) {
+          decimals = Number(await contract.decimals());
+          this.decimals.set(asset, decimals);
+        }
+        const round = (await contract.latestRoundData()) as { answer: ethers.BigNumber; updatedAt: ethers.BigNumber };
+        const price = parseFloat(ethers.utils.formatUnits(round.answer, decimals));
+        return [asset, price, round.updatedAt.toNumber()] as [Asset, number, number];
+      })
+    );
+    return rows;
+  }
+}
+
+export interface SpotFeedOptions {
+  pollIntervalMs: number;
+  polygonRpcUrl?: string | null;
+  chainlinkFeeds?: Partial<Record<Asset, string>> | null;
+}
+
+/** Build a spot feed by name ("binance", "coinbase", "chainlink"), or null when unset */
+export function createSpotFeed(kind: string | null | undefined, opts: SpotFeedOptions): SpotFeed | null {
+  if (kind === "binance") return new BinanceSpotFeed(opts.pollIntervalMs);
+  if (kind === "coinbase") return new CoinbaseSpotFeed(opts.pollIntervalMs);
+  if (kind === "chainlink") {
+    if (!opts.polygonRpcUrl) throw new Error("polygon_rpc_url is required for the chainlink spot feed");
+    return new ChainlinkSpotFeed(opts.polygonRpcUrl, opts.chainlinkFeeds ?? {}, opts.pollIntervalMs);
+  }
+  return null;
+}
@@ -0,0 +1,81 @@
+import type { Storage } from "./storage.js";
+import type { Strategy } from "./strategy.js";
+import type { Asset, MarketSnapshot, TokenPrice } from "./types.js";
+
+const MARKETS: Array<[Asset, (s: MarketSnapshot) => { up_token: TokenPrice | null; down_token: TokenPrice | null }]> = [
+  ["BTC", (s) => s.btc_market],
+  ["ETH", (s) => s.eth_market],
+  ["SOL", (s) => s.solana_market],
+  ["XRP", (s) => s.xrp_market],
+];
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+interface SideStats {
+  ticks: number;
+  /** Ticks with both a bid and an ask */
+  quoted: number;
+  spreads: number[];
+}
+
+/** Bid/ask spread statistics per token through each period, persisted per period to `spreads/<period>.json` */
+export class SpreadTracker implements Strategy {
+  readonly name = "spreads";
+  private storage: Storage;
+  private period: number | null = null;
+  private stats: Map<string, SideStats> = new Map();
+
+  constructor(storage: Storage) {
+    this.storage = storage;
+  }
+
+  onSnapshot(snapshot: MarketSnapshot): void {
+    if (this.period !== snapshot.period_timestamp) {
+      const finished = this.flush();
+      if (finished) log(finished);
+      this.period = snapshot.period_timestamp;
+    }
+    for (const [asset, market] of MARKETS) {
+      const m = market(snapshot);
+      for (const [side, token] of [["Up", m.up_token], ["Down", m.down_token]] as Array<[string, TokenPrice | null]>) {
+        if (!token) continue;
+        const key = `${asset} ${side}`;
+        const s = this.stats.get(key) ?? { ticks: 0, quoted: 0, spreads: [] };
+        this.stats.set(key, s);
+        s.ticks++;
+        if (token.bid == null || token.ask == null) continue;
+        s.quoted++;
+        s.spreads.push(token.ask - token.bid);
+      }
+    }
+  }
+
+  /** Persist the finished period (avg/median/max spread and two-sided quote ratio), and return a one-line summary */
+  flush(): string | null {
+    if (this.period == null || this.stats.size === 0) return null;
+    const tokens: Record<string, { avg: number | null; median: number | null; max: number | null; quoted_pct: number }> = {};
+    for (const [key, s] of this.stats) {
+      const sorted = [...s.spreads].sort((a, b) => a - b);
+      tokens[key] = {
+        avg: sorted.length > 0 ? sorted.reduce((a, b) => a + b, 0) / sorted.length : null,
+        median: sorted.length > 0 ? sorted[Math.floor(sorted.length / 2)] : null,
+        max: sorted.length > 0 ? sorted[sorted.length - 1] : null,
+        quoted_pct: (s.quoted / s.ticks) * 100,
+      };
+    }
+    this.storage.writeJson(`spreads/${this.period}.json`, { period_timestamp: this.period, tokens });
+    const summary = Object.entries(tokens)
+      .map(([key, t]) => `${key} ${t.avg != null ? t.avg.toFixed(3) : "-"} (${t.quoted_pct.toFixed(0)}% quoted)`)
+      .join(" | ");
+    this.stats = new Map();
+    return `↔️ Avg spread (period ${this.period}): ${summary}`;
+  }
+
+  /** Persist and log the last period (end of replay) */
+  onFinish(): void {
+    const finished = this.flush();
+    if (finished) log(finished);
+  }
+}
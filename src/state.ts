@@ -0,0 +1,67 @@
+import { readFileSync, writeFileSync } from "fs";
+import { formatTime } from "./money.js";
+import type { Storage } from "./storage.js";
+import type { TraderCheckpoint } from "./trader.js";
+
+/** Trader state written at shutdown, relative to `data_dir` */
+export const STATE_FILE = "state.json";
+/** Imported state waiting for the next start to pick it up */
+export const PENDING_IMPORT_FILE = "state-import.json";
+
+/** What `state.json` holds */
+export interface SavedState {
+  saved_at: number;
+  trader: TraderCheckpoint;
+}
+
+/** Everything needed to continue a deployment on another host, in one file */
+interface StateBundle {
+  version: 1;
+  exported_at: number;
+  state: SavedState;
+  control: unknown;
+  equity_history: unknown[];
+}
+
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * `--state export <file>`: bundle the state saved at the last shutdown (positions, resting orders,
+ * stop-out and rejection counters), the control file and the equity history into `file`. Stop the bot
+ * first, between periods, so the saved state is current.
+ */
+export function exportState(storage: Storage, file: string): void {
+  const state = storage.readJson<SavedState>(STATE_FILE);
+  if (!state) throw new Error(`No ${storage.path(STATE_FILE)} - stop the bot once so it saves its state, then export`);
+  const bundle: StateBundle = {
+    version: 1,
+    exported_at: Date.now(),
+    state,
+    control: storage.readJson<unknown>("control.json"),
+    equity_history: storage.readJsonl<unknown>("position_snapshots.jsonl"),
+  };
+  writeFileSync(file, JSON.stringify(bundle));
+  const cp = state.trader;
+  log(
+    `📦 Exported state saved ${formatTime(state.saved_at)}: ${cp.positions.length} position(s), ` +
+      `${cp.strategy_orders.length} resting strategy order(s), ${bundle.equity_history.length} equity point(s) -> ${file}`
+  );
+}
+
+/**
+ * `--state import <file>`: unpack a bundle into this host's `data_dir`. The trader state is restored by
+ * the next start; the control file is replaced and the equity history appended.
+ */
+export function importState(storage: Storage, file: string): void {
+  const bundle = JSON.parse(readFileSync(file, "utf-8")) as StateBundle;
+  if (bundle.version !== 1 || !bundle.state?.trader) throw new Error(`${file} is not a state export`);
+  storage.writeJson(PENDING_IMPORT_FILE, bundle.state);
+  if (bundle.control != null) storage.writeJson("control.json", bundle.control);
+  for (const row of bundle.equity_history) storage.appendJsonl("position_snapshots.jsonl", row);
+  log(
+    `📥 Imported state from ${file} (exported ${formatTime(bundle.exported_at)}): ` +
+      `${bundle.state.trader.positions.length} position(s), restored on the next start`
+  );
+}
@@ -0,0 +1,54 @@
+function log(msg: string): void {
+  process.stderr.write(msg + "\n");
+}
+
+/**
+ * Synthetic stops (the CLOB has none): a stop fires once the mark has been at or below its level for
+ * `confirmTicks` consecutive checks, so one bad tick cannot trigger it, and only while the mark is within
+ * `maxSlippage` of the level: the exit is a marketable limit at `stop - maxSlippage`, never lower.
+ */
+export class StopTriggerEngine {
+  private confirmTicks: number;
+  private maxSlippage: number | null;
+  private touches: Map<string, number> = new Map();
+  private gapWarned: Set<string> = new Set();
+
+  constructor(confirmTicks: number, maxSlippage: number | null) {
+    this.confirmTicks = Math.max(1, confirmTicks);
+    this.maxSlippage = maxSlippage;
+  }
+
+  /** Record this check's mark; true when the stop should be executed now */
+  check(key: string, mark: number, stop: number): boolean {
+    if (mark > stop) {
+      this.clear(key);
+      return false;
+    }
+    const touches = (this.touches.get(key) ?? 0) + 1;
+    this.touches.set(key, touches);
+    if (touches < this.confirmTicks) {
+      log(`⏱️ Stop ${key} touched (mark $${mark.toFixed(2)} <= $${stop.toFixed(2)}), confirming ${touches}/${this.confirmTicks}`);
+      return false;
+    }
+    const floor = this.limitPrice(stop);
+    if (mark < floor) {
+      if (!this.gapWarned.has(key)) {
+        this.gapWarned.add(key);
+        log(`⚠️ Stop ${key}: mark $${mark.toFixed(2)} gapped below the slippage floor $${floor.toFixed(2)} - holding until it is back above`);
+      }
+      return false;
+    }
+    this.clear(key);
+    return true;
+  }
+
+  /** Lowest acceptable exit price for a stop level */
+  limitPrice(stop: number): number {
+    return this.maxSlippage == null ? 0 : Math.max(0.01, stop - this.maxSlippage);
+  }
+
+  clear(key: string): void {
+    this.touches.delete(key);
+    this.gapWarned.delete(key);
+  }
+}
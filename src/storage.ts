@@ -0,0 +1,42 @@
+import { appendFileSync, existsSync, mkdirSync, readFileSync, writeFileSync } from "fs";
+import { dirname, join } from "path";
+
+/** Local JSON/JSONL persistence under the configured data directory */
+export class Storage {
+  private dir: string;
+
+  constructor(dir: string) {
+    this.dir = dir;
+  }
+
+  path(...parts: string[]): string {
+    return join(this.dir, ...parts);
+  }
+
+  appendJsonl(relPath: string, record: unknown): void {
+    const file = this.path(relPath);
+    mkdirSync(dirname(file), { recursive: true });
+    appendFileSync(file, JSON.stringify(record) + "\n");
+  }
+
+  writeJson(relPath: string, value: unknown): void {
+    const file = this.path(relPath);
+    mkdirSync(dirname(file), { recursive: true });
+    writeFileSync(file, JSON.stringify(value, null, 2));
+  }
+
+  readJson<T>(relPath: string): T | null {
+    const file = this.path(relPath);
+    if (!existsSync(file)) return null;
+    return JSON.parse(readFileSync(file, "utf-8")) as T;
+  }
+
+  readJsonl<T>(relPath: string): T[] {
+    const file = this.path(relPath);
+    if (!existsSync(file)) return [];
+    return readFileSync(file, "utf-8")
+      .split("\n")
+      .filter((line) => line.trim().length > 0)
+      .map((line) => JSON.parse(line) as T);
+  }
+}
@@ -0,0 +1,11 @@
+import type { BotRuntime } from "./runtime.js";
+import type { MarketSnapshot } from "./types.js";
+
+/** A trading strategy driven by {@link BotRuntime.run}: one call per snapshot, after the runtime's per-tick work */
+export interface Strategy {
+  /** Step name in logs and alerts when a call throws */
+  readonly name: string;
+  onSnapshot(snapshot: MarketSnapshot, runtime: BotRuntime): unknown;
+  /** Once, after the last snapshot (end of replay) */
+  onFinish?(runtime: BotRuntime): unknown;
+}
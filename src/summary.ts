@@ -0,0 +1,78 @@
+import { formatTime, formatUsd } from "./money.js";
+import type { TraderState } from "./trader.js";
+import { tokenTypeDisplayName } from "./types.js";
+
+export type SummarySection = "pnl" | "orders" | "positions" | "margin";
+export type SummaryDestination = "log" | "notifier" | "dashboard";
+
+export interface SummaryConfig {
+  /** Seconds between summaries (default 30) */
+  interval_seconds?: number | null;
+  /** What to include, in order (default all) */
+  sections?: SummarySection[] | null;
+  /** Where to send it (default log only) */
+  destinations?: SummaryDestination[] | null;
+  /** One line instead of one line per order and position */
+  compact?: boolean | null;
+}
+
+const ALL_SECTIONS: SummarySection[] = ["pnl", "orders", "positions", "margin"];
+
+/** Periodic trading summary from a trader state snapshot */
+export function formatSummary(state: TraderState, cfg: SummaryConfig): string {
+  const sections = cfg.sections ?? ALL_SECTIONS;
+  const t = state.totals;
+  const time = formatTime(state.ts_ms, true);
+  if (cfg.compact) {
+    const parts = [`📋 ${time}`];
+    for (const s of sections) {
+      if (s === "pnl") parts.push(`PnL ${formatUsd(t.realized_pnl)}`, `fills ${t.filled}/${t.orders}`);
+      if (s === "orders") parts.push(`orders ${state.orders.length}`);
+      if (s === "positions") {
+        const shares = state.positions.reduce((sum, p) => sum + p.remaining_units, 0);
+        const cost = state.positions.reduce((sum, p) => sum + p.cost, 0);
+        parts.push(`pos ${state.positions.length} (${shares.toFixed(1)} sh, ${formatUsd(cost)})`);
+      }
+      if (s === "margin") parts.push(`worst ${formatUsd(state.portfolio.worst_case_pnl)}`);
+    }
+    return parts.join(" | ");
+  }
+  const lines = [`📋 Summary ${time}`];
+  for (const s of sections) {
+    if (s === "pnl") {
+      lines.push(
+        `   PnL ${formatUsd(t.realized_pnl)} realized | ${t.filled} of ${t.orders} order(s) filled, ${t.cancelled} cancelled, ` +
+          `${t.closed} closed | invested ${formatUsd(t.invested)}` + (state.balance != null ? ` | balance ${formatUsd(state.balance)}` : "")
+      );
+    }
+    if (s === "orders") {
+      lines.push(`   Working orders: ${state.orders.length}`);
+      for (const o of state.orders) {
+        lines.push(
+          `      ${o.side} ${tokenTypeDisplayName(o.token_type)} ${o.units.toFixed(2)} @ $${o.price.toFixed(2)} = ${formatUsd(o.units * o.price)} ` +
+            `[${o.order_id ?? "pending"}]`
+        );
+      }
+    }
+    if (s === "positions") {
+      lines.push(`   Open positions: ${state.positions.length}`);
+      for (const p of state.positions) {
+        lines.push(
+          `      ${p.position_id} ${tokenTypeDisplayName(p.token_type)} ${p.remaining_units.toFixed(2)} sh @ $${p.avg_price.toFixed(4)}` +
+            ` | cost ${formatUsd(p.cost)}` +
+            (p.value != null && p.mark != null ? ` | value ${formatUsd(p.value)} @ $${p.mark.toFixed(2)}` : " | awaiting resolution") +
+            ` | realized ${formatUsd(p.realized_pnl)}` + (p.disputed ? " | ⚖️ DISPUTED" : "")
+        );
+      }
+    }
+    if (s === "margin") {
+      const pf = state.portfolio;
+      lines.push(`   Worst case ${formatUsd(pf.worst_case_pnl)} | best case ${formatUsd(pf.best_case_pnl)} over ${pf.markets.length} market(s)`);
+      for (const m of pf.markets) {
+        lines.push(`      ${m.asset} ${formatTime(m.period_timestamp * 1000, true)}: up ${formatUsd(m.pnl_if_up)} / down ${formatUsd(m.pnl_if_down)}`);
+      }
+      if (pf.unattributed_cost > 0) lines.push(`      strategy inventory ${formatUsd(pf.unattributed_cost)} (counted as lost)`);
+    }
+  }
+  return lines.join("\n");
+}
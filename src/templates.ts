@@ -0,0 +1,60 @@
+import { formatTime, formatUsd } from "./money.js";
+
+/** Values an alert template can reference as `{{name}}` */
+export type TemplateVars = Record<string, string | number | null | undefined>;
+
+/**
+ * Built-in alert wording, one template per alert kind. Override any of them (e.g. to translate) under
+ * `alerts.templates`. Filters: `{{pnl|usd}}` (display currency), `{{price|price:4}}` ($ and decimals),
+ * `{{pct|fixed:1}}` (decimals), `{{period|time}}` (epoch seconds in the display time zone).
+ */
+export const DEFAULT_TEMPLATES: Record<string, string> = {
+  alert: "🚨 {{message}}",
+  suppressed: " (+{{count}} similar suppressed)",
+  endpoint_failover: "{{endpoint}} switched from {{from}} to {{to}}",
+  failover_standby: "{{instance}} found the lease taken by {{holder}} and stepped down",
+  failover_takeover: "{{instance}} took over order placement (primary lease {{lease_age}})",
+  exchange_halt: "Exchange halted ({{reason}}) - entries paused, re-checking every {{recheck_seconds}}s",
+  snapshot_gap: "No priced snapshot for {{seconds}}s",
+  strategy_error: "{{step}} threw: {{error}}",
+  period_pnl: "Realized PnL moved {{pnl|usd}} in one period (limit {{limit|usd}})",
+  order_rejections: "{{count}} orders rejected in a row (last: {{token}}, {{type}}: {{error}})",
+  fill_deviation: "{{token}} filled at {{fill_price|price:4}}, {{deviation_pct|fixed:1}}% off target {{target_price|price:4}}",
+  slippage: "{{asset}} average slippage {{avg_cents|fixed:2}}c/share over the last {{window}} fills (max {{max_cents|fixed:2}}c)",
+  uma_dispute: "UMA dispute on {{token}} (period {{period|time}}): {{shares|fixed:2}} shares held, settlement on hold",
+  uma_dispute_over: "UMA dispute on {{token}} is over ({{status}})",
+  auth_failure: "Order rejected for credentials/signature: {{error}}",
+  redemption_failed: "Winnings unredeemed: redemption of {{condition_id}} failed after {{waiting_minutes}} min queued: {{error}}",
+};
+
+function applyFilter(value: string | number, filter: string | undefined, arg: string | undefined): string {
+  const n = typeof value === "number" ? value : parseFloat(value);
+  const digits = arg != null ? parseInt(arg, 10) : undefined;
+  if (filter == null || !Number.isFinite(n)) return String(value);
+  if (filter === "usd") return formatUsd(n);
+  if (filter === "price") return `$${n.toFixed(digits ?? 2)}`;
+  if (filter === "fixed") return n.toFixed(digits ?? 2);
+  if (filter === "time") return formatTime(n * 1000);
+  return String(value);
+}
+
+/** Fill `{{name}}` / `{{name|filter:arg}}` placeholders; unknown names render empty */
+export function renderTemplate(template: string, vars: TemplateVars): string {
+  return template.replace(/\{\{\s*(\w+)\s*(?:\|\s*(\w+)(?::(\d+))?\s*)?\}\}/g, (_m, name: string, filter?: string, arg?: string) => {
+    const value = vars[name];
+    return value == null ? "" : applyFilter(value, filter, arg);
+  });
+}
+
+/** Alert wording: the configured templates over the built-in ones */
+export class NotificationTemplates {
+  private templates: Record<string, string>;
+
+  constructor(overrides: Record<string, string> | null | undefined) {
+    this.templates = { ...DEFAULT_TEMPLATES, ...(overrides ?? {}) };
+  }
+
+  render(kind: string, vars: TemplateVars): string {
+    return renderTemplate(this.templates[kind] ?? `${kind} {{message}}`, vars);
+  }
+}
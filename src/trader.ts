@@ -1,27 +1,679 @@
-import { createClobClient, placeLimitOrder } from "./clob.js";
+import type { ClobClient } from "clob-client-sdk";
+import { createClobClient, getCollateralBalance, getFillDetails, placeFakOrder, placeLimitOrder } from "./clob.js";
+import type { FillDetails } from "./clob.js";
 import type { PolymarketApi } from "./api.js";
+import { clock, nowSeconds } from "./clock.js";
+import type { BotControl } from "./control.js";
+import type { TradeEvent, TradeEventBus } from "./events.js";
+import type { Notifier } from "./notifier.js";
+import type { RedemptionScheduler } from "./redeem.js";
+import type { RiskBudget } from "./risk.js";
+import { portfolioView } from "./portfolio.js";
+import type { Holding, PortfolioView } from "./portfolio.js";
+import type { HaltGuard } from "./halt.js";
+import type { FailoverGuard } from "./failover.js";
+import type { FillProbabilityModel, FillQuery } from "./fill-model.js";
+import { LatencyTracker } from "./latency.js";
+import { StopTriggerEngine } from "./stops.js";
+import { SimFillRule } from "./sim-fill.js";
+import { randomSeed, seededRandom } from "./rng.js";
+import { SLIPPAGE_FILE, SlippageMonitor } from "./slippage.js";
+import { DISPUTES_FILE, isUnderDispute } from "./disputes.js";
+import type { DisputeRecord } from "./disputes.js";
+import type { CapitalMark } from "./capital.js";
+import type { SlippageRecord } from "./slippage.js";
+import { PERMANENT_REJECTIONS, classifyRejection } from "./rejections.js";
+import type { RejectionType } from "./rejections.js";
+import { formatTime, formatUsd } from "./money.js";
+import { logs } from "./logging.js";
+import { attributeByAsset } from "./reports.js";
+import type { AssetAttribution } from "./reports.js";
+import type { SpotFeed } from "./spot.js";
+import type { Storage } from "./storage.js";
 import type { Config } from "./config.js";
-import type { BuyOpportunity, TokenType } from "./types.js";
-import { tokenTypeDisplayName } from "./types.js";
+import type { Asset, BuyOpportunity, MarketSnapshot, TokenType } from "./types.js";
+import { assetForTokenType, oppositeTokenType, tokenPriceForType, tokenTypeDisplayName } from "./types.js";
 
-interface PendingTrade {
+export interface SellLeg {
+  reason: "take_profit" | "stop_loss" | "hedge" | "early_exit" | "resolution" | "spread_capture" | "adverse_selection";
+  units: number;
+  price: number;
+  pnl: number;
+  /** Exit order id (simulated ids are `sim-N`); null for resolution */
+  order_id?: string | null;
+}
+
+/** What an exit order executed; sell legs are booked from this, not from the order sent */
+interface ExitFill {
+  order_id: string;
+  units: number;
+  price: number;
+}
+
+/** One-cancels-other exit pair: a resting take-profit sell and a stop trigger */
+interface OcoPair {
+  take_profit: StrategyOrder;
+  stop_price: number;
+}
+
+/** A buy placed into a filled position; it joins the position at average cost once it fills */
+interface PendingAdd {
+  order_id: string | null;
+  units: number;
+  price: number;
+  target_price: number;
+}
+
+export interface PendingTrade {
+  /** Generated id; several positions can share a token and period (re-entry after a stop-out) */
+  position_id: string;
   token_id: string;
   condition_id: string;
   token_type: TokenType;
   market_timestamp: number;
+  units: number;
+  purchase_price: number;
+  investment_amount: number;
+  remaining_units: number;
+  sell_legs: SellLeg[];
+  take_profits_hit: number;
+  /** First fill price, the reference for DCA steps */
+  entry_price: number;
+  dca_adds: number;
+  /** Reason code and note of the order that opened the position */
+  reason: string;
+  note: string | null;
+  oco: OcoPair | null;
+  oco_armed: boolean;
+  /** Resting sell of the spread-capture pair this position belongs to */
+  spread_capture?: StrategyOrder | null;
+  spread_capture_armed?: boolean;
+  /** The order that opened the position */
+  order_id: string | null;
+  /** Every filled buy order of the position, the opening one first */
+  order_ids?: string[];
+  /** Buys placed into the position that have not filled yet, see `checkPendingAdds` */
+  pending_adds?: PendingAdd[];
+  target_price: number;
+  filled: boolean;
+  fill_elapsed_seconds: number | null;
+  /** Last bid seen while the position was open, compared with the payout at resolution */
+  last_mark: { bid: number; time_remaining_seconds: number } | null;
+  /** Set while the market's UMA resolution is disputed (`uma_dispute_check`); settlement waits for it */
+  dispute?: { since_ms: number; resolved_ms: number | null } | null;
   sold: boolean;
+  hedged: boolean;
+  cancelled: boolean;
+}
+
+/** Custom order sizing; register with `BotRuntimeBuilder.sizer` */
+export interface Sizer {
+  /** Shares to buy; `defaultUnits` is what the config would size. Zero or less skips the order. */
+  units(opportunity: BuyOpportunity, limitPrice: number, defaultUnits: number): number;
+}
+
+/** A plain limit order for a strategy that manages its own orders (grid ladders) */
+export interface StrategyOrder {
+  order_id: string;
+  token_id: string;
+  token_type: TokenType;
+  period_timestamp: number;
+  side: "BUY" | "SELL";
+  price: number;
+  units: number;
+  /** Strategy reason code carried onto the order's events */
+  reason?: string;
+}
+
+/** Session order and PnL totals, see `Trader.sessionTotals` */
+export interface SessionTotals {
+  orders: number;
+  filled: number;
+  cancelled: number;
+  closed: number;
+  open: number;
+  invested: number;
+  realized_pnl: number;
+  by_asset: Partial<Record<Asset, AssetAttribution>>;
+  by_reason: Record<string, { filled: number; realized_pnl: number }>;
+  maker_fills: number;
+  taker_fills: number;
+  maker_ratio: number | null;
+}
+
+/** A working order: an unfilled position buy or a resting strategy-managed order */
+export interface OrderState {
+  order_id: string | null;
+  position_id: string | null;
+  period_timestamp: number;
+  token_type: TokenType;
+  token_id: string;
+  side: "BUY" | "SELL";
+  price: number;
+  units: number;
+  reason: string | null;
+}
+
+export interface PositionState {
+  position_id: string;
+  period_timestamp: number;
+  token_type: TokenType;
+  token_id: string;
+  units: number;
+  remaining_units: number;
+  avg_price: number;
+  /** Total paid for the position (all units) */
+  invested: number;
+  /** Cost basis of the remaining units: `remaining_units * avg_price` */
+  cost: number;
+  /** Best bid of the latest snapshot; null once the period is over (the position awaits resolution) */
+  mark: number | null;
+  /** Remaining units at `mark` */
+  value: number | null;
+  realized_pnl: number;
+  reason: string;
+  legs: SellLeg[];
+  /** The market's UMA resolution is disputed; the position is held until it settles */
+  disputed: boolean;
+}
+
+/** Point-in-time copy of the trader's state for dashboards, the control API and tools; plain JSON */
+export interface TraderState {
+  ts_ms: number;
+  simulation: boolean;
+  orders: OrderState[];
+  positions: PositionState[];
+  totals: SessionTotals;
+  /** Worst-case PnL of open positions and resting buys over every resolution combination */
+  portfolio: PortfolioView;
+  /** USDC balance; null in simulation or when the CLOB call fails */
+  balance: number | null;
+}
+
+/** Serializable trader state for moving a deployment between hosts (`--state export` / `--state import`) */
+export interface TraderCheckpoint {
+  simulation: boolean;
+  positions: PendingTrade[];
+  positions_by_token: Record<string, string[]>;
+  position_seq: number;
+  strategy_orders: StrategyOrder[];
+  strategy_inventory_cost: number;
+  sim_inventory: Record<string, number>;
+  sim_order_seq: number;
+  stop_outs: Record<string, { stops: number; reentries: number }>;
+  rejected_tokens: string[];
+  consecutive_rejections: number;
+  liquidity: Record<"maker" | "taker", { fills: number; notional: number }>;
 }
 
 export class Trader {
   private api: PolymarketApi;
   private config: Config["trading"];
   private simulation: boolean;
+  /** Positions by position id */
   private pendingTrades: Map<string, PendingTrade> = new Map();
+  /** Position ids per `${period}_${token_id}`, oldest first */
+  private positionsByToken: Map<string, string[]> = new Map();
+  private positionSeq = 0;
+  /** Latest snapshot seen by `checkPositions`; marks open positions for `state()` */
+  private lastSnapshot: MarketSnapshot | null = null;
+  private client: ClobClient | null = null;
+  private clientUrl: string | null = null;
+  private spotFeed: SpotFeed | null = null;
+  private storage: Storage | null = null;
+  private control: BotControl | null = null;
+  private notifier: Notifier | null = null;
+  private sizer: Sizer | null = null;
+  private redemptions: RedemptionScheduler | null = null;
+  private events: TradeEventBus | null = null;
+  private riskBudget: RiskBudget | null = null;
+  private halt: HaltGuard | null = null;
+  private failover: FailoverGuard | null = null;
+  private fillModel: FillProbabilityModel | null = null;
+  /** Simulation: share of each winning fill treated as unfilled (`sim_adverse_selection`) */
+  private adverseHaircut = 0;
+  private consecutiveRejections = 0;
+  /** `${period}_${token_type}` of buys placed by this or an earlier run (from `placements.jsonl`) */
+  private placementMarkers: Set<string> = new Set();
+  private lastResolutionCheckMs = 0;
+  private latency: LatencyTracker;
+  private stops: StopTriggerEngine;
+  private simFill: SimFillRule;
+  private slippage: SlippageMonitor;
+  /** Resting strategy-managed orders by order id */
+  private strategyOrders: Map<string, StrategyOrder> = new Map();
+  /** Cost of shares bought through strategy-managed orders and not yet sold */
+  private strategyInventoryCost = 0;
+  /** Simulation: shares per token held through filled strategy-managed orders, which bound sells */
+  private simInventory: Map<string, number> = new Map();
+  private simOrderSeq = 0;
+  /** Seed of the `order_jitter` draws, recorded in the session header so a run can be reproduced */
+  readonly seed: number;
+  private random: () => number;
+  /** Wall-clock ms when markets were last subscribed, for `flat_periods.after_discovery_seconds` */
+  private marketsSubscribedMs: number | null = null;
+  /** `${period}_${token_id}` whose orders were refused for a reason a retry cannot fix */
+  private rejectedTokens: Set<string> = new Set();
+  /** Flat-period skips already logged, one per period, token and window */
+  private flatSkips: Set<string> = new Set();
+  /** Live fills by liquidity side */
+  private liquidity: Record<"maker" | "taker", { fills: number; notional: number }> = {
+    maker: { fills: 0, notional: 0 },
+    taker: { fills: 0, notional: 0 },
+  };
+  /** Stop-outs and re-entries per `${period}_${token_type}` */
+  private stopOuts: Map<string, { stops: number; reentries: number }> = new Map();
 
   constructor(api: PolymarketApi, config: Config["trading"], simulation: boolean) {
     this.api = api;
     this.config = config;
     this.simulation = simulation;
+    this.latency = new LatencyTracker(config.latency_warn_p95_ms ?? 1000);
+    this.stops = new StopTriggerEngine(config.stop_trigger?.confirm_ticks ?? 1, config.stop_trigger?.max_slippage ?? null);
+    this.simFill = new SimFillRule(config.sim_fill_epsilon ?? 0.0001, config.sim_fill_at_limit ?? "fill");
+    this.slippage = new SlippageMonitor(config.alerts?.slippage_window ?? 20, config.alerts?.max_avg_slippage ?? null);
+    this.seed = config.order_jitter?.seed ?? randomSeed();
+    this.random = seededRandom(this.seed);
+  }
+
+  setHaltGuard(halt: HaltGuard): void {
+    this.halt = halt;
+  }
+
+  setFailover(failover: FailoverGuard): void {
+    this.failover = failover;
+  }
+
+  setAdverseSelectionHaircut(haircut: number): void {
+    this.adverseHaircut = Math.min(0.95, Math.max(0, haircut));
+  }
+
+  setFillModel(model: FillProbabilityModel | null): void {
+    this.fillModel = model;
+  }
+
+  /** Fill-probability model estimate for a limit buy; null when no model is trained */
+  estimateFillProbability(q: FillQuery): number | null {
+    return this.fillModel?.estimate(q) ?? null;
+  }
+
+  /**
+   * Failover takeover: cancel every resting order on the account. They belong to the primary that
+   * stopped, whose positions this instance does not track; cancelling leaves a clean book to trade from.
+   */
+  async reconcileOpenOrders(): Promise<void> {
+    if (this.simulation) return;
+    const client = await this.getClient();
+    const open = (await client.getOpenOrders()) as Array<{ id?: string; asset_id?: string; side?: string; price?: string }>;
+    log(`🧹 Reconciling ${open.length} open order(s) left by the previous primary\n`);
+    for (const o of open) {
+      if (!o.id) continue;
+      await client.cancelOrder({ orderID: o.id });
+      log(`   Cancelled ${o.side ?? "?"} ${o.id} @ ${o.price ?? "?"} (token ${o.asset_id ?? "?"})\n`);
+    }
+  }
+
+  setRiskBudget(budget: RiskBudget): void {
+    this.riskBudget = budget;
+  }
+
+  /** Cost of open positions plus resting buys, as counted against the shared risk budget */
+  openExposure(): number {
+    let total = 0;
+    for (const t of this.pendingTrades.values()) {
+      if (!t.sold) total += t.remaining_units * t.purchase_price;
+      for (const a of t.pending_adds ?? []) total += a.units * a.price;
+    }
+    for (const o of this.strategyOrders.values()) if (o.side === "BUY") total += o.units * o.price;
+    return total + this.strategyInventoryCost;
+  }
+
+  /**
+   * Open positions and resting buys (counted as filled) resolved both ways per market; `extra` adds a
+   * prospective order. Grid inventory is not tracked per token and counts as lost either way.
+   */
+  portfolio(extra: Holding | null = null): PortfolioView {
+    const holdings: Holding[] = [];
+    for (const t of this.pendingTrades.values()) {
+      for (const a of t.pending_adds ?? []) holdings.push({ period_timestamp: t.market_timestamp, token_type: t.token_type, units: a.units, cost: a.units * a.price });
+      if (t.sold || t.cancelled) continue;
+      const units = t.filled ? t.remaining_units : t.units;
+      holdings.push({ period_timestamp: t.market_timestamp, token_type: t.token_type, units, cost: units * t.purchase_price });
+    }
+    for (const o of this.strategyOrders.values()) {
+      if (o.side === "BUY") holdings.push({ period_timestamp: o.period_timestamp, token_type: o.token_type, units: o.units, cost: o.units * o.price });
+    }
+    if (extra) holdings.push(extra);
+    return portfolioView(holdings, this.strategyInventoryCost);
+  }
+
+  /** Whether a buy would take the worst-case loss past `max_worst_case_loss`; logs when it would */
+  private exceedsMaxLoss(buy: Holding): boolean {
+    const max = this.config.max_worst_case_loss;
+    if (max == null) return false;
+    const before = this.portfolio().worst_case_pnl;
+    const after = this.portfolio(buy).worst_case_pnl;
+    // A buy that hedges (leaves the worst case no worse) is always allowed.
+    if (after >= before - 1e-9 || -after <= max + 1e-9) return false;
+    log(
+      `🛑 Skipping ${tokenTypeDisplayName(buy.token_type)} buy: worst-case loss would be ${formatUsd(-after)} ` +
+        `(now ${formatUsd(Math.max(0, -before))}, max_worst_case_loss ${formatUsd(max)})\n`
+    );
+    return true;
+  }
+
+  /**
+   * Whether a buy would open a market (`<asset>@<period>`, one condition_id) beyond `max_open_markets`.
+   * Adding to a market already open is always allowed. With `open_markets_policy: "replace_unfilled"` a
+   * slot is freed by cancelling the resting buys of the oldest market none of whose orders filled yet;
+   * otherwise (`skip_new`) the markets already open keep their slots and the new one is skipped.
+   */
+  private async exceedsMaxOpenMarkets(order: { period_timestamp: number; token_type: TokenType; reason?: string }): Promise<boolean> {
+    const max = this.config.max_open_markets;
+    if (max == null) return false;
+    const key = (period: number, t: TokenType) => `${assetForTokenType(t)}@${period}`;
+    const open = new Set(this.portfolio().markets.map((m) => `${m.asset}@${m.period_timestamp}`));
+    if (open.has(key(order.period_timestamp, order.token_type)) || open.size < max) return false;
+    if (this.config.open_markets_policy === "replace_unfilled") {
+      const strategyMarkets = new Set([...this.strategyOrders.values()].map((o) => key(o.period_timestamp, o.token_type)));
+      const trades = [...this.pendingTrades.values()].filter((t) => !t.sold && !t.cancelled);
+      const replaceable = [...open]
+        .filter((m) => !strategyMarkets.has(m))
+        .filter((m) => trades.every((t) => key(t.market_timestamp, t.token_type) !== m || !t.filled))
+        .sort((a, b) => Number(a.split("@")[1]) - Number(b.split("@")[1]));
+      const victim = replaceable.find((m) => Number(m.split("@")[1]) < order.period_timestamp);
+      if (victim) {
+        log(`🔄 ${victim} gives up its open-market slot to ${key(order.period_timestamp, order.token_type)} (max_open_markets ${max})\n`);
+        for (const t of trades) if (key(t.market_timestamp, t.token_type) === victim) await this.cancelUnfilled(t, Math.max(0, t.market_timestamp + PERIOD_DURATION - nowSeconds()));
+        if (this.portfolio().markets.length < max) return false;
+      }
+    }
+    log(`🛑 Skipping ${tokenTypeDisplayName(order.token_type)} buy: ${open.size} market(s) already open (max_open_markets ${max})\n`);
+    this.emit(
+      { market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: null, reason: order.reason },
+      { kind: "skipped", detail: "max_open_markets" }
+    );
+    return true;
+  }
+
+  /** Markets were just (re)subscribed: starts the `flat_periods.after_discovery_seconds` window */
+  noteMarketsSubscribed(): void {
+    this.marketsSubscribedMs = clock().now();
+  }
+
+  /**
+   * Whether `flat_periods` blocks a new entry now. Logs and emits a `skipped` event the first time per
+   * period, token and window.
+   */
+  private inFlatPeriod(order: { period_timestamp: number; token_type: TokenType; reason?: string }): boolean {
+    const cfg = this.config.flat_periods;
+    if (!cfg) return false;
+    const elapsed = nowSeconds() - order.period_timestamp;
+    let window: string | null = null;
+    if (cfg.first_seconds != null && elapsed < cfg.first_seconds) window = `first ${cfg.first_seconds}s of the period`;
+    else if (cfg.last_seconds != null && PERIOD_DURATION - elapsed <= cfg.last_seconds) window = `last ${cfg.last_seconds}s of the period`;
+    else if (
+      cfg.after_discovery_seconds != null &&
+      this.marketsSubscribedMs != null &&
+      clock().now() - this.marketsSubscribedMs < cfg.after_discovery_seconds * 1000
+    ) {
+      window = `${cfg.after_discovery_seconds}s after market discovery`;
+    }
+    if (window == null) return false;
+    const key = `${order.period_timestamp}_${order.token_type}_${window}`;
+    if (!this.flatSkips.has(key)) {
+      this.flatSkips.add(key);
+      log(`🧊 Flat period: no new ${tokenTypeDisplayName(order.token_type)} entries in the ${window}\n`);
+      this.emit(
+        { market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: null, reason: order.reason },
+        { kind: "skipped", detail: `flat period: ${window}` }
+      );
+    }
+    return true;
+  }
+
+  /**
+   * Place a limit order for a strategy that tracks its own orders. Null when the asset is paused, the
+   * exchange is halted, a buy falls in a flat period or does not fit the open-market limit, the worst-case
+   * loss limit or the shared risk budget, or a simulated sell is naked; throws if the CLOB rejects it.
+   */
+  async placeOrder(order: Omit<StrategyOrder, "order_id">): Promise<StrategyOrder | null> {
+    if (this.control?.isPaused(assetForTokenType(order.token_type)) || this.halt?.isHalted() || this.failover?.isStandby()) return null;
+    if (order.side === "BUY" && this.inFlatPeriod(order)) return null;
+    if (this.rejectedTokens.has(`${order.period_timestamp}_${order.token_id}`)) return null;
+    if (this.simulation && order.side === "SELL" && !this.checkSimSell(order)) return null;
+    const cost = order.side === "BUY" ? order.units * order.price : 0;
+    if (cost > 0 && (await this.exceedsMaxOpenMarkets(order))) return null;
+    if (cost > 0 && this.exceedsMaxLoss({ period_timestamp: order.period_timestamp, token_type: order.token_type, units: order.units, cost })) return null;
+    if (cost > 0 && this.riskBudget && !this.riskBudget.tryReserve(cost)) return null;
+    let orderId: string;
+    try {
+      orderId = await this.submitLimit(order.token_id, order.side, order.price, order.units);
+    } catch (e) {
+      if (cost > 0) this.riskBudget?.release(cost);
+      if (this.halt?.noteError(e)) throw e;
+      const type = this.noteRejection(order, e);
+      this.emit(
+        { market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: null, reason: order.reason },
+        { kind: "rejected", units: order.units, price: order.price, detail: `${type}: ${String(e)}` }
+      );
+      throw e;
+    }
+    this.consecutiveRejections = 0;
+    const placed: StrategyOrder = { ...order, order_id: orderId };
+    this.strategyOrders.set(orderId, placed);
+    this.emit({ market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: orderId, reason: order.reason }, {
+      kind: "placed",
+      units: order.units,
+      price: order.price,
+      detail: order.side,
+    });
+    return placed;
+  }
+
+  /** Whether a strategy-managed order has filled; a filled order stops being tracked */
+  async isFilled(order: StrategyOrder, snapshot: MarketSnapshot): Promise<boolean> {
+    if (!this.strategyOrders.has(order.order_id)) return false;
+    const filled = await this.orderFilled(order, snapshot);
+    if (!filled) return false;
+    const liquidity = await this.recordLiveFill({ ...order, reason: order.reason ?? "unspecified", target_price: order.price });
+    this.markFilled(order, liquidity);
+    return true;
+  }
+
+  /**
+   * Book a strategy-managed order as filled without checking, e.g. a sell sent into the bid. In simulation
+   * a sell larger than the shares still held fills only those, and `order.units` is reduced to match.
+   */
+  markFilled(order: StrategyOrder, liquidity: "maker" | "taker" | null = null): void {
+    if (!this.strategyOrders.delete(order.order_id)) return;
+    if (this.simulation) {
+      const held = this.simInventory.get(order.token_id) ?? 0;
+      if (order.side === "SELL" && order.units > held + 1e-9) {
+        log(`⚠️ SIM SELL ${order.order_id} ${tokenTypeDisplayName(order.token_type)}: ${order.units} share(s) asked, ${held} held - filling ${held}\n`);
+        order.units = held;
+      }
+      const after = held + (order.side === "BUY" ? order.units : -order.units);
+      if (after > 1e-9) this.simInventory.set(order.token_id, after);
+      else this.simInventory.delete(order.token_id);
+    }
+    const cost = order.units * order.price;
+    this.strategyInventoryCost = Math.max(0, this.strategyInventoryCost + (order.side === "BUY" ? cost : -cost));
+    this.emit({ market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: order.order_id, reason: order.reason }, {
+      kind: "filled",
+      units: order.units,
+      price: order.price,
+      detail: order.side,
+      liquidity,
+    });
+  }
+
+  /** Cancel a resting strategy-managed order */
+  async cancelOrder(order: StrategyOrder): Promise<void> {
+    if (!this.strategyOrders.has(order.order_id)) return;
+    await this.cancelResting(order.order_id);
+    this.strategyOrders.delete(order.order_id);
+    if (order.side === "BUY") this.riskBudget?.release(order.units * order.price);
+    this.emit({ market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: order.order_id, reason: order.reason }, {
+      kind: "cancelled",
+      units: order.units,
+      price: order.price,
+      detail: order.side,
+    });
+  }
+  /**
+   * Simulation check that a sell is covered by held shares not already committed to resting sells. A
+   * naked sell is rejected, or with `sim_naked_sells: "flag"` placed with a warning (it fills only what is
+   * held by then).
+   */
+  private checkSimSell(order: Omit<StrategyOrder, "order_id">): boolean {
+    const committed = [...this.strategyOrders.values()]
+      .filter((o) => o.side === "SELL" && o.token_id === order.token_id)
+      .reduce((sum, o) => sum + o.units, 0);
+    const available = (this.simInventory.get(order.token_id) ?? 0) - committed;
+    if (order.units <= available + 1e-9) return true;
+    const reject = (this.config.sim_naked_sells ?? "reject") === "reject";
+    const detail = `naked sell: ${order.units} share(s) > ${Math.max(0, available)} available`;
+    log(`${reject ? "🚫" : "⚠️"} SIM SELL ${tokenTypeDisplayName(order.token_type)} @ $${order.price.toFixed(2)} ${reject ? "rejected" : "flagged"} - ${detail}\n`);
+    if (reject) {
+      this.emit(
+        { market_timestamp: order.period_timestamp, token_type: order.token_type, order_id: null, reason: order.reason },
+        { kind: "rejected", units: order.units, price: order.price, detail }
+      );
+    }
+    return !reject;
+  }
+
+  /** Submit a limit order live, or make up an id in simulation; returns the order id */
+  private async submitLimit(tokenId: string, side: "BUY" | "SELL", price: number, units: number): Promise<string> {
+    if (this.simulation) return `sim-${++this.simOrderSeq}`;
+    const client = await this.getClient();
+    const result = await placeLimitOrder(client, {
+      tokenId,
+      side,
+      price: Math.round(price * 100) / 100,
+      size: Math.round(units * 100) / 100,
+    });
+    if (!result.orderID) throw new Error(`order rejected (status ${result.status})`);
+    return result.orderID;
+  }
+
+  /** Simulation: a buy fills once the ask reaches its price, a sell once the bid does (see `SimFillRule`). Live: ask the CLOB. */
+  private async orderFilled(order: StrategyOrder, snapshot: MarketSnapshot): Promise<boolean> {
+    if (this.simulation) {
+      const quote = tokenPriceForType(snapshot, order.token_type);
+      return this.simFill.crosses(order.side, (order.side === "BUY" ? quote?.ask : quote?.bid) ?? null, order.price);
+    }
+    try {
+      const client = await this.getClient();
+      const live = (await client.getOrder(order.order_id)) as { size_matched?: string; original_size?: string };
+      const matched = parseFloat(live.size_matched ?? "0");
+      return matched > 0 && matched >= parseFloat(live.original_size ?? String(order.units));
+    } catch {
+      return false;
+    }
+  }
+
+  private async cancelResting(orderId: string): Promise<void> {
+    if (this.simulation) return;
+    const client = await this.getClient();
+    await client.cancelOrder({ orderID: orderId });
+  }
+
+  /**
+   * Look up a live fill: maker/taker is counted towards the session split, and the executed price is
+   * written to `slippage.jsonl` against the target. Returns maker/taker; null in simulation or when unknown.
+   */
+  private async recordLiveFill(fill: {
+    order_id: string;
+    token_id: string;
+    token_type: TokenType;
+    period_timestamp: number;
+    side: "BUY" | "SELL";
+    reason: string;
+    units: number;
+    target_price: number;
+  }): Promise<"maker" | "taker" | null> {
+    if (this.simulation) return null;
+    let details: FillDetails = { liquidity: null, price: null };
+    try {
+      details = await getFillDetails(await this.getClient(), fill.order_id, fill.token_id);
+    } catch (e) {
+      log(`Could not look up the fill of order ${fill.order_id}: ${String(e)}\n`);
+    }
+    const { liquidity, price } = details;
+    if (liquidity) {
+      this.liquidity[liquidity].fills++;
+      this.liquidity[liquidity].notional += fill.units * fill.target_price;
+    }
+    if (price != null) {
+      const asset = assetForTokenType(fill.token_type);
+      const slippage = fill.side === "BUY" ? price - fill.target_price : fill.target_price - price;
+      const record: SlippageRecord = {
+        ts: nowSeconds(),
+        order_id: fill.order_id,
+        period_timestamp: fill.period_timestamp,
+        token_type: fill.token_type,
+        asset,
+        order_type: fill.reason,
+        side: fill.side,
+        liquidity,
+        target_price: fill.target_price,
+        fill_price: price,
+        units: fill.units,
+        slippage,
+        cost: slippage * fill.units,
+      };
+      this.storage?.appendJsonl(SLIPPAGE_FILE, record);
+      const avg = this.slippage.add(asset, slippage);
+      if (avg != null) {
+        this.notifier?.alert(`slippage_${asset}`, "slippage", {
+          asset,
+          avg_cents: avg * 100,
+          window: this.config.alerts?.slippage_window ?? 20,
+          max_cents: (this.config.alerts?.max_avg_slippage ?? 0) * 100,
+        });
+      }
+    }
+    return liquidity;
+  }
+
+  /** Share of live fills (by count) that were maker, or null before the first classified fill */
+  makerRatio(): number | null {
+    const total = this.liquidity.maker.fills + this.liquidity.taker.fills;
+    return total > 0 ? this.liquidity.maker.fills / total : null;
+  }
+
+  setEventBus(events: TradeEventBus): void {
+    this.events = events;
+  }
+
+  private emit(
+    trade: {
+      market_timestamp: number;
+      token_type: TokenType;
+      order_id: string | null;
+      position_id?: string;
+      reason?: string;
+      note?: string | null;
+    },
+    event: Partial<TradeEvent> & Pick<TradeEvent, "kind">
+  ): void {
+    this.events?.emit({
+      ts_ms: clock().now(),
+      period_timestamp: trade.market_timestamp,
+      token_type: trade.token_type,
+      order_id: trade.order_id,
+      position_id: trade.position_id ?? null,
+      reason: trade.reason,
+      note: trade.note ?? null,
+      ...event,
+    });
+  }
+
+  /** Latency distribution report for live orders, or null when none were placed */
+  latencyReport(): string | null {
+    return this.latency.report();
   }
 
   /** Check if we already have an active (unsold) position for this period + token type */
@@ -38,6 +690,308 @@ export class Trader {
     return false;
   }
 
+  /** Order and PnL totals over every position this process has opened */
+  sessionTotals(): SessionTotals {
+    const trades = [...this.pendingTrades.values()];
+    const filled = trades.filter((t) => t.filled);
+    const legPnl = (t: PendingTrade) => t.sell_legs.reduce((s, l) => s + l.pnl, 0);
+    const byReason: Record<string, { filled: number; realized_pnl: number }> = {};
+    for (const t of filled) {
+      const row = byReason[t.reason] ?? { filled: 0, realized_pnl: 0 };
+      row.filled++;
+      row.realized_pnl += legPnl(t);
+      byReason[t.reason] = row;
+    }
+    return {
+      orders: trades.length,
+      filled: filled.length,
+      cancelled: trades.filter((t) => t.cancelled).length,
+      closed: filled.filter((t) => t.sold).length,
+      open: filled.filter((t) => !t.sold).length,
+      invested: filled.reduce((sum, t) => sum + t.investment_amount, 0),
+      realized_pnl: filled.reduce((sum, t) => sum + legPnl(t), 0),
+      by_asset: attributeByAsset(trades.map((t) => ({ token_type: t.token_type, filled: t.filled, realized_pnl: legPnl(t) }))),
+      by_reason: byReason,
+      maker_fills: this.liquidity.maker.fills,
+      taker_fills: this.liquidity.taker.fills,
+      maker_ratio: this.makerRatio(),
+    };
+  }
+
+  /** Everything needed to continue where this process stops */
+  checkpoint(): TraderCheckpoint {
+    return JSON.parse(
+      JSON.stringify({
+        simulation: this.simulation,
+        positions: [...this.pendingTrades.values()],
+        positions_by_token: Object.fromEntries(this.positionsByToken),
+        position_seq: this.positionSeq,
+        strategy_orders: [...this.strategyOrders.values()],
+        strategy_inventory_cost: this.strategyInventoryCost,
+        sim_inventory: Object.fromEntries(this.simInventory),
+        sim_order_seq: this.simOrderSeq,
+        stop_outs: Object.fromEntries(this.stopOuts),
+        rejected_tokens: [...this.rejectedTokens],
+        consecutive_rejections: this.consecutiveRejections,
+        liquidity: this.liquidity,
+      })
+    ) as TraderCheckpoint;
+  }
+
+  /**
+   * Continue from a checkpoint taken by another process. Refuses one from the other mode: simulated
+   * positions are not on the exchange and live ones would be missed by a simulation.
+   */
+  restore(cp: TraderCheckpoint): void {
+    if (cp.simulation !== this.simulation) {
+      throw new Error(`state was saved in ${cp.simulation ? "simulation" : "live"} mode, this run is ${this.simulation ? "simulation" : "live"}`);
+    }
+    this.pendingTrades = new Map(cp.positions.map((t) => [t.position_id, t]));
+    this.positionsByToken = new Map(Object.entries(cp.positions_by_token));
+    this.positionSeq = cp.position_seq;
+    this.strategyOrders = new Map(cp.strategy_orders.map((o) => [o.order_id, o]));
+    this.strategyInventoryCost = cp.strategy_inventory_cost;
+    this.simInventory = new Map(Object.entries(cp.sim_inventory));
+    this.simOrderSeq = cp.sim_order_seq;
+    this.stopOuts = new Map(Object.entries(cp.stop_outs));
+    this.rejectedTokens = new Set(cp.rejected_tokens);
+    this.consecutiveRejections = cp.consecutive_rejections;
+    this.liquidity = cp.liquidity;
+    for (const t of cp.positions) if (!t.sold) this.placementMarkers.add(`${t.market_timestamp}_${t.token_type}`);
+    const open = cp.positions.filter((t) => !t.sold).length;
+    log(`♻️ Restored ${cp.positions.length} position(s) (${open} open) and ${cp.strategy_orders.length} resting strategy order(s)\n`);
+  }
+
+  /** Working orders, open positions, session totals and balance; a copy, so callers can keep or mutate it */
+  async state(): Promise<TraderState> {
+    const trades = [...this.pendingTrades.values()];
+    const orders: OrderState[] = trades
+      .filter((t) => !t.filled && !t.sold)
+      .map((t) => ({
+        order_id: t.order_id,
+        position_id: t.position_id,
+        period_timestamp: t.market_timestamp,
+        token_type: t.token_type,
+        token_id: t.token_id,
+        side: "BUY" as const,
+        price: t.purchase_price,
+        units: t.units,
+        reason: t.reason,
+      }));
+    for (const t of trades) {
+      for (const a of t.pending_adds ?? []) {
+        orders.push({
+          order_id: a.order_id,
+          position_id: t.position_id,
+          period_timestamp: t.market_timestamp,
+          token_type: t.token_type,
+          token_id: t.token_id,
+          side: "BUY",
+          price: a.price,
+          units: a.units,
+          reason: t.reason,
+        });
+      }
+    }
+    for (const o of this.strategyOrders.values()) {
+      orders.push({
+        order_id: o.order_id,
+        // A spread-capture sell belongs to its position, which manages its cancellation.
+        position_id: trades.find((t) => t.spread_capture?.order_id === o.order_id)?.position_id ?? null,
+        period_timestamp: o.period_timestamp,
+        token_type: o.token_type,
+        token_id: o.token_id,
+        side: o.side,
+        price: o.price,
+        units: o.units,
+        reason: o.reason ?? null,
+      });
+    }
+    const snapshot = this.lastSnapshot;
+    const positions: PositionState[] = trades
+      .filter((t) => t.filled && !t.sold)
+      .map((t) => {
+        const mark = snapshot?.period_timestamp === t.market_timestamp ? (tokenPriceForType(snapshot, t.token_type)?.bid ?? null) : null;
+        return {
+          position_id: t.position_id,
+          period_timestamp: t.market_timestamp,
+          token_type: t.token_type,
+          token_id: t.token_id,
+          units: t.units,
+          remaining_units: t.remaining_units,
+          avg_price: t.purchase_price,
+          invested: t.investment_amount,
+          cost: t.remaining_units * t.purchase_price,
+          mark,
+          value: mark != null ? t.remaining_units * mark : null,
+          realized_pnl: t.sell_legs.reduce((sum, l) => sum + l.pnl, 0),
+          reason: t.reason,
+          legs: t.sell_legs.map((l) => ({ ...l })),
+          disputed: t.dispute != null && t.dispute.resolved_ms == null,
+        };
+      });
+    return {
+      ts_ms: clock().now(),
+      simulation: this.simulation,
+      orders,
+      positions,
+      totals: this.sessionTotals(),
+      portfolio: this.portfolio(),
+      balance: await this.collateralBalance(),
+    };
+  }
+
+  /**
+   * Append open positions marked at the snapshot's best bid to `position_snapshots.jsonl`, with session
+   * realized PnL, so exposure and equity can be reconstructed between fills. Live, `capital` adds the
+   * balance, net deposits/withdrawals and account equity (positions of earlier periods at cost).
+   */
+  recordPositionSnapshot(snapshot: MarketSnapshot, capital: CapitalMark | null = null): void {
+    const positions = [...this.pendingTrades.values()]
+      .filter((t) => t.filled && !t.sold)
+      .map((t) => {
+        const mark = tokenPriceForType(snapshot, t.token_type)?.bid ?? null;
+        return {
+          position_id: t.position_id,
+          period_timestamp: t.market_timestamp,
+          token_type: t.token_type,
+          token_id: t.token_id,
+          units: t.remaining_units,
+          avg_price: t.purchase_price,
+          cost: t.remaining_units * t.purchase_price,
+          mark,
+          value: mark != null ? t.remaining_units * mark : null,
+          unrealized_pnl: mark != null && t.market_timestamp === snapshot.period_timestamp ? (mark - t.purchase_price) * t.remaining_units : null,
+          realized_pnl: t.sell_legs.reduce((sum, l) => sum + l.pnl, 0),
+        };
+      });
+    const realized = this.sessionTotals().realized_pnl;
+    const unrealized = positions.reduce((sum, p) => sum + (p.unrealized_pnl ?? 0), 0);
+    this.storage?.appendJsonl("position_snapshots.jsonl", {
+      ts: nowSeconds(),
+      period_timestamp: snapshot.period_timestamp,
+      time_remaining_seconds: snapshot.time_remaining_seconds,
+      positions,
+      exposure: positions.reduce((sum, p) => sum + p.cost, 0),
+      value: positions.reduce((sum, p) => sum + (p.value ?? p.cost), 0),
+      realized_pnl: realized,
+      unrealized_pnl: unrealized,
+      equity_pnl: realized + unrealized,
+      ...(capital && {
+        ...capital,
+        equity:
+          capital.balance +
+          capital.owed_winnings +
+          positions.reduce((sum, p) => sum + p.units * (p.unrealized_pnl != null && p.mark != null ? p.mark : p.avg_price), 0),
+      }),
+    });
+  }
+
+  /** USDC balance of the trading account; null in simulation or when the CLOB call fails */
+  async collateralBalance(): Promise<number | null> {
+    if (this.simulation) return null;
+    try {
+      return (await getCollateralBalance(await this.getClient())).balance;
+    } catch {
+      return null;
+    }
+  }
+
+  /** Load placement markers written by earlier runs for periods from `sincePeriod` on (restart guard) */
+  loadPlacementMarkers(sincePeriod: number): number {
+    if (!this.storage) return 0;
+    const rows = this.storage.readJsonl<{ period_timestamp: number; token_type: TokenType }>("placements.jsonl");
+    let loaded = 0;
+    for (const row of rows) {
+      if (row.period_timestamp < sincePeriod) continue;
+      this.placementMarkers.add(`${row.period_timestamp}_${row.token_type}`);
+      loaded++;
+    }
+    return loaded;
+  }
+
+  /**
+   * Drop period-start opportunities already placed before a restart: persisted placement markers, plus
+   * (live) any open CLOB order resting on the same token.
+   */
+  async withoutPriorPlacements(opportunities: BuyOpportunity[]): Promise<BuyOpportunity[]> {
+    let openTokenIds = new Set<string>();
+    if (!this.simulation) {
+      try {
+        const client = await this.getClient();
+        const open = (await client.getOpenOrders()) as Array<{ asset_id?: string }>;
+        openTokenIds = new Set(open.map((o) => o.asset_id ?? ""));
+      } catch (e) {
+        log(`⚠️ Could not list open orders for the duplicate guard: ${String(e)}\n`);
+      }
+    }
+    return opportunities.filter((opp) => {
+      const marked = this.placementMarkers.has(`${opp.period_timestamp}_${opp.token_type}`);
+      if (marked || openTokenIds.has(opp.token_id)) {
+        log(`🔒 Skipping ${tokenTypeDisplayName(opp.token_type)}: already ${marked ? "placed this period" : "has an open order"}\n`);
+        return false;
+      }
+      return true;
+    });
+  }
+
+  setSpotFeed(feed: SpotFeed | null): void {
+    this.spotFeed = feed;
+  }
+
+  /** Persist fills and closed positions (`fills.jsonl`, `positions.jsonl`) for reports */
+  setStorage(storage: Storage | null): void {
+    this.storage = storage;
+  }
+
+  /** Runtime pause switches; entries for a paused asset are skipped, exits are not */
+  setControl(control: BotControl | null): void {
+    this.control = control;
+  }
+
+  /** Custom order size for limit buys (`BotRuntimeBuilder.sizer`) */
+  setSizer(sizer: Sizer | null): void {
+    this.sizer = sizer;
+  }
+
+  /** Alert sink for rejected orders and off-target fills (`trading.alerts`) */
+  setNotifier(notifier: Notifier | null): void {
+    this.notifier = notifier;
+  }
+
+  /** On-chain redemption queue for resolved positions (live only) */
+  setRedemptions(redemptions: RedemptionScheduler | null): void {
+    this.redemptions = redemptions;
+  }
+
+  /** Whether a stopped-out token may be bought again this period under its asset's re-entry rule */
+  canReenter(periodTimestamp: number, tokenType: TokenType, snapshot: MarketSnapshot): boolean {
+    const record = this.stopOuts.get(`${periodTimestamp}_${tokenType}`);
+    if (!record || record.reentries >= record.stops) return false;
+    if (this.hasActivePosition(periodTimestamp, tokenType)) return false;
+    const rule = this.config.reentry_after_stop?.[assetForTokenType(tokenType)];
+    if (!rule || rule.mode === "never") return false;
+    if (rule.max_reentries != null && record.reentries >= rule.max_reentries) return false;
+    if (rule.mode === "recross") return this.isBackAcrossOpen(periodTimestamp, tokenType, snapshot);
+    return true;
+  }
+
+  /** Underlying back on this token's side of the period open; falls back to token mid >= 0.50 without a spot feed */
+  private isBackAcrossOpen(periodTimestamp: number, tokenType: TokenType, snapshot: MarketSnapshot): boolean {
+    const asset = assetForTokenType(tokenType);
+    const spot = this.spotFeed?.price(asset) ?? null;
+    const open = this.spotFeed?.periodOpen(asset, periodTimestamp) ?? null;
+    if (spot != null && open != null) return tokenType.endsWith("Up") ? spot >= open : spot <= open;
+    const price = tokenPriceForType(snapshot, tokenType);
+    return price?.bid != null && price.ask != null && (price.bid + price.ask) / 2 >= 0.5;
+  }
+
+  /** Count a re-entry against the stop-out that allowed it */
+  noteReentry(periodTimestamp: number, tokenType: TokenType): void {
+    const record = this.stopOuts.get(`${periodTimestamp}_${tokenType}`);
+    if (record) record.reentries++;
+  }
+
   /** Execute limit buy: place order on CLOB or simulate */
   async executeLimitBuy(
     opportunity: BuyOpportunity,
@@ -45,8 +999,54 @@ export class Trader {
     sharesOverride: number | null
   ): Promise<void> {
     const fixedAmount = this.config.fixed_trade_amount;
-    const units = sharesOverride ?? fixedAmount / opportunity.bid_price;
+    const defaultUnits = sharesOverride ?? fixedAmount / opportunity.bid_price;
+    const baseUnits = this.sizer ? this.sizer.units(opportunity, limitPrice, defaultUnits) : defaultUnits;
+    if (!(baseUnits > 0)) {
+      log(`📏 Sizer returned ${baseUnits} for ${tokenTypeDisplayName(opportunity.token_type)} - skipping\n`);
+      return;
+    }
+    const jitter = this.config.order_jitter;
+    const sizePct = jitter?.size_pct ?? 0;
+    // Jittered sizes are rounded to the CLOB's 0.01 share step so what is recorded is exactly what is sent.
+    const units = sizePct > 0 ? Math.max(0.01, Math.round(baseUnits * (1 + ((this.random() * 2 - 1) * sizePct) / 100) * 100) / 100) : baseUnits;
+    const delayMs = jitter?.max_delay_ms ? Math.floor(this.random() * jitter.max_delay_ms) : 0;
+    // Before the checks below: an asset paused or an exchange halted during the delay must stop the order.
+    if (delayMs > 0) await clock().sleep(delayMs);
     const investmentAmount = units * opportunity.bid_price;
+    // The jitter delay is deliberate, not decision latency.
+    const decisionMs = (opportunity.decision_ms ?? Date.now()) + delayMs;
+    const recordJitter = (orderId: string) => {
+      if (sizePct > 0 || delayMs > 0) {
+        this.storage?.appendJsonl("jitter.jsonl", {
+          ts: nowSeconds(),
+          order_id: orderId,
+          period_timestamp: opportunity.period_timestamp,
+          token_type: opportunity.token_type,
+          base_units: baseUnits,
+          units,
+          delay_ms: delayMs,
+        });
+      }
+    };
+    const asset = assetForTokenType(opportunity.token_type);
+    if (this.control?.isPaused(asset)) {
+      log(`⏸️ ${asset} paused - skipping ${tokenTypeDisplayName(opportunity.token_type)} buy\n`);
+      return;
+    }
+    // The guard already logged the halt; one line per skipped order would be the noise it exists to avoid.
+    if (this.halt?.isHalted() || this.failover?.isStandby()) return;
+    if (this.inFlatPeriod(opportunity)) return;
+    if (this.rejectedTokens.has(`${opportunity.period_timestamp}_${opportunity.token_id}`)) {
+      log(`⛔ Skipping ${tokenTypeDisplayName(opportunity.token_type)} buy: rejected earlier this period for a reason a retry cannot fix\n`);
+      return;
+    }
+    if (await this.exceedsMaxOpenMarkets(opportunity)) return;
+    const holding = { period_timestamp: opportunity.period_timestamp, token_type: opportunity.token_type, units, cost: units * limitPrice };
+    if (this.exceedsMaxLoss(holding)) return;
+    if (this.riskBudget && !this.riskBudget.tryReserve(units * limitPrice)) {
+      log(`🛑 Skipping ${tokenTypeDisplayName(opportunity.token_type)} buy: shared risk budget exhausted\n`);
+      return;
+    }
 
     log(
       `\n═══════════════════════════════════════════════════════════\n📋 PLACING LIMIT BUY ORDER\n═══════════════════════════════════════════════════════════\n` +
@@ -54,57 +1054,975 @@ export class Trader {
         `   Token ID: ${opportunity.token_id}\n` +
         `   Limit Price: $${limitPrice.toFixed(2)}\n` +
         `   Size: ${units.toFixed(2)} shares\n` +
-        `   Investment: $${investmentAmount.toFixed(2)}\n`
+        `   Investment: ${formatUsd(investmentAmount)}\n`
     );
 
     if (this.simulation) {
-      log("🎮 SIMULATION MODE - Limit order NOT placed\n");
-      const key = `${opportunity.period_timestamp}_${opportunity.token_id}_limit`;
-      this.pendingTrades.set(key, {
-        token_id: opportunity.token_id,
-        condition_id: opportunity.condition_id,
+      const orderId = `sim-${++this.simOrderSeq}`;
+      log(`🎮 SIMULATION MODE - Limit order NOT placed (simulated as ${orderId})\n`);
+      this.recordBuy(opportunity, units, limitPrice, orderId);
+      recordJitter(orderId);
+      return;
+    }
+
+    const client = await this.getClient();
+    const size = Math.round(units * 100) / 100;
+    const price = Math.round(limitPrice * 100) / 100;
+    const sentMs = Date.now();
+    let result: { orderID: string; status: string };
+    try {
+      result = await placeLimitOrder(client, {
+        tokenId: opportunity.token_id,
+        side: "BUY",
+        price,
+        size,
+        tickSize: "0.01",
+        negRisk: false,
+      });
+      if (!result.orderID) throw new Error(`order rejected (status ${result.status})`);
+    } catch (e) {
+      this.riskBudget?.release(units * limitPrice);
+      if (this.halt?.noteError(e)) return;
+      const type = this.noteRejection({ ...opportunity, side: "BUY", price, units: size }, e);
+      this.emit(
+        {
+          market_timestamp: opportunity.period_timestamp,
+          token_type: opportunity.token_type,
+          order_id: null,
+          reason: opportunity.reason,
+          note: opportunity.note,
+        },
+        { kind: "rejected", units: size, price, detail: `${type}: ${String(e)}` }
+      );
+      throw e;
+    }
+    this.consecutiveRejections = 0;
+    const ackLatency = this.latency.recordPlacement(result.orderID, decisionMs, sentMs, Date.now());
+    log(`✅ LIMIT BUY PLACED - Order ID: ${result.orderID} Status: ${result.status} (${ackLatency}ms decision-to-ack)\n`);
+    this.recordBuy(opportunity, size, price, result.orderID);
+    recordJitter(result.orderID);
+  }
+
+  /**
+   * Classify a CLOB rejection and append it to `rejections.jsonl`. A rejection no retry can fix blocks the
+   * token for the rest of the period; repeated rejections alert.
+   */
+  private noteRejection(
+    order: { period_timestamp: number; token_type: TokenType; token_id: string; side: "BUY" | "SELL"; price: number; units: number; reason?: string },
+    error: unknown
+  ): RejectionType {
+    const type = classifyRejection(error);
+    this.storage?.appendJsonl("rejections.jsonl", {
+      ts: nowSeconds(),
+      period_timestamp: order.period_timestamp,
+      token_type: order.token_type,
+      token_id: order.token_id,
+      side: order.side,
+      price: order.price,
+      units: order.units,
+      reason: order.reason ?? null,
+      type,
+      message: String(error),
+    });
+    if (PERMANENT_REJECTIONS.has(type)) {
+      const key = `${order.period_timestamp}_${order.token_id}`;
+      if (!this.rejectedTokens.has(key)) {
+        this.rejectedTokens.add(key);
+        log(`⛔ ${tokenTypeDisplayName(order.token_type)} rejected (${type}) - no more orders in this token this period\n`);
+      }
+    }
+    if (type === "unauthorized") this.notifier?.alert("auth_failure", "auth_failure", { error: String(error) });
+    this.consecutiveRejections++;
+    const limit = this.config.alerts?.max_consecutive_rejections ?? 3;
+    if (this.consecutiveRejections >= limit) {
+      this.notifier?.alert("order_rejections", "order_rejections", {
+        count: this.consecutiveRejections,
+        token: tokenTypeDisplayName(order.token_type),
+        type,
+        error: String(error),
+      });
+    }
+    return type;
+  }
+
+  /**
+   * Per-snapshot position management: detect fills, then apply stop-loss (sell or hedge) or take-profits.
+   * Inside the closing window, unfilled orders are cancelled and coin-flip positions exited.
+   */
+  async checkPositions(snapshot: MarketSnapshot): Promise<void> {
+    const exitWindow = this.config.early_exit_seconds;
+    const inExitWindow = exitWindow != null && snapshot.time_remaining_seconds <= exitWindow;
+    this.lastSnapshot = snapshot;
+    await this.checkResolutions(snapshot.period_timestamp);
+    for (const trade of [...this.pendingTrades.values()]) {
+      if (trade.market_timestamp !== snapshot.period_timestamp) continue;
+      if (trade.pending_adds?.length) await this.checkPendingAdds(trade, snapshot, inExitWindow);
+      if (trade.sold) continue;
+      if (!trade.filled) {
+        const fillPrice = await this.fillPrice({ order_id: trade.order_id, token_type: trade.token_type, price: trade.purchase_price, units: trade.units }, snapshot);
+        trade.filled = fillPrice != null;
+        if (trade.filled && trade.order_id) this.latency.recordFill(trade.order_id);
+        if (trade.filled) {
+          if (trade.order_id) trade.order_ids = [trade.order_id];
+          trade.fill_elapsed_seconds = PERIOD_DURATION - snapshot.time_remaining_seconds;
+          const liquidity = trade.order_id
+            ? await this.recordLiveFill({
+                order_id: trade.order_id,
+                token_id: trade.token_id,
+                token_type: trade.token_type,
+                period_timestamp: trade.market_timestamp,
+                side: "BUY",
+                reason: trade.reason,
+                units: trade.units,
+                target_price: trade.target_price,
+              })
+            : null;
+          if (logs("fills")) {
+            log(
+              `✅ FILLED ${this.label(trade)}: ${trade.units.toFixed(2)} shares @ $${trade.purchase_price.toFixed(2)} = ${formatUsd(trade.investment_amount)} ` +
+                `(${trade.fill_elapsed_seconds}s into period${liquidity ? `, ${liquidity}` : ""})\n`
+            );
+          }
+          this.storage?.appendJsonl("fills.jsonl", {
+            ts: nowSeconds(),
+            position_id: trade.position_id,
+            period_timestamp: trade.market_timestamp,
+            token_type: trade.token_type,
+            token_id: trade.token_id,
+            units: trade.units,
+            price: trade.purchase_price,
+            elapsed_seconds: trade.fill_elapsed_seconds,
+            liquidity,
+            reason: trade.reason,
+          });
+          this.checkFillDeviation(trade, fillPrice as number);
+          this.emit(trade, { kind: "filled", units: trade.units, price: fillPrice as number, liquidity });
+        } else if (inExitWindow) {
+          await this.cancelUnfilled(trade, snapshot.time_remaining_seconds);
+        } else if (logs("fill_checks")) {
+          const ask = tokenPriceForType(snapshot, trade.token_type)?.ask;
+          log(
+            `🔎 ${this.label(trade)} not filled: limit $${trade.purchase_price.toFixed(2)}, ` +
+              `ask ${ask != null ? `$${ask.toFixed(2)}` : "N/A"}, ${snapshot.time_remaining_seconds}s left\n`
+          );
+        }
+        continue;
+      }
+      const bid = tokenPriceForType(snapshot, trade.token_type)?.bid ?? null;
+      if (bid == null) continue;
+      trade.last_mark = { bid, time_remaining_seconds: snapshot.time_remaining_seconds };
+      const dca = this.config.dca?.enabled ? this.config.dca : null;
+      const band = this.config.early_exit_band;
+      const ocoCfg = this.config.oco;
+      const capture = this.config.spread_capture?.enabled ? this.config.spread_capture : null;
+      try {
+        if (capture && !trade.spread_capture_armed && !inExitWindow) await this.armSpreadCapture(trade, snapshot, capture);
+        if (trade.spread_capture) await this.checkSpreadCapture(trade, trade.spread_capture, snapshot, inExitWindow);
+        if (trade.spread_capture) {
+          // The shares under the resting sell are hedged by the other leg's; only those beyond it keep a stop.
+          const uncovered = trade.remaining_units - trade.spread_capture.units;
+          if (uncovered > 1e-9) await this.stopUncovered(trade, bid, uncovered);
+          continue;
+        }
+        if (dca && !inExitWindow) await this.checkDca(trade, snapshot, dca);
+        if (trade.oco && Math.abs(trade.oco.take_profit.units - trade.remaining_units) > 1e-9) {
+          // A DCA add changed size and entry: re-arm around the blended position.
+          await this.cancelOco(trade);
+          trade.oco_armed = false;
+        }
+        if (ocoCfg && !trade.oco_armed && !inExitWindow) await this.armOco(trade, ocoCfg);
+        // With DCA the stop is the bid at which the blended position loses `max_loss`.
+        const dcaMaxLoss = dca?.max_loss ?? null;
+        const stopLoss = dca ? (dcaMaxLoss != null ? trade.purchase_price - dcaMaxLoss / trade.remaining_units : null) : this.config.stop_loss_price;
+        const stopKey = `${trade.market_timestamp}_${trade.token_type}`;
+        if (inExitWindow && band && bid >= band[0] && bid <= band[1]) {
+          await this.cancelOco(trade);
+          log(
+            `\n⏳ EARLY EXIT ${this.label(trade)}: bid $${bid.toFixed(2)} inside ` +
+              `$${band[0].toFixed(2)}-$${band[1].toFixed(2)} with ${snapshot.time_remaining_seconds}s left\n`
+          );
+          const exit = await this.placeExit(trade, trade.token_id, "SELL", bid, trade.remaining_units);
+          if (exit.units > 0) {
+            const pnl = (exit.price - trade.purchase_price) * exit.units;
+            this.recordLeg(trade, { reason: "early_exit", units: exit.units, price: exit.price, pnl, order_id: exit.order_id });
+          }
+        } else if (trade.oco) {
+          await this.checkOco(trade, trade.oco, snapshot, bid);
+        } else if (stopLoss != null && this.stops.check(stopKey, bid, stopLoss)) {
+          const trigger =
+            dcaMaxLoss != null
+              ? `loss ${formatUsd((trade.purchase_price - bid) * trade.remaining_units)} >= ${formatUsd(dcaMaxLoss)} ` +
+                `on ${trade.dca_adds + 1} entries, avg $${trade.purchase_price.toFixed(4)}`
+              : `bid $${bid.toFixed(2)} <= $${stopLoss.toFixed(2)}`;
+          await this.exitLosingPosition(trade, snapshot, bid, trigger);
+        } else {
+          await this.checkTakeProfits(trade, bid);
+        }
+      } catch (e) {
+        log(`Error exiting ${this.label(trade)} position: ${String(e)}\n`);
+      }
+    }
+  }
+
+  /** Scale out: sell the configured fraction of the original size at each take-profit level reached */
+  private async checkTakeProfits(trade: PendingTrade, bid: number): Promise<void> {
+    const levels = this.config.take_profit_levels ?? [];
+    while (trade.take_profits_hit < levels.length) {
+      const level = levels[trade.take_profits_hit];
+      if (bid < trade.purchase_price * (1 + level.gain_pct / 100)) return;
+      const units = Math.min(trade.units * level.fraction, trade.remaining_units);
+      trade.take_profits_hit++;
+      if (units <= 0) continue;
+      log(
+        `\n💰 TAKE-PROFIT ${trade.take_profits_hit}/${levels.length} ${this.label(trade)}: ` +
+          `+${level.gain_pct}% reached (bid $${bid.toFixed(2)}), selling ${units.toFixed(2)} shares\n`
+      );
+      const exit = await this.placeExit(trade, trade.token_id, "SELL", bid, units);
+      if (exit.units <= 0) {
+        // Nothing executed: the level is tried again on the next snapshot.
+        trade.take_profits_hit--;
+        return;
+      }
+      this.recordLeg(trade, {
+        reason: "take_profit",
+        units: exit.units,
+        price: exit.price,
+        pnl: (exit.price - trade.purchase_price) * exit.units,
+        order_id: exit.order_id,
+      });
+    }
+  }
+
+  /** Rest a take-profit sell for the whole position and set its stop; one attempt per position */
+  private async armOco(trade: PendingTrade, cfg: NonNullable<Config["trading"]["oco"]>): Promise<void> {
+    trade.oco_armed = true;
+    const tpPrice = Math.min(0.99, Math.round(trade.purchase_price * (1 + cfg.take_profit_pct / 100) * 100) / 100);
+    const stopPrice = trade.purchase_price * (1 - cfg.stop_pct / 100);
+    const units = trade.remaining_units;
+    let orderId: string;
+    try {
+      orderId = await this.submitLimit(trade.token_id, "SELL", tpPrice, units);
+    } catch (e) {
+      log(`Error arming OCO for ${this.label(trade)} - falling back to regular exits: ${String(e)}\n`);
+      return;
+    }
+    trade.oco = {
+      take_profit: {
+        order_id: orderId,
+        token_id: trade.token_id,
+        token_type: trade.token_type,
+        period_timestamp: trade.market_timestamp,
+        side: "SELL",
+        price: tpPrice,
+        units,
+        reason: "oco_take_profit",
+      },
+      stop_price: stopPrice,
+    };
+    log(
+      `🔗 OCO ${this.label(trade)}: take-profit ${units.toFixed(2)} @ $${tpPrice.toFixed(2)} resting, ` +
+        `stop at bid <= $${stopPrice.toFixed(2)}\n`
+    );
+    this.emit({ ...trade, order_id: orderId, reason: "oco_take_profit" }, { kind: "placed", units, price: tpPrice });
+  }
+
+  /** Whichever side of the pair executes first cancels the other */
+  private async checkOco(trade: PendingTrade, oco: OcoPair, snapshot: MarketSnapshot, bid: number): Promise<void> {
+    const tp = oco.take_profit;
+    if (await this.orderFilled(tp, snapshot)) {
+      trade.oco = null;
+      log(`\n🎯 OCO TAKE-PROFIT ${this.label(trade)} filled @ $${tp.price.toFixed(2)} - stop cancelled\n`);
+      this.recordLeg(trade, {
+        reason: "take_profit",
+        units: tp.units,
+        price: tp.price,
+        pnl: (tp.price - trade.purchase_price) * tp.units,
+        order_id: tp.order_id,
+      });
+      return;
+    }
+    if (!this.stops.check(`${trade.market_timestamp}_${trade.token_type}_oco`, bid, oco.stop_price)) return;
+    log(`\n🛑 OCO STOP ${this.label(trade)}: bid $${bid.toFixed(2)} <= $${oco.stop_price.toFixed(2)} - cancelling take-profit\n`);
+    // If the cancel fails the take-profit may have just filled; the next snapshot finds out.
+    await this.cancelOco(trade);
+    const exit = await this.placeExit(trade, trade.token_id, "SELL", bid, trade.remaining_units);
+    if (exit.units > 0) {
+      const pnl = (exit.price - trade.purchase_price) * exit.units;
+      this.recordLeg(trade, { reason: "stop_loss", units: exit.units, price: exit.price, pnl, order_id: exit.order_id });
+    }
+  }
+
+  /**
+   * Spread capture: once both sides of a market filled for less than $1 combined, rest a sell on each
+   * (matched shares) priced to sum above the combined cost plus fees and `min_profit`, split by the
+   * current mids. Both legs arm together; a pair that cannot be priced that way is managed as before.
+   */
+  private async armSpreadCapture(
+    trade: PendingTrade,
+    snapshot: MarketSnapshot,
+    cfg: NonNullable<Config["trading"]["spread_capture"]>
+  ): Promise<void> {
+    const otherType = oppositeTokenType(trade.token_type);
+    const other = [...this.pendingTrades.values()].find(
+      (t) => t.market_timestamp === trade.market_timestamp && t.token_type === otherType && t.filled && !t.sold && !t.spread_capture_armed
+    );
+    if (!other || trade.oco || other.oco || trade.sell_legs.length > 0 || other.sell_legs.length > 0) return;
+    trade.spread_capture_armed = true;
+    other.spread_capture_armed = true;
+    const cost = trade.purchase_price + other.purchase_price;
+    const target = cost * (1 + (this.config.taker_fee_rate ?? 0)) + (cfg.min_profit ?? 0.01);
+    const mid = (t: PendingTrade) => {
+      const q = tokenPriceForType(snapshot, t.token_type);
+      return q?.bid != null && q.ask != null ? (q.bid + q.ask) / 2 : t.purchase_price;
+    };
+    const ceilCent = (p: number) => Math.ceil(p * 100 - 1e-9) / 100;
+    const price = Math.min(0.99, Math.max(0.01, ceilCent((target * mid(trade)) / (mid(trade) + mid(other)))));
+    const otherPrice = Math.min(0.99, Math.max(0.01, ceilCent(target - price)));
+    if (cost >= 1 || price + otherPrice < target - 1e-9) {
+      log(`↔️ Spread capture skipped for ${this.label(trade)} + ${this.label(other)}: cost $${cost.toFixed(4)}, target $${target.toFixed(4)}\n`);
+      return;
+    }
+    const units = Math.min(trade.remaining_units, other.remaining_units);
+    const legs: Array<[PendingTrade, number]> = [
+      [trade, price],
+      [other, otherPrice],
+    ];
+    const placed: Array<[PendingTrade, StrategyOrder]> = [];
+    for (const [t, p] of legs) {
+      try {
+        const orderId = await this.submitLimit(t.token_id, "SELL", p, units);
+        placed.push([
+          t,
+          {
+            order_id: orderId,
+            token_id: t.token_id,
+            token_type: t.token_type,
+            period_timestamp: t.market_timestamp,
+            side: "SELL",
+            price: p,
+            units,
+            reason: "spread_capture",
+          },
+        ]);
+      } catch (e) {
+        log(`Error arming spread capture for ${this.label(t)} - keeping the pair: ${String(e)}\n`);
+        for (const [, o] of placed) await this.cancelResting(o.order_id).catch(() => undefined);
+        return;
+      }
+    }
+    for (const [t, o] of placed) {
+      t.spread_capture = o;
+      this.strategyOrders.set(o.order_id, o);
+      this.emit({ ...t, order_id: o.order_id, reason: "spread_capture" }, { kind: "placed", units, price: o.price });
+    }
+    log(
+      `↔️ SPREAD CAPTURE ${this.label(trade)} + ${this.label(other)}: cost $${cost.toFixed(4)}, ` +
+        `selling ${units.toFixed(2)} @ $${price.toFixed(2)} + $${otherPrice.toFixed(2)} = $${(price + otherPrice).toFixed(2)}\n`
+    );
+  }
+
+  /**
+   * Once one capture sell fills the pair is no longer hedged: the other leg's sell is cancelled and both
+   * legs go back under the regular exits. Inside the closing window both resting sells are cancelled.
+   */
+  private async checkSpreadCapture(trade: PendingTrade, order: StrategyOrder, snapshot: MarketSnapshot, inExitWindow: boolean): Promise<void> {
+    const otherType = oppositeTokenType(trade.token_type);
+    const partner = [...this.pendingTrades.values()].find(
+      (t) => t.market_timestamp === trade.market_timestamp && t.token_type === otherType && t.spread_capture != null
+    );
+    if (!(await this.orderFilled(order, snapshot))) {
+      if (!inExitWindow) return;
+      log(`↔️ Spread capture ${this.label(trade)}: closing window reached - cancelling the resting sells\n`);
+      await this.cancelSpreadCapture(trade);
+      if (partner) await this.cancelSpreadCapture(partner);
+      return;
+    }
+    trade.spread_capture = null;
+    this.strategyOrders.delete(order.order_id);
+    log(`\n↔️ SPREAD CAPTURE ${this.label(trade)} sell filled @ $${order.price.toFixed(2)}\n`);
+    const feeRate = this.config.taker_fee_rate ?? 0;
+    this.recordLeg(trade, {
+      reason: "spread_capture",
+      units: order.units,
+      price: order.price,
+      pnl: (order.price - trade.purchase_price) * order.units - order.price * order.units * feeRate,
+      order_id: order.order_id,
+    });
+    // A partner sell that filled too is booked on its own turn.
+    if (partner?.spread_capture && !(await this.orderFilled(partner.spread_capture, snapshot))) {
+      log(`↔️ Spread capture ${this.label(partner)} no longer hedged - cancelling its sell\n`);
+      await this.cancelSpreadCapture(partner);
+    }
+  }
+
+  /** Cancel a resting spread-capture sell; the leg goes back under the regular exits */
+  private async cancelSpreadCapture(trade: PendingTrade): Promise<void> {
+    const order = trade.spread_capture;
+    if (!order) return;
+    await this.cancelResting(order.order_id);
+    trade.spread_capture = null;
+    this.strategyOrders.delete(order.order_id);
+    this.emit({ ...trade, order_id: order.order_id, reason: "spread_capture" }, { kind: "cancelled", units: order.units, price: order.price });
+  }
+
+  /** Stop-loss on the shares of a spread-capture leg beyond its resting sell, which the pair does not hedge */
+  private async stopUncovered(trade: PendingTrade, bid: number, units: number): Promise<void> {
+    const stopLoss = this.config.stop_loss_price;
+    if (stopLoss == null || !this.stops.check(`${trade.market_timestamp}_${trade.token_type}`, bid, stopLoss)) return;
+    log(
+      `\n🛑 STOP-LOSS ${this.label(trade)} (bid $${bid.toFixed(2)} <= $${stopLoss.toFixed(2)}): ` +
+        `selling the ${units.toFixed(2)} share(s) the spread capture does not cover\n`
+    );
+    const exit = await this.placeExit(trade, trade.token_id, "SELL", bid, units);
+    if (exit.units <= 0) return;
+    const feeRate = this.config.taker_fee_rate ?? 0;
+    const pnl = (exit.price - trade.purchase_price) * exit.units - exit.price * exit.units * feeRate;
+    this.recordLeg(trade, { reason: "stop_loss", units: exit.units, price: exit.price, pnl, order_id: exit.order_id });
+  }
+
+  private async cancelOco(trade: PendingTrade): Promise<void> {
+    if (!trade.oco) return;
+    const tp = trade.oco.take_profit;
+    await this.cancelResting(tp.order_id);
+    trade.oco = null;
+    this.emit({ ...trade, order_id: tp.order_id, reason: "oco_take_profit" }, { kind: "cancelled", units: tp.units, price: tp.price });
+  }
+
+  /**
+   * DCA: buy again at the ask once it is another `step` below the first fill, while the position is
+   * unsold and within `max_adds` / `max_notional`. The add aggregates into the position at average cost.
+   */
+  private async checkDca(trade: PendingTrade, snapshot: MarketSnapshot, dca: NonNullable<Config["trading"]["dca"]>): Promise<void> {
+    if (trade.dca_adds >= dca.max_adds || trade.sell_legs.length > 0) return;
+    if (snapshot.time_remaining_seconds <= (this.config.min_time_remaining_seconds ?? 0)) return;
+    const quote = tokenPriceForType(snapshot, trade.token_type);
+    const ask = quote?.ask ?? null;
+    const trigger = trade.entry_price - (trade.dca_adds + 1) * dca.step;
+    if (ask == null || ask > trigger) return;
+    const shares = dca.shares ?? trade.units / (trade.dca_adds + 1);
+    if (trade.investment_amount + shares * ask > dca.max_notional + 1e-9) return;
+    log(
+      `\n📉 DCA ${trade.dca_adds + 1}/${dca.max_adds} ${this.label(trade)}: ask $${ask.toFixed(2)} <= ` +
+        `$${trigger.toFixed(2)} (first fill $${trade.entry_price.toFixed(2)}), adding ${shares.toFixed(2)} shares\n`
+    );
+    const unitsBefore = trade.units;
+    // Counted before placing: a skipped or failed add is not retried at the same level.
+    trade.dca_adds++;
+    await this.executeLimitBuy(
+      {
+        condition_id: trade.condition_id,
+        token_id: trade.token_id,
+        token_type: trade.token_type,
+        bid_price: ask,
+        period_timestamp: trade.market_timestamp,
+        time_remaining_seconds: snapshot.time_remaining_seconds,
+        time_elapsed_seconds: PERIOD_DURATION - snapshot.time_remaining_seconds,
+        use_market_order: false,
+        reason: "dca_add",
+        note: `add ${trade.dca_adds}/${dca.max_adds} below first fill $${trade.entry_price.toFixed(2)}`,
+      },
+      ask,
+      shares
+    );
+    if (trade.units > unitsBefore) {
+      log(`   Blended entry $${trade.purchase_price.toFixed(4)} on ${trade.units.toFixed(2)} shares (cost ${formatUsd(trade.investment_amount)})\n`);
+    }
+  }
+
+  /** Cancel a resting buy that has not filled before the closing window */
+  private async cancelUnfilled(trade: PendingTrade, secondsLeft: number): Promise<void> {
+    log(`\n🚫 Cancelling unfilled ${this.label(trade)} limit buy (${secondsLeft}s left)\n`);
+    if (!this.simulation && trade.order_id) {
+      try {
+        const client = await this.getClient();
+        await client.cancelOrder({ orderID: trade.order_id });
+      } catch (e) {
+        log(`Error cancelling order ${trade.order_id}: ${String(e)}\n`);
+        return;
+      }
+    }
+    trade.cancelled = true;
+    trade.sold = true;
+    this.emit(trade, { kind: "cancelled", units: trade.units, price: trade.purchase_price });
+  }
+
+  /**
+   * Fill-check the buys resting on a position: a filled add joins it at average cost (or, once the position
+   * has been exited, becomes a position of its own); unfilled adds are cancelled in the closing window or
+   * when the position is gone.
+   */
+  private async checkPendingAdds(trade: PendingTrade, snapshot: MarketSnapshot, inExitWindow: boolean): Promise<void> {
+    const resting: PendingAdd[] = [];
+    for (const add of trade.pending_adds ?? []) {
+      const fillPrice = await this.fillPrice({ order_id: add.order_id, token_type: trade.token_type, price: add.price, units: add.units }, snapshot);
+      if (fillPrice == null) {
+        if (!(inExitWindow || trade.sold) || !(await this.cancelAdd(trade, add))) resting.push(add);
+        continue;
+      }
+      if (add.order_id) this.latency.recordFill(add.order_id);
+      const liquidity = add.order_id
+        ? await this.recordLiveFill({
+            order_id: add.order_id,
+            token_id: trade.token_id,
+            token_type: trade.token_type,
+            period_timestamp: trade.market_timestamp,
+            side: "BUY",
+            reason: trade.reason,
+            units: add.units,
+            target_price: add.target_price,
+          })
+        : null;
+      let target = trade;
+      if (trade.sold) {
+        const { token_id, condition_id, token_type, market_timestamp, reason, note } = trade;
+        target = this.openPosition({ token_id, condition_id, token_type, market_timestamp, reason, note }, add);
+        target.filled = true;
+        target.fill_elapsed_seconds = PERIOD_DURATION - snapshot.time_remaining_seconds;
+      } else {
+        trade.units += add.units;
+        trade.remaining_units += add.units;
+        trade.investment_amount += add.units * add.price;
+        trade.purchase_price = trade.investment_amount / trade.units;
+      }
+      if (add.order_id) (target.order_ids ??= []).push(add.order_id);
+      if (logs("fills")) {
+        log(
+          `✅ FILLED add to ${this.label(target)}: ${add.units.toFixed(2)} shares @ $${add.price.toFixed(2)}, position ` +
+            `${target.units.toFixed(2)} shares @ avg $${target.purchase_price.toFixed(4)} (cost ${formatUsd(target.investment_amount)})` +
+            `${liquidity ? `, ${liquidity}` : ""}\n`
+        );
+      }
+      this.storage?.appendJsonl("fills.jsonl", {
+        ts: nowSeconds(),
+        position_id: target.position_id,
+        period_timestamp: trade.market_timestamp,
+        token_type: trade.token_type,
+        token_id: trade.token_id,
+        units: add.units,
+        price: add.price,
+        elapsed_seconds: PERIOD_DURATION - snapshot.time_remaining_seconds,
+        liquidity,
+        reason: trade.reason,
+      });
+      this.checkFillDeviation({ token_type: trade.token_type, target_price: add.target_price }, fillPrice);
+      this.emit({ ...target, order_id: add.order_id }, { kind: "filled", units: add.units, price: fillPrice, liquidity });
+    }
+    trade.pending_adds = resting;
+  }
+
+  /** Cancel a resting add; false when the cancel failed and the order may still fill */
+  private async cancelAdd(trade: PendingTrade, add: PendingAdd): Promise<boolean> {
+    log(`\n🚫 Cancelling unfilled add to ${this.label(trade)} (order ${add.order_id})\n`);
+    if (!this.simulation && add.order_id) {
+      try {
+        const client = await this.getClient();
+        await client.cancelOrder({ orderID: add.order_id });
+      } catch (e) {
+        log(`Error cancelling order ${add.order_id}: ${String(e)}\n`);
+        return false;
+      }
+    }
+    this.emit({ ...trade, order_id: add.order_id }, { kind: "cancelled", units: add.units, price: add.price });
+    return true;
+  }
+
+  /**
+   * Settle positions from finished periods every `market_closure_check_interval_seconds`: unfilled
+   * orders expire, filled units are closed at $1/$0 once the CLOB reports a winner.
+   */
+  private async checkResolutions(currentPeriod: number): Promise<void> {
+    const now = clock().now();
+    if (now - this.lastResolutionCheckMs < this.config.market_closure_check_interval_seconds * 1000) return;
+    this.lastResolutionCheckMs = now;
+    for (const trade of this.pendingTrades.values()) {
+      if (trade.market_timestamp >= currentPeriod) continue;
+      for (const add of trade.pending_adds ?? []) {
+        this.emit({ ...trade, order_id: add.order_id }, { kind: "cancelled", units: add.units, price: add.price, detail: "expired" });
+      }
+      trade.pending_adds = [];
+      if (trade.sold) continue;
+      if (!trade.filled) {
+        trade.cancelled = true;
+        trade.sold = true;
+        this.emit(trade, { kind: "cancelled", units: trade.units, price: trade.purchase_price, detail: "expired" });
+        continue;
+      }
+      let winner: boolean | null = null;
+      try {
+        const market = await this.api.getMarketByConditionId(trade.condition_id);
+        const token = market.tokens.find((t) => (t.token_id ?? t.tokenId) === trade.token_id);
+        winner = token?.winner ?? null;
+        if (winner === false && !market.tokens.some((t) => t.winner)) winner = null;
+      } catch {
+        continue;
+      }
+      if (this.config.uma_dispute_check && (await this.heldForDispute(trade))) continue;
+      if (winner == null) continue;
+      // Resolved: a resting OCO take-profit or spread-capture sell can no longer fill.
+      trade.oco = null;
+      if (trade.spread_capture) this.strategyOrders.delete(trade.spread_capture.order_id);
+      trade.spread_capture = null;
+      const price = winner ? 1 : 0;
+      if (this.simulation && winner && this.adverseHaircut > 0) {
+        // Live, part of a winning limit buy would not have filled; book that part as never bought.
+        const dropped = trade.remaining_units * this.adverseHaircut;
+        // Nor was their cost spent: the session's invested total and ROI count only what was bought.
+        trade.investment_amount = Math.max(0, trade.investment_amount - dropped * trade.purchase_price);
+        this.recordLeg(trade, { reason: "adverse_selection", units: dropped, price: trade.purchase_price, pnl: 0, order_id: null });
+      }
+      const units = trade.remaining_units;
+      if (logs("resolutions")) log(`\n🏁 RESOLVED ${this.label(trade)}: ${winner ? "WON" : "LOST"} (period ${formatTime(trade.market_timestamp * 1000)})\n`);
+      if (trade.last_mark) {
+        // What holding earned over selling the same shares into the last bid seen.
+        this.storage?.appendJsonl("resolutions.jsonl", {
+          ts: nowSeconds(),
+          position_id: trade.position_id,
+          period_timestamp: trade.market_timestamp,
+          token_type: trade.token_type,
+          units,
+          last_bid: trade.last_mark.bid,
+          last_mark_seconds_left: trade.last_mark.time_remaining_seconds,
+          payout: price,
+          resolution_edge: (price - trade.last_mark.bid) * units,
+        });
+      }
+      this.recordLeg(trade, { reason: "resolution", units, price, pnl: (price - trade.purchase_price) * units, order_id: null });
+      if (winner && !this.simulation) this.redemptions?.enqueue(trade.condition_id);
+    }
+  }
+
+  /** Record an exit leg and close the position once nothing remains */
+  private recordLeg(trade: PendingTrade, leg: SellLeg): void {
+    trade.sell_legs.push(leg);
+    trade.remaining_units = Math.max(0, trade.remaining_units - leg.units);
+    if (trade.remaining_units <= 1e-9) trade.sold = true;
+    const realized = trade.sell_legs.reduce((sum, l) => sum + l.pnl, 0);
+    log(
+      `   ${trade.position_id} leg ${trade.sell_legs.length} (${leg.reason}${leg.order_id ? `, order ${leg.order_id}` : ""}): ` +
+        `${leg.units.toFixed(2)} @ $${leg.price.toFixed(2)} ` +
+        `PnL ${formatUsd(leg.pnl)} | Position realized ${formatUsd(realized)}, ${trade.remaining_units.toFixed(2)} shares left\n`
+    );
+    // The adverse-selection haircut takes off shares that were never bought: no cash moved, so no exit.
+    if (leg.reason !== "adverse_selection") {
+      this.emit({ ...trade, order_id: leg.order_id ?? null }, { kind: "exit", units: leg.units, price: leg.price, pnl: leg.pnl, detail: leg.reason });
+    }
+    if (trade.sold) this.emit(trade, { kind: "closed", units: trade.units, price: trade.purchase_price, pnl: realized });
+    if (trade.sold) {
+      this.stops.clear(`${trade.market_timestamp}_${trade.token_type}`);
+      this.stops.clear(`${trade.market_timestamp}_${trade.token_type}_oco`);
+      this.storage?.appendJsonl("positions.jsonl", {
+        ts: nowSeconds(),
+        position_id: trade.position_id,
+        period_timestamp: trade.market_timestamp,
+        token_type: trade.token_type,
+        token_id: trade.token_id,
+        units: trade.units,
+        purchase_price: trade.purchase_price,
+        investment_amount: trade.investment_amount,
+        proceeds: trade.sell_legs.reduce((sum, l) => sum + (l.reason === "adverse_selection" ? 0 : l.units * l.price), 0),
+        fill_elapsed_seconds: trade.fill_elapsed_seconds,
+        realized_pnl: realized,
+        legs: trade.sell_legs,
+        reason: trade.reason,
+        note: trade.note,
+        dispute: trade.dispute ?? null,
+      });
+    }
+  }
+
+  /**
+   * Whether a finished period's position must wait for a UMA dispute on its market. Alerts and records
+   * the dispute when it opens, and again when the market is no longer contested.
+   */
+  private async heldForDispute(trade: PendingTrade): Promise<boolean> {
+    let disputed: boolean;
+    let status: string | null;
+    try {
+      const uma = await this.api.getUmaResolution(trade.condition_id);
+      disputed = isUnderDispute(uma);
+      status = uma.status;
+    } catch (e) {
+      log(`Could not check UMA status of ${this.label(trade)}: ${String(e)}\n`);
+      return trade.dispute != null && trade.dispute.resolved_ms == null;
+    }
+    const open = trade.dispute != null && trade.dispute.resolved_ms == null;
+    if (disputed === open) return disputed;
+    const record: DisputeRecord = {
+      ts: nowSeconds(),
+      kind: disputed ? "opened" : "resolved",
+      position_id: trade.position_id,
+      period_timestamp: trade.market_timestamp,
+      token_type: trade.token_type,
+      condition_id: trade.condition_id,
+      status,
+    };
+    this.storage?.appendJsonl(DISPUTES_FILE, record);
+    if (disputed) {
+      trade.dispute = { since_ms: clock().now(), resolved_ms: null };
+      log(`\n⚖️ DISPUTED ${this.label(trade)}: UMA resolution of period ${formatTime(trade.market_timestamp * 1000)} contested - holding settlement\n`);
+      this.notifier?.alert(`uma_dispute_${trade.condition_id}`, "uma_dispute", {
+        token: tokenTypeDisplayName(trade.token_type),
+        period: trade.market_timestamp,
+        shares: trade.remaining_units,
+      });
+    } else if (trade.dispute) {
+      trade.dispute.resolved_ms = clock().now();
+      log(`⚖️ Dispute over for ${this.label(trade)} (UMA status ${status ?? "unknown"})\n`);
+      this.notifier?.alert(`uma_dispute_${trade.condition_id}_over`, "uma_dispute_over", {
+        token: tokenTypeDisplayName(trade.token_type),
+        status: status ?? "unknown",
+      });
+    }
+    return disputed;
+  }
+
+  /** Token plus position and order ids, so a log line can be traced to the position's orders and events */
+  private label(trade: PendingTrade): string {
+    return `${tokenTypeDisplayName(trade.token_type)} [${trade.position_id}${trade.order_id ? ` ${trade.order_id}` : ""}]`;
+  }
+
+  /** Exit order at `price`, fill-and-kill when live; returns what executed (everything, in simulation) */
+  private async placeExit(
+    trade: PendingTrade,
+    tokenId: string,
+    side: "BUY" | "SELL",
+    price: number,
+    units: number
+  ): Promise<ExitFill> {
+    if (this.simulation) {
+      const orderId = `sim-${++this.simOrderSeq}`;
+      log(`🎮 SIMULATION MODE - Exit order NOT placed (simulated as ${orderId}, position ${trade.position_id})\n`);
+      return { order_id: orderId, units, price };
+    }
+    const client = await this.getClient();
+    const size = Math.round(units * 100) / 100;
+    // Fill-and-kill: a GTC exit left resting after the bid moved would be neither filled nor tracked.
+    const result = await placeFakOrder(client, { tokenId, side, price, size });
+    let matched = 0;
+    let fillPrice = price;
+    try {
+      const order = (await client.getOrder(result.orderID)) as { size_matched?: string };
+      matched = parseFloat(order.size_matched ?? "0") || 0;
+      if (matched > 0) fillPrice = (await getFillDetails(client, result.orderID, tokenId)).price ?? price;
+    } catch (e) {
+      log(`Error reading exit order ${result.orderID}: ${String(e)}\n`);
+    }
+    log(
+      `✅ EXIT ${side} (${tokenTypeDisplayName(trade.token_type)}, position ${trade.position_id}) - ` +
+        `Order ID: ${result.orderID} Status: ${result.status}, executed ${matched.toFixed(2)}/${size.toFixed(2)} @ $${fillPrice.toFixed(4)}\n`
+    );
+    // The order was sized to two decimals; a full execution closes the unrounded remainder too.
+    return { order_id: result.orderID, units: matched >= size - 1e-9 ? units : Math.min(matched, units), price: fillPrice };
+  }
+
+  /**
+   * Stop-loss exit. Either sell into the bid, or buy the opposite token so each pair pays $1 at
+   * resolution and the loss is locked at the combined cost; whichever loses less after fees wins.
+   */
+  private async exitLosingPosition(trade: PendingTrade, snapshot: MarketSnapshot, bid: number, trigger: string): Promise<void> {
+    const feeRate = this.config.taker_fee_rate ?? 0;
+    const units = trade.remaining_units;
+    const sellLoss = (trade.purchase_price - bid) * units + bid * units * feeRate;
+    const oppositeType = oppositeTokenType(trade.token_type);
+    const opposite = tokenPriceForType(snapshot, oppositeType);
+    const oppAsk = opposite?.ask ?? null;
+    const hedgeCap = this.config.hedge_price;
+    let hedgeLoss: number | null = null;
+    if (opposite && oppAsk != null && (hedgeCap == null || oppAsk <= hedgeCap)) {
+      hedgeLoss = (trade.purchase_price + oppAsk - 1) * units + oppAsk * units * feeRate;
+    }
+    const hedge = hedgeLoss != null && hedgeLoss < sellLoss;
+    const stopKey = `${trade.market_timestamp}_${trade.token_type}`;
+    const record = this.stopOuts.get(stopKey) ?? { stops: 0, reentries: 0 };
+    record.stops++;
+    this.stopOuts.set(stopKey, record);
+
+    log(
+      `\n🛑 STOP-LOSS ${this.label(trade)} (${trigger})\n` +
+        `   Sell loss: ${formatUsd(sellLoss)} | Hedge loss: ${hedgeLoss != null ? formatUsd(hedgeLoss) : "N/A"}\n` +
+        `   Action: ${hedge ? `BUY ${tokenTypeDisplayName(oppositeType)} @ $${oppAsk?.toFixed(2)}` : `SELL @ $${bid.toFixed(2)}`}\n`
+    );
+
+    // Losses are booked on what executed; an unexecuted remainder stays open for the next snapshot.
+    if (hedge && opposite && oppAsk != null) {
+      const exit = await this.placeExit(trade, opposite.token_id, "BUY", oppAsk, units);
+      if (exit.units <= 0) return;
+      trade.hedged = true;
+      const pnl = -((trade.purchase_price + exit.price - 1) * exit.units + exit.price * exit.units * feeRate);
+      this.recordLeg(trade, { reason: "hedge", units: exit.units, price: exit.price, pnl, order_id: exit.order_id });
+    } else {
+      const exit = await this.placeExit(trade, trade.token_id, "SELL", bid, units);
+      if (exit.units <= 0) return;
+      const pnl = (exit.price - trade.purchase_price) * exit.units - exit.price * exit.units * feeRate;
+      this.recordLeg(trade, { reason: "stop_loss", units: exit.units, price: exit.price, pnl, order_id: exit.order_id });
+    }
+  }
+
+  /**
+   * Fill price once the buy has filled, else null. Simulation: a resting buy fills at its limit once the
+   * ask reaches it under the `SimFillRule`. Live: ask the CLOB.
+   */
+  private async fillPrice(
+    order: { order_id: string | null; token_type: TokenType; price: number; units: number },
+    snapshot: MarketSnapshot
+  ): Promise<number | null> {
+    if (this.simulation || !order.order_id) {
+      const price = tokenPriceForType(snapshot, order.token_type);
+      if (!this.simFill.crosses("BUY", price?.ask ?? null, order.price)) return null;
+      if (!this.config.sim_fill_requires_print) return order.price;
+      return this.simFill.crosses("BUY", price?.trade_stats?.low ?? null, order.price) ? order.price : null;
+    }
+    try {
+      const client = await this.getClient();
+      const live = (await client.getOrder(order.order_id)) as { size_matched?: string; original_size?: string; price?: string };
+      const matched = parseFloat(live.size_matched ?? "0");
+      const original = parseFloat(live.original_size ?? String(order.units));
+      if (!(matched > 0 && matched >= original)) return null;
+      const price = parseFloat(live.price ?? "");
+      return Number.isFinite(price) ? price : order.price;
+    } catch {
+      return null;
+    }
+  }
+
+  private checkFillDeviation(trade: Pick<PendingTrade, "token_type" | "target_price">, fillPrice: number): void {
+    const maxPct = this.config.alerts?.max_fill_deviation_pct ?? 5;
+    const deviationPct = (Math.abs(fillPrice - trade.target_price) / trade.target_price) * 100;
+    if (deviationPct > maxPct) {
+      this.notifier?.alert(`fill_deviation_${trade.token_type}`, "fill_deviation", {
+        token: tokenTypeDisplayName(trade.token_type),
+        fill_price: fillPrice,
+        deviation_pct: deviationPct,
+        target_price: trade.target_price,
+      });
+    }
+  }
+
+  /**
+   * Record a buy. A repeat buy of the same token in a period joins the open position at average cost once
+   * it fills; while the position's own order is still resting, the new order opens a position of its own.
+   */
+  private recordBuy(opportunity: BuyOpportunity, units: number, price: number, orderId: string | null): void {
+    const placed = (positionId: string) =>
+      this.emit(
+        {
+          market_timestamp: opportunity.period_timestamp,
+          token_type: opportunity.token_type,
+          order_id: orderId,
+          position_id: positionId,
+          reason: opportunity.reason,
+          note: opportunity.note,
+        },
+        { kind: "placed", units, price }
+      );
+    const marker = `${opportunity.period_timestamp}_${opportunity.token_type}`;
+    if (!this.placementMarkers.has(marker)) {
+      this.placementMarkers.add(marker);
+      this.storage?.appendJsonl("placements.jsonl", {
+        ts: nowSeconds(),
+        period_timestamp: opportunity.period_timestamp,
         token_type: opportunity.token_type,
-        market_timestamp: opportunity.period_timestamp,
-        sold: false,
+        token_id: opportunity.token_id,
+        order_id: orderId,
+        reason: opportunity.reason ?? "unspecified",
+        note: opportunity.note ?? null,
+        price: opportunity.bid_price,
+        spread: opportunity.spread ?? null,
+        // Scored at placement so calibration measures the model as it was used.
+        fill_probability: this.estimateFillProbability({
+          token_type: opportunity.token_type,
+          price: opportunity.bid_price,
+          spread: opportunity.spread ?? null,
+          period_timestamp: opportunity.period_timestamp,
+        }),
       });
+    }
+    const tokenKey = `${opportunity.period_timestamp}_${opportunity.token_id}`;
+    const ids = this.positionsByToken.get(tokenKey) ?? [];
+    const existing = ids.length > 0 ? this.pendingTrades.get(ids[ids.length - 1]) : undefined;
+    if (existing && existing.filled && !existing.sold) {
+      (existing.pending_adds ??= []).push({ order_id: orderId, units, price, target_price: opportunity.bid_price });
+      placed(existing.position_id);
+      log(`📦 Order ${orderId} adds ${units.toFixed(2)} shares @ $${price.toFixed(2)} to position ${existing.position_id} once it fills\n`);
       return;
     }
+    const position = this.openPosition(
+      {
+        token_id: opportunity.token_id,
+        condition_id: opportunity.condition_id,
+        token_type: opportunity.token_type,
+        market_timestamp: opportunity.period_timestamp,
+        reason: opportunity.reason ?? "unspecified",
+        note: opportunity.note ?? null,
+      },
+      { order_id: orderId, units, price, target_price: opportunity.bid_price }
+    );
+    log(`📂 Opened position ${position.position_id} for order ${orderId}\n`);
+    placed(position.position_id);
+  }
 
+  /** A new, unfilled position for one buy order */
+  private openPosition(
+    market: Pick<PendingTrade, "token_id" | "condition_id" | "token_type" | "market_timestamp" | "reason" | "note">,
+    order: PendingAdd
+  ): PendingTrade {
+    const positionId = `pos-${++this.positionSeq}`;
+    const tokenKey = `${market.market_timestamp}_${market.token_id}`;
+    this.positionsByToken.set(tokenKey, [...(this.positionsByToken.get(tokenKey) ?? []), positionId]);
+    const position: PendingTrade = {
+      ...market,
+      position_id: positionId,
+      units: order.units,
+      purchase_price: order.price,
+      investment_amount: order.units * order.price,
+      remaining_units: order.units,
+      sell_legs: [],
+      take_profits_hit: 0,
+      entry_price: order.price,
+      dca_adds: 0,
+      oco: null,
+      oco_armed: false,
+      spread_capture: null,
+      spread_capture_armed: false,
+      order_id: order.order_id,
+      order_ids: [],
+      pending_adds: [],
+      target_price: order.target_price,
+      filled: false,
+      fill_elapsed_seconds: null,
+      last_mark: null,
+      sold: false,
+      hedged: false,
+      cancelled: false,
+    };
+    this.pendingTrades.set(positionId, position);
+    return position;
+  }
+
+  /** Authenticated CLOB client, reused for all live orders and rebuilt when the CLOB API fails over */
+  private async getClient(): Promise<ClobClient> {
+    const clobUrl = this.api.getClobUrl();
+    if (this.client && this.clientUrl === clobUrl) return this.client;
     const pk = this.api.getPrivateKey();
     if (!pk) throw new Error("private_key required for live trading");
     const cfg = {
-      gamma_api_url: "https://gamma-api.polymarket.com",
-      clob_api_url: this.api.getClobUrl(),
+      gamma_api_url: this.api.getGammaUrl(),
+      clob_api_url: clobUrl,
       api_key: null,
       api_secret: null,
       api_passphrase: null,
       private_key: pk,
       proxy_wallet_address: this.api.getProxyWalletAddress(),
-      signature_type: null,
+      signature_type: this.api.getSignatureType(),
     } as Config["polymarket"];
-    const client = await createClobClient(cfg);
-    const size = Math.round(units * 100) / 100;
-    const price = Math.round(limitPrice * 100) / 100;
-    const result = await placeLimitOrder(client, {
-      tokenId: opportunity.token_id,
-      side: "BUY",
-      price,
-      size,
-      tickSize: "0.01",
-      negRisk: false,
-    });
-    log(`✅ LIMIT BUY PLACED - Order ID: ${result.orderID} Status: ${result.status}\n`);
-    const key = `${opportunity.period_timestamp}_${opportunity.token_id}_limit`;
-    this.pendingTrades.set(key, {
-      token_id: opportunity.token_id,
-      condition_id: opportunity.condition_id,
-      token_type: opportunity.token_type,
-      market_timestamp: opportunity.period_timestamp,
-      sold: false,
-    });
+    this.client = await createClobClient(cfg);
+    this.clientUrl = clobUrl;
+    return this.client;
   }
 }
 
+const PERIOD_DURATION = 900;
+
 function log(msg: string): void {
   process.stderr.write(msg);
 }
@@ -0,0 +1,71 @@
+import { nowSeconds } from "./clock.js";
+import type { TradeStats } from "./types.js";
+
+export interface TradePrint {
+  /** Transaction hash when the source provides one */
+  id?: string;
+  token_id: string;
+  side: "BUY" | "SELL";
+  price: number;
+  size: number;
+  timestamp: number;
+}
+
+/** Rolling per-token traded volume and taker buy/sell imbalance over the last `windowSeconds` */
+export class TradeStatsTracker {
+  private windowSeconds: number;
+  private prints: Map<string, TradePrint[]> = new Map();
+  private seen: Set<string> = new Set();
+
+  constructor(windowSeconds: number) {
+    this.windowSeconds = windowSeconds;
+  }
+
+  /** Add prints (duplicates from overlapping polls are ignored) */
+  ingest(prints: TradePrint[]): void {
+    for (const p of prints) {
+      const key = `${p.id ?? ""}|${p.token_id}|${p.timestamp}|${p.side}|${p.price}|${p.size}`;
+      if (this.seen.has(key)) continue;
+      this.seen.add(key);
+      const list = this.prints.get(p.token_id) ?? [];
+      list.push(p);
+      this.prints.set(p.token_id, list);
+    }
+  }
+
+  stats(tokenId: string, nowSec: number = nowSeconds()): TradeStats | null {
+    const cutoff = nowSec - this.windowSeconds;
+    const list = (this.prints.get(tokenId) ?? []).filter((p) => p.timestamp >= cutoff);
+    this.prints.set(tokenId, list);
+    if (list.length === 0) return null;
+    let buy = 0;
+    let sell = 0;
+    let notional = 0;
+    let low = Infinity;
+    let high = -Infinity;
+    let last = list[0];
+    for (const p of list) {
+      if (p.side === "BUY") buy += p.size;
+      else sell += p.size;
+      notional += p.size * p.price;
+      low = Math.min(low, p.price);
+      high = Math.max(high, p.price);
+      if (p.timestamp >= last.timestamp) last = p;
+    }
+    return {
+      window_seconds: this.windowSeconds,
+      trade_count: list.length,
+      volume: buy + sell,
+      notional,
+      imbalance: (buy - sell) / (buy + sell),
+      last_price: last.price,
+      low,
+      high,
+    };
+  }
+
+  /** Forget dedup keys once they can no longer reappear in a poll */
+  prune(): void {
+    if (this.seen.size > 10_000) this.seen.clear();
+  }
+}
@@ -1,5 +1,7 @@
 // Gamma API / CLOB types aligned with Rust models
 
+import type { PriceHistory } from "./history.js";
+
 export interface Market {
   conditionId: string;
   id?: string;
@@ -8,6 +10,8 @@ export interface Market {
   resolutionSource?: string;
   endDateISO?: string;
   endDateIso?: string;
+  /** Full end timestamp (Gamma's `endDateIso` may be date-only) */
+  endDate?: string;
   active: boolean;
   closed: boolean;
   tokens?: Token[];
@@ -20,6 +24,7 @@ export interface Token {
   token_id?: string;
   outcome: string;
   price?: string;
+  winner?: boolean;
 }
 
 export interface MarketDetails {
@@ -37,10 +42,28 @@ export interface OrderBook {
   asks: OrderBookEntry[];
 }
 
+/** Rolling trade-print statistics for one token */
+export interface TradeStats {
+  window_seconds: number;
+  trade_count: number;
+  volume: number;
+  notional: number;
+  /** (taker buy - taker sell) / volume, in [-1, 1] */
+  imbalance: number;
+  last_price: number;
+  low: number;
+  high: number;
+}
+
 export interface TokenPrice {
   token_id: string;
   bid: number | null;
   ask: number | null;
+  trade_stats?: TradeStats | null;
+  /** (bid size - ask size) / (bid size + ask size) over the top book levels, in [-1, 1] */
+  book_imbalance?: number | null;
+  /** Set when the monitor discarded this tick's quote as bad data (bid/ask are then null) */
+  quality_flag?: "crossed" | "out_of_range" | "jump" | null;
 }
 
 export type TokenType =
@@ -76,12 +99,33 @@ export interface BuyOpportunity {
   time_remaining_seconds: number;
   time_elapsed_seconds: number;
   use_market_order: boolean;
+  /** Wall-clock ms when the strategy decided to trade (latency instrumentation) */
+  decision_ms?: number;
+  /** Machine-readable decision that produced the order (period_start_limit, latency_arb, ...) for attribution */
+  reason?: string;
+  /** Free-text context logged and stored with the order */
+  note?: string;
+  /** Token ask - bid when the opportunity was built; feeds the fill-probability model */
+  spread?: number | null;
+}
+
+/** First Up/Down quotes seen in the period, and the underlying's period open */
+export interface PeriodOpen {
+  up_bid: number | null;
+  up_ask: number | null;
+  down_bid: number | null;
+  down_ask: number | null;
+  /** Spot open from the spot feed; null without a feed or before its first print in the period */
+  spot: number | null;
+  /** Seconds into the period at which the Up/Down quotes were captured */
+  observed_elapsed_seconds: number;
 }
 
 export interface MarketData {
   condition_id: string;
   up_token: TokenPrice | null;
   down_token: TokenPrice | null;
+  period_open?: PeriodOpen | null;
 }
 
 export interface MarketSnapshot {
@@ -91,4 +135,55 @@ export interface MarketSnapshot {
   xrp_market: MarketData;
   time_remaining_seconds: number;
   period_timestamp: number;
+  /** Recent bid/ask per token (`price_history_minutes`) */
+  history?: PriceHistory | null;
+  /** Set on the first snapshot after an interval with no market data (unix ms, exclusive) */
+  gap?: { from_ms: number; to_ms: number } | null;
+}
+
+export type Asset = "BTC" | "ETH" | "SOL" | "XRP";
+
+export function assetForTokenType(t: TokenType): Asset {
+  if (t.startsWith("Btc")) return "BTC";
+  if (t.startsWith("Eth")) return "ETH";
+  if (t.startsWith("Solana")) return "SOL";
+  return "XRP";
+}
+
+/** Side named by an outcome label: exactly "Up"/"Down" up to case and whitespace, else null */
+export function outcomeSide(label: string | undefined): "up" | "down" | null {
+  const l = (label ?? "").trim().toLowerCase();
+  return l === "up" ? "up" : l === "down" ? "down" : null;
+}
+
+/** Up/Down token IDs of a market, matched by outcome label (token order is not meaningful) */
+export function upDownTokenIds(market: Market): { upId: string | null; downId: string | null } {
+  let upId: string | null = null;
+  let downId: string | null = null;
+  for (const t of market.tokens ?? []) {
+    const id = t.tokenId ?? t.token_id ?? null;
+    const side = outcomeSide(t.outcome);
+    if (side === "up") upId = id;
+    else if (side === "down") downId = id;
+  }
+  return { upId, downId };
+}
+
+/** The other outcome of the same market (Up <-> Down) */
+export function oppositeTokenType(t: TokenType): TokenType {
+  return (t.endsWith("Up") ? t.replace(/Up$/, "Down") : t.replace(/Down$/, "Up")) as TokenType;
+}
+
+/** Market data in a snapshot that a token type belongs to */
+export function marketForTokenType(snap: MarketSnapshot, t: TokenType): MarketData {
+  if (t.startsWith("Btc")) return snap.btc_market;
+  if (t.startsWith("Eth")) return snap.eth_market;
+  if (t.startsWith("Solana")) return snap.solana_market;
+  return snap.xrp_market;
+}
+
+/** Current bid/ask of a token type in a snapshot */
+export function tokenPriceForType(snap: MarketSnapshot, t: TokenType): TokenPrice | null {
+  const market = marketForTokenType(snap, t);
+  return t.endsWith("Up") ? market.up_token : market.down_token;
 }
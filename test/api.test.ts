@@ -0,0 +1,60 @@
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { readFileSync } from "fs";
+import { gammaTokens, parseStringList } from "../src/api.js";
+import { upDownTokenIds } from "../src/types.js";
+
+/** Gamma market payloads as they have been seen in the wild, by shape */
+const FIXTURES = JSON.parse(readFileSync(new URL("./fixtures/gamma-markets.json", import.meta.url), "utf-8")) as Record<
+  string,
+  Record<string, unknown>
+>;
+
+function upDown(name: string): { upId: string | null; downId: string | null } {
+  return upDownTokenIds({ conditionId: "0x1", slug: String(FIXTURES[name].slug), question: "", active: true, closed: false, tokens: gammaTokens(FIXTURES[name]) });
+}
+
+test("parseStringList reads arrays, JSON strings and comma-separated strings", () => {
+  assert.deepEqual(parseStringList(["Up", " Down "]), ["Up", "Down"]);
+  assert.deepEqual(parseStringList([1111, 2222]), ["1111", "2222"]);
+  assert.deepEqual(parseStringList('["Up", "Down"]'), ["Up", "Down"]);
+  assert.deepEqual(parseStringList("Up, Down"), ["Up", "Down"]);
+  assert.deepEqual(parseStringList("'1111', \"2222\""), ["1111", "2222"]);
+  assert.deepEqual(parseStringList(""), []);
+  assert.deepEqual(parseStringList(null), []);
+  assert.deepEqual(parseStringList(42), []);
+});
+
+test("array outcomes", () => {
+  assert.deepEqual(upDown("array_outcomes"), { upId: "1111", downId: "2222" });
+});
+
+test("JSON-string outcomes", () => {
+  assert.deepEqual(upDown("json_string_outcomes"), { upId: "1111", downId: "2222" });
+});
+
+test("comma-separated outcomes", () => {
+  assert.deepEqual(upDown("comma_separated_outcomes"), { upId: "1111", downId: "2222" });
+});
+
+test("Down listed before Up keeps each id on its own outcome", () => {
+  assert.deepEqual(upDown("reversed_order"), { upId: "1111", downId: "2222" });
+});
+
+test("a tokens[] payload wins over outcomes/clobTokenIds and drops tokens without an id", () => {
+  const tokens = gammaTokens(FIXTURES.tokens_array);
+  assert.deepEqual(tokens, [
+    { token_id: "2222", outcome: "Down" },
+    { token_id: "1111", outcome: "Up" },
+  ]);
+  assert.deepEqual(upDown("tokens_array"), { upId: "1111", downId: "2222" });
+});
+
+test("outcomes and ids of different lengths yield no tokens", () => {
+  assert.deepEqual(gammaTokens(FIXTURES.mismatched_lengths), []);
+  assert.deepEqual(upDown("mismatched_lengths"), { upId: null, downId: null });
+});
+
+test("clob_token_ids is read when clobTokenIds is absent", () => {
+  assert.deepEqual(upDown("snake_case_ids"), { upId: "1111", downId: "2222" });
+});
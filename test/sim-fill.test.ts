@@ -0,0 +1,67 @@
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { SimFillRule } from "../src/sim-fill.js";
+
+const EPSILON = 0.0001;
+const LIMIT = 0.45;
+const fill = new SimFillRule(EPSILON, "fill");
+const tradeThrough = new SimFillRule(EPSILON, "trade_through");
+
+test("no touch never fills", () => {
+  for (const rule of [fill, tradeThrough]) {
+    assert.equal(rule.crosses("BUY", null, LIMIT), false);
+    assert.equal(rule.crosses("SELL", null, LIMIT), false);
+  }
+});
+
+test("touch at the limit is settled by the tie-break", () => {
+  for (const side of ["BUY", "SELL"] as const) {
+    assert.equal(fill.crosses(side, LIMIT, LIMIT), true, side);
+    assert.equal(tradeThrough.crosses(side, LIMIT, LIMIT), false, side);
+  }
+});
+
+test("touch within epsilon of the limit, either side, is a tie", () => {
+  for (const side of ["BUY", "SELL"] as const) {
+    for (const touch of [LIMIT + EPSILON / 2, LIMIT - EPSILON / 2, LIMIT + EPSILON, LIMIT - EPSILON]) {
+      assert.equal(fill.crosses(side, touch, LIMIT), true, `${side} @ ${touch}`);
+      assert.equal(tradeThrough.crosses(side, touch, LIMIT), false, `${side} @ ${touch}`);
+    }
+  }
+});
+
+test("touch just outside epsilon fills only when it is through the limit", () => {
+  const through = 0.00011;
+  for (const rule of [fill, tradeThrough]) {
+    assert.equal(rule.crosses("BUY", LIMIT - through, LIMIT), true, "ask below a buy limit");
+    assert.equal(rule.crosses("BUY", LIMIT + through, LIMIT), false, "ask above a buy limit");
+    assert.equal(rule.crosses("SELL", LIMIT + through, LIMIT), true, "bid above a sell limit");
+    assert.equal(rule.crosses("SELL", LIMIT - through, LIMIT), false, "bid below a sell limit");
+  }
+});
+
+test("decimal noise does not keep an order at its limit from filling", () => {
+  // 0.1 + 0.35 is 0.44999999999999996: the order that was READY at $0.45 but never filled.
+  const noisy = 0.1 + 0.35;
+  assert.notEqual(noisy, LIMIT);
+  assert.equal(fill.crosses("BUY", LIMIT, noisy), true, "ask 0.45 vs buy limit 0.1+0.35");
+  assert.equal(fill.crosses("BUY", noisy, LIMIT), true, "ask 0.1+0.35 vs buy limit 0.45");
+  assert.equal(fill.crosses("SELL", LIMIT, noisy), true, "bid 0.45 vs sell limit 0.1+0.35");
+  assert.equal(fill.crosses("SELL", noisy, LIMIT), true, "bid 0.1+0.35 vs sell limit 0.45");
+  // An averaged cost lands on the same noise.
+  const averaged = (0.44 * 3 + 0.46 * 3) / 6;
+  assert.equal(fill.crosses("SELL", LIMIT, averaged), true, "bid 0.45 vs sell at averaged cost");
+  // Trading through still means through, not noise.
+  assert.equal(tradeThrough.crosses("BUY", noisy, LIMIT), false);
+  assert.equal(tradeThrough.crosses("BUY", LIMIT, noisy), false);
+});
+
+test("with a zero epsilon the comparison is exact and the noisy limit misses", () => {
+  const exact = new SimFillRule(0, "fill");
+  assert.equal(exact.crosses("BUY", LIMIT, LIMIT), true);
+  assert.equal(exact.crosses("BUY", LIMIT, 0.1 + 0.35), false);
+});
+
+test("a negative epsilon is treated as zero", () => {
+  assert.equal(new SimFillRule(-1, "fill").epsilon, 0);
+});